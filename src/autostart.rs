@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{DesktopFile, DesktopFileError};
+use crate::trash::{self, Trashed};
+use crate::{logging, xdg};
+
+pub const ENABLED_KEY: &str = "X-GNOME-Autostart-enabled";
+pub const DELAY_KEY: &str = "X-GNOME-Autostart-Delay";
+pub const PHASE_KEY: &str = "X-GNOME-Autostart-Phase";
+
+/// Values GNOME Session accepts for `X-GNOME-Autostart-Phase`, in start order.
+pub const PHASES: &[&str] = &[
+    "Initialization",
+    "WindowManager",
+    "Panel",
+    "Desktop",
+    "Applications",
+];
+
+/// A startup entry after applying the user/system overlay: a file in the user
+/// autostart directory replaces a system one with the same ID.
+#[derive(Debug, Clone)]
+pub struct AutostartEntry {
+    pub id: String,
+    pub file: DesktopFile,
+    pub user_path: Option<PathBuf>,
+    pub system_path: Option<PathBuf>,
+}
+
+impl AutostartEntry {
+    pub fn is_enabled(&self) -> bool {
+        self.file.desktop_entry.hidden != Some(true)
+            && self.file.desktop_entry.extra_key(ENABLED_KEY) != Some("false")
+    }
+
+    pub fn origin(&self) -> &'static str {
+        match (&self.user_path, &self.system_path) {
+            (Some(_), Some(_)) => "User override",
+            (Some(_), None) => "User",
+            _ => "System",
+        }
+    }
+
+    pub fn delay(&self) -> u32 {
+        self.file
+            .desktop_entry
+            .extra_key(DELAY_KEY)
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(0)
+    }
+
+    pub fn phase(&self) -> Option<&str> {
+        self.file.desktop_entry.extra_key(PHASE_KEY)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/autostart`
+pub fn user_dir() -> Option<PathBuf> {
+    xdg::config_home().map(|dir| dir.join("autostart"))
+}
+
+/// `autostart` under each of `$XDG_CONFIG_DIRS`, most important first.
+pub fn system_dirs() -> Vec<PathBuf> {
+    xdg::config_dirs()
+        .into_iter()
+        .map(|dir| dir.join("autostart"))
+        .collect()
+}
+
+/// All startup entries, sorted by ID.
+pub fn list_entries() -> Vec<AutostartEntry> {
+    let mut system: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for dir in system_dirs() {
+        for path in desktop_files_in(&dir) {
+            if let Some(id) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
+                system.entry(id).or_insert(path);
+            }
+        }
+    }
+
+    let mut user: BTreeMap<String, PathBuf> = BTreeMap::new();
+    if let Some(dir) = user_dir() {
+        for path in desktop_files_in(&dir) {
+            if let Some(id) = path.file_name().map(|n| n.to_string_lossy().to_string()) {
+                user.insert(id, path);
+            }
+        }
+    }
+
+    let mut ids: Vec<String> = system.keys().chain(user.keys()).cloned().collect();
+    ids.sort();
+    ids.dedup();
+
+    ids.into_iter()
+        .filter_map(|id| {
+            let user_path = user.get(&id).cloned();
+            let system_path = system.get(&id).cloned();
+            let effective = user_path.as_ref().or(system_path.as_ref())?;
+            match DesktopFile::from_file(effective) {
+                Ok(file) => Some(AutostartEntry {
+                    id,
+                    file,
+                    user_path,
+                    system_path,
+                }),
+                Err(e) => {
+                    logging::warn(
+                        "autostart",
+                        format!("Could not load {}: {e}", effective.display()),
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Enables or disables an entry. Disabling sets both `Hidden=true` and
+/// `X-GNOME-Autostart-enabled=false` so every session manager honours it.
+pub fn set_enabled(
+    entry: &AutostartEntry,
+    enabled: bool,
+) -> Result<AutostartEntry, DesktopFileError> {
+    update(entry, |file| {
+        if enabled {
+            file.desktop_entry.hidden = None;
+            file.desktop_entry.set_extra_key(ENABLED_KEY, Some("true"));
+        } else {
+            file.desktop_entry.hidden = Some(true);
+            file.desktop_entry.set_extra_key(ENABLED_KEY, Some("false"));
+        }
+    })
+}
+
+pub fn set_delay(entry: &AutostartEntry, seconds: u32) -> Result<AutostartEntry, DesktopFileError> {
+    update(entry, |file| {
+        let value = seconds.to_string();
+        file.desktop_entry
+            .set_extra_key(DELAY_KEY, (seconds > 0).then_some(value.as_str()));
+    })
+}
+
+pub fn set_phase(
+    entry: &AutostartEntry,
+    phase: Option<&str>,
+) -> Result<AutostartEntry, DesktopFileError> {
+    update(entry, |file| {
+        file.desktop_entry.set_extra_key(PHASE_KEY, phase)
+    })
+}
+
+/// Copies an application entry into the user autostart directory.
+pub fn add(file: &DesktopFile, id: &str) -> Result<PathBuf, DesktopFileError> {
+    let dir = user_dir().ok_or_else(|| io::Error::other("No home directory"))?;
+    let path = dir.join(id);
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{id} is already in autostart"),
+        )
+        .into());
+    }
+
+    let mut file = file.clone();
+    file.desktop_entry.hidden = None;
+    file.desktop_entry.no_display = None;
+    fs::create_dir_all(&dir)?;
+    file.save(&path)?;
+    Ok(path)
+}
+
+/// Moves the user copy of an entry to the trash. A system entry it overrode
+/// becomes effective again. Returns what `trash::restore` needs to undo it.
+pub fn remove_user_copy(entry: &AutostartEntry) -> Result<Option<Trashed>, DesktopFileError> {
+    match entry.user_path {
+        Some(ref path) => trash::trash(path),
+        None => Ok(None),
+    }
+}
+
+/// Applies `change` to the entry and writes the result to the user directory,
+/// creating a user override for system entries.
+fn update<F: FnOnce(&mut DesktopFile)>(
+    entry: &AutostartEntry,
+    change: F,
+) -> Result<AutostartEntry, DesktopFileError> {
+    let user_path = match entry.user_path {
+        Some(ref path) => path.clone(),
+        None => user_dir()
+            .ok_or_else(|| io::Error::other("No home directory"))?
+            .join(&entry.id),
+    };
+    if let Some(dir) = user_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let mut file = entry.file.clone();
+    change(&mut file);
+    file.save(&user_path)?;
+
+    Ok(AutostartEntry {
+        id: entry.id.clone(),
+        file,
+        user_path: Some(user_path),
+        system_path: entry.system_path.clone(),
+    })
+}
+
+fn desktop_files_in(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "desktop"))
+                .collect()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,288 @@
+use glib::Propagation;
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::autostart::{self, AutostartEntry, PHASES};
+use crate::feedback::Feedback;
+use crate::trash::{self, Trashed};
+
+/// How long the delay must stay unchanged before it is saved.
+const DELAY_SAVE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Opens the window listing session startup entries.
+pub fn present(parent: &adw::ApplicationWindow) {
+    let window = adw::Window::new();
+    window.set_title(Some("Autostart"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(900, 600);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Autostart"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let refresh_button = gtk::Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.set_tooltip_text(Some("Reload Autostart Entries"));
+    header.pack_start(&refresh_button);
+
+    let description = gtk::Label::new(Some(
+        "Entries in your autostart folder override system entries with the same ID. \
+         Changes to system entries are saved as user overrides.",
+    ));
+    description.add_css_class("dim-label");
+    description.set_wrap(true);
+    description.set_halign(gtk::Align::Start);
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&description);
+    content.append(&list);
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&content));
+    scrolled.set_vexpand(true);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&scrolled);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    // The last removed user copy, which the toast can put back
+    let pending_undo: Rc<RefCell<Option<Trashed>>> = Rc::new(RefCell::new(None));
+    let actions = gio::SimpleActionGroup::new();
+    let undo_remove = gio::SimpleAction::new("undo-remove", None);
+    {
+        let list = list.clone();
+        let feedback = feedback.clone();
+        let pending_undo = pending_undo.clone();
+        undo_remove.connect_activate(move |_, _| {
+            let Some(item) = pending_undo.borrow_mut().take() else {
+                return;
+            };
+            match trash::restore(&item) {
+                Ok(()) => {
+                    feedback.success("autostart", format!("Restored {}", item.original.display()))
+                }
+                Err(e) => feedback.error("autostart", "Could not undo", &e),
+            }
+            populate(&list, &feedback, &pending_undo);
+        });
+    }
+    actions.add_action(&undo_remove);
+    window.insert_action_group("autostart", Some(&actions));
+
+    populate(&list, &feedback, &pending_undo);
+
+    {
+        let list = list.clone();
+        refresh_button.connect_clicked(move |_| populate(&list, &feedback, &pending_undo));
+    }
+
+    window.present();
+}
+
+fn populate(list: &gtk::ListBox, feedback: &Feedback, pending_undo: &Rc<RefCell<Option<Trashed>>>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let entries = autostart::list_entries();
+    if entries.is_empty() {
+        let row = adw::ActionRow::new();
+        row.set_title("No autostart entries");
+        list.append(&row);
+        return;
+    }
+
+    for entry in entries {
+        list.append(&create_row(list, entry, feedback, pending_undo));
+    }
+}
+
+fn create_row(
+    list: &gtk::ListBox,
+    entry: AutostartEntry,
+    feedback: &Feedback,
+    pending_undo: &Rc<RefCell<Option<Trashed>>>,
+) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(&glib::markup_escape_text(&entry.file.desktop_entry.name));
+    row.set_subtitle(&glib::markup_escape_text(&format!(
+        "{} · {}",
+        entry.id,
+        entry.origin()
+    )));
+
+    let delay_spin = gtk::SpinButton::with_range(0.0, 600.0, 1.0);
+    delay_spin.set_value(entry.delay() as f64);
+    delay_spin.set_tooltip_text(Some("Delay in seconds (X-GNOME-Autostart-Delay)"));
+    delay_spin.set_valign(gtk::Align::Center);
+    row.add_suffix(&delay_spin);
+
+    let mut phase_names = vec!["Default Phase"];
+    phase_names.extend_from_slice(PHASES);
+    let phase_dropdown = gtk::DropDown::from_strings(&phase_names);
+    phase_dropdown.set_tooltip_text(Some("Session phase (X-GNOME-Autostart-Phase)"));
+    phase_dropdown.set_valign(gtk::Align::Center);
+    let selected = entry
+        .phase()
+        .and_then(|phase| PHASES.iter().position(|p| *p == phase))
+        .map(|i| i as u32 + 1)
+        .unwrap_or(0);
+    phase_dropdown.set_selected(selected);
+    row.add_suffix(&phase_dropdown);
+
+    let enabled_switch = gtk::Switch::new();
+    enabled_switch.set_active(entry.is_enabled());
+    enabled_switch.set_valign(gtk::Align::Center);
+    row.add_suffix(&enabled_switch);
+
+    // The first change of a system entry creates the user copy, so the
+    // button follows the entry as it is updated
+    let remove_button = gtk::Button::new();
+    remove_button.add_css_class("flat");
+    remove_button.set_valign(gtk::Align::Center);
+    update_remove_button(&remove_button, &entry);
+    row.add_suffix(&remove_button);
+
+    let entry = Rc::new(RefCell::new(entry));
+
+    {
+        let entry = entry.clone();
+        let list = list.clone();
+        let feedback = feedback.clone();
+        let pending_undo = pending_undo.clone();
+        remove_button.connect_clicked(move |_| {
+            let entry = entry.borrow().clone();
+            match autostart::remove_user_copy(&entry) {
+                Ok(undo) => {
+                    let message = format!("Moved {} to the trash", entry.id);
+                    if undo.is_some() {
+                        feedback.success_with_action(
+                            "autostart",
+                            message,
+                            "Undo",
+                            "autostart.undo-remove",
+                        );
+                    } else {
+                        feedback.success("autostart", message);
+                    }
+                    *pending_undo.borrow_mut() = undo;
+                }
+                Err(e) => {
+                    feedback.error("autostart", &format!("Could not remove {}", entry.id), &e)
+                }
+            }
+            populate(&list, &feedback, &pending_undo);
+        });
+    }
+
+    {
+        let entry = entry.clone();
+        let feedback = feedback.clone();
+        let remove_button = remove_button.clone();
+        enabled_switch.connect_state_set(move |_, state| {
+            let result = autostart::set_enabled(&entry.borrow(), state);
+            match result {
+                Ok(updated) => {
+                    update_remove_button(&remove_button, &updated);
+                    *entry.borrow_mut() = updated;
+                    Propagation::Proceed
+                }
+                Err(e) => {
+                    let id = entry.borrow().id.clone();
+                    feedback.error("autostart", &format!("Could not update {id}"), &e);
+                    // Leave the switch showing the state that is still in effect
+                    Propagation::Stop
+                }
+            }
+        });
+    }
+
+    {
+        let entry = entry.clone();
+        let feedback = feedback.clone();
+        let remove_button = remove_button.clone();
+        // Holding the arrows changes the value many times a second; only the
+        // value it settles on is written
+        let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+        delay_spin.connect_value_changed(move |spin| {
+            if let Some(source) = pending.borrow_mut().take() {
+                source.remove();
+            }
+            let entry = entry.clone();
+            let feedback = feedback.clone();
+            let remove_button = remove_button.clone();
+            let pending_clone = pending.clone();
+            let delay = spin.value() as u32;
+            let source = glib::timeout_add_local_once(DELAY_SAVE_TIMEOUT, move || {
+                pending_clone.borrow_mut().take();
+                let result = autostart::set_delay(&entry.borrow(), delay);
+                match result {
+                    Ok(updated) => {
+                        update_remove_button(&remove_button, &updated);
+                        *entry.borrow_mut() = updated;
+                    }
+                    Err(e) => {
+                        let id = entry.borrow().id.clone();
+                        feedback.error("autostart", &format!("Could not update {id}"), &e);
+                    }
+                }
+            });
+            *pending.borrow_mut() = Some(source);
+        });
+    }
+
+    {
+        let entry = entry.clone();
+        let feedback = feedback.clone();
+        let remove_button = remove_button.clone();
+        phase_dropdown.connect_selected_notify(move |dropdown| {
+            let phase = match dropdown.selected() {
+                0 => None,
+                i => PHASES.get(i as usize - 1).copied(),
+            };
+            let result = autostart::set_phase(&entry.borrow(), phase);
+            match result {
+                Ok(updated) => {
+                    update_remove_button(&remove_button, &updated);
+                    *entry.borrow_mut() = updated;
+                }
+                Err(e) => {
+                    let id = entry.borrow().id.clone();
+                    feedback.error("autostart", &format!("Could not update {id}"), &e);
+                }
+            }
+        });
+    }
+
+    row
+}
+
+/// Offers removing a user entry, or reverting a user copy of a system entry.
+/// Hidden while the entry only exists in a system directory.
+fn update_remove_button(button: &gtk::Button, entry: &AutostartEntry) {
+    let (icon, tooltip) = if entry.system_path.is_some() {
+        ("edit-undo-symbolic", "Revert to System Entry")
+    } else {
+        ("user-trash-symbolic", "Remove from Autostart")
+    };
+    button.set_icon_name(icon);
+    button.set_tooltip_text(Some(tooltip));
+    button.set_visible(entry.user_path.is_some());
+}
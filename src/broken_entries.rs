@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{get_desktop_file_paths, DesktopFile, DesktopFileError};
+use crate::{desktop_id, exec, trash, xdg};
+
+/// Why the program of an application entry cannot be started: a `TryExec`
+/// or `Exec` program that is missing or not executable, or an `Exec` value
+/// that cannot be parsed. Empty for working entries and other types.
+pub fn program_problems(file: &DesktopFile) -> Vec<String> {
+    let entry = &file.desktop_entry;
+    let mut problems = Vec::new();
+    if entry.entry_type != "Application" {
+        return problems;
+    }
+
+    if let Some(try_exec) = entry
+        .try_exec
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        if let Err(e) = exec::resolve(try_exec) {
+            problems.push(format!("TryExec: {e}"));
+        }
+    }
+
+    // Entries without Exec are D-Bus activated or already reported as invalid
+    if let Some(value) = entry
+        .exec
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+    {
+        if let Err(e) = exec::split_args(value) {
+            problems.push(format!("Exec: {e}"));
+        } else if let Some(program) = exec::program(value) {
+            if let Err(e) = exec::resolve(&program) {
+                problems.push(format!("Exec: {e}"));
+            }
+        }
+    }
+
+    problems
+}
+
+/// A user launcher whose program is gone, typically left behind by an
+/// application that was uninstalled.
+#[derive(Debug, Clone)]
+pub struct OrphanedLauncher {
+    pub path: PathBuf,
+    pub id: String,
+    pub name: String,
+    pub problems: Vec<String>,
+    /// Whether the launcher overrides a system entry with the same ID, which
+    /// reappears when the launcher is deleted.
+    pub overrides_system: bool,
+    pub no_display: bool,
+}
+
+/// Launchers in `~/.local/share/applications` with a missing program.
+/// Entries with `Hidden=true` are skipped, as they only hide a system entry.
+pub fn orphaned_launchers() -> Vec<OrphanedLauncher> {
+    let Some(user_apps) = xdg::data_home().map(|d| d.join("applications")) else {
+        return Vec::new();
+    };
+
+    let mut launchers: Vec<OrphanedLauncher> = get_desktop_file_paths()
+        .into_iter()
+        .map(PathBuf::from)
+        .filter(|path| path.parent() == Some(user_apps.as_path()))
+        .filter_map(|path| {
+            let file = DesktopFile::from_file(&path).ok()?;
+            if file.desktop_entry.hidden == Some(true) {
+                return None;
+            }
+            let problems = program_problems(&file);
+            if problems.is_empty() {
+                return None;
+            }
+            let id = desktop_id::from_path(&path)?;
+            let overrides_system = desktop_id::find_existing(&id)
+                .iter()
+                .any(|p| Path::new(p).parent() != Some(user_apps.as_path()));
+            Some(OrphanedLauncher {
+                id,
+                name: file.desktop_entry.name.clone(),
+                problems,
+                overrides_system,
+                no_display: file.desktop_entry.no_display == Some(true),
+                path,
+            })
+        })
+        .collect();
+    launchers.sort_by_key(|l| l.name.to_lowercase());
+    launchers
+}
+
+/// Keeps a launcher out of menus by setting `NoDisplay=true`.
+pub fn hide(path: &Path) -> Result<(), DesktopFileError> {
+    let mut file = DesktopFile::from_file(path)?;
+    file.desktop_entry.no_display = Some(true);
+    file.save(path)
+}
+
+/// Moves a launcher to the trash.
+pub fn delete(path: &Path) -> Result<(), DesktopFileError> {
+    trash::trash(path)?;
+    Ok(())
+}
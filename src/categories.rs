@@ -0,0 +1,570 @@
+/// Registered categories of the Desktop Menu Specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CategoryKind {
+    Main,
+    Additional,
+    /// Only meaningful for a specific desktop, so `OnlyShowIn` is required.
+    Reserved,
+}
+
+#[derive(Debug)]
+pub struct Category {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: CategoryKind,
+    /// Alternatives of categories that must be present together with this
+    /// one, e.g. `[["Graphics", "2DGraphics"]]` or `[["Graphics"], ["Office"]]`.
+    pub related: &'static [&'static [&'static str]],
+}
+
+const fn main(
+    name: &'static str,
+    description: &'static str,
+    related: &'static [&'static [&'static str]],
+) -> Category {
+    Category {
+        name,
+        description,
+        kind: CategoryKind::Main,
+        related,
+    }
+}
+
+const fn additional(
+    name: &'static str,
+    description: &'static str,
+    related: &'static [&'static [&'static str]],
+) -> Category {
+    Category {
+        name,
+        description,
+        kind: CategoryKind::Additional,
+        related,
+    }
+}
+
+const fn reserved(name: &'static str, description: &'static str) -> Category {
+    Category {
+        name,
+        description,
+        kind: CategoryKind::Reserved,
+        related: &[],
+    }
+}
+
+const EDUCATION_OR_SCIENCE: &[&[&str]] = &[&["Education"], &["Science"]];
+const MULTIMEDIA: &[&[&str]] = &[
+    &["AudioVideo", "Audio"],
+    &["AudioVideo", "Video"],
+    &["AudioVideo"],
+];
+
+pub const CATEGORIES: &[Category] = &[
+    main(
+        "AudioVideo",
+        "Application for presenting, creating, or processing multimedia (audio/video)",
+        &[],
+    ),
+    main("Audio", "An audio application", &[&["AudioVideo"]]),
+    main("Video", "A video application", &[&["AudioVideo"]]),
+    main("Development", "An application for development", &[]),
+    main("Education", "Educational software", &[]),
+    main("Game", "A game", &[]),
+    main(
+        "Graphics",
+        "Application for viewing, creating, or processing graphics",
+        &[],
+    ),
+    main("Network", "Network application such as a web browser", &[]),
+    main("Office", "An office type application", &[]),
+    main("Science", "Scientific software", &[]),
+    main("Settings", "Settings applications", &[]),
+    main(
+        "System",
+        "System application, \"System Tools\" such as a log viewer or network monitor",
+        &[],
+    ),
+    main("Utility", "Small utility application, \"Accessories\"", &[]),
+    additional(
+        "Building",
+        "A tool to build applications",
+        &[&["Development"]],
+    ),
+    additional(
+        "Debugger",
+        "A tool to debug applications",
+        &[&["Development"]],
+    ),
+    additional("IDE", "IDE application", &[&["Development"]]),
+    additional(
+        "GUIDesigner",
+        "A GUI designer application",
+        &[&["Development"]],
+    ),
+    additional("Profiling", "A profiling tool", &[&["Development"]]),
+    additional(
+        "RevisionControl",
+        "Applications like cvs or subversion",
+        &[&["Development"]],
+    ),
+    additional("Translation", "A translation tool", &[&["Development"]]),
+    additional("Calendar", "Calendar application", &[&["Office"]]),
+    additional("ContactManagement", "E.g. an address book", &[&["Office"]]),
+    additional(
+        "Database",
+        "Application to manage a database",
+        &[&["Office"], &["Development"], &["AudioVideo"]],
+    ),
+    additional("Dictionary", "A dictionary", &[&["Office", "TextTools"]]),
+    additional("Chart", "Chart application", &[&["Office"]]),
+    additional("Email", "Email application", &[&["Office", "Network"]]),
+    additional(
+        "Finance",
+        "Application to manage your finance",
+        &[&["Office"]],
+    ),
+    additional("FlowChart", "A flowchart application", &[&["Office"]]),
+    additional("PDA", "Tool to manage your PDA", &[&["Office"]]),
+    additional(
+        "ProjectManagement",
+        "Project management application",
+        &[&["Office", "Development"]],
+    ),
+    additional("Presentation", "Presentation software", &[&["Office"]]),
+    additional("Spreadsheet", "A spreadsheet", &[&["Office"]]),
+    additional("WordProcessor", "A word processor", &[&["Office"]]),
+    additional(
+        "2DGraphics",
+        "2D based graphical application",
+        &[&["Graphics"]],
+    ),
+    additional(
+        "VectorGraphics",
+        "Application for viewing, creating, or processing vector graphics",
+        &[&["Graphics", "2DGraphics"]],
+    ),
+    additional(
+        "RasterGraphics",
+        "Application for viewing, creating, or processing raster (bitmap) graphics",
+        &[&["Graphics", "2DGraphics"]],
+    ),
+    additional(
+        "3DGraphics",
+        "Application for viewing, creating, or processing 3-D graphics",
+        &[&["Graphics"]],
+    ),
+    additional("Scanning", "Tool to scan a file/text", &[&["Graphics"]]),
+    additional(
+        "OCR",
+        "Optical character recognition application",
+        &[&["Graphics", "Scanning"]],
+    ),
+    additional(
+        "Photography",
+        "Camera tools, etc.",
+        &[&["Graphics"], &["Office"]],
+    ),
+    additional(
+        "Publishing",
+        "Desktop Publishing applications and Color Management tools",
+        &[&["Graphics"], &["Office"]],
+    ),
+    additional(
+        "Viewer",
+        "Tool to view e.g. a graphic or pdf file",
+        &[&["Graphics"], &["Office"]],
+    ),
+    additional("TextTools", "A text tool utility", &[&["Utility"]]),
+    additional(
+        "DesktopSettings",
+        "Configuration tool for the GUI",
+        &[&["Settings"]],
+    ),
+    additional(
+        "HardwareSettings",
+        "A tool to manage hardware components, like sound cards, video cards or printers",
+        &[&["Settings"]],
+    ),
+    additional(
+        "Printing",
+        "A tool to manage printers",
+        &[&["HardwareSettings", "Settings"]],
+    ),
+    additional(
+        "PackageManager",
+        "A package manager application",
+        &[&["Settings"]],
+    ),
+    additional("Dialup", "A dial-up program", &[&["Network"]]),
+    additional(
+        "InstantMessaging",
+        "An instant messaging client",
+        &[&["Network"]],
+    ),
+    additional("Chat", "A chat client", &[&["Network"]]),
+    additional("IRCClient", "An IRC client", &[&["Network"]]),
+    additional(
+        "Feed",
+        "RSS, podcast and other subscription based contents",
+        &[&["Network"]],
+    ),
+    additional(
+        "FileTransfer",
+        "Tools like FTP or P2P programs",
+        &[&["Network"]],
+    ),
+    additional(
+        "HamRadio",
+        "HAM radio software",
+        &[&["Network"], &["Audio"]],
+    ),
+    additional("News", "A news reader or a news ticker", &[&["Network"]]),
+    additional("P2P", "A P2P program", &[&["Network"]]),
+    additional(
+        "RemoteAccess",
+        "A tool to remotely manage your PC",
+        &[&["Network"]],
+    ),
+    additional("Telephony", "Telephony via PC", &[&["Network"]]),
+    additional(
+        "TelephonyTools",
+        "Telephony tools, to dial a number, manage PBX, ...",
+        &[&["Utility"]],
+    ),
+    additional(
+        "VideoConference",
+        "Video Conference software",
+        &[&["Network"]],
+    ),
+    additional("WebBrowser", "A web browser", &[&["Network"]]),
+    additional(
+        "WebDevelopment",
+        "A tool for web developers",
+        &[&["Network"], &["Development"]],
+    ),
+    additional(
+        "Midi",
+        "An app related to MIDI",
+        &[&["AudioVideo", "Audio"]],
+    ),
+    additional("Mixer", "Just a mixer", &[&["AudioVideo", "Audio"]]),
+    additional("Sequencer", "A sequencer", &[&["AudioVideo", "Audio"]]),
+    additional("Tuner", "A tuner", &[&["AudioVideo", "Audio"]]),
+    additional("TV", "A TV application", &[&["AudioVideo", "Video"]]),
+    additional(
+        "AudioVideoEditing",
+        "Application to edit audio/video files",
+        MULTIMEDIA,
+    ),
+    additional(
+        "Player",
+        "Application to play audio/video files",
+        MULTIMEDIA,
+    ),
+    additional(
+        "Recorder",
+        "Application to record audio/video files",
+        MULTIMEDIA,
+    ),
+    additional(
+        "DiscBurning",
+        "Application to burn a disc",
+        &[&["AudioVideo"]],
+    ),
+    additional("ActionGame", "An action game", &[&["Game"]]),
+    additional("AdventureGame", "Adventure style game", &[&["Game"]]),
+    additional("ArcadeGame", "Arcade style game", &[&["Game"]]),
+    additional("BoardGame", "A board game", &[&["Game"]]),
+    additional("BlocksGame", "Falling blocks game", &[&["Game"]]),
+    additional("CardGame", "A card game", &[&["Game"]]),
+    additional("KidsGame", "A game for kids", &[&["Game"]]),
+    additional("LogicGame", "Logic games like puzzles, etc", &[&["Game"]]),
+    additional("RolePlaying", "A role playing game", &[&["Game"]]),
+    additional("Shooter", "A shooter game", &[&["Game"]]),
+    additional("Simulation", "A simulation game", &[&["Game"]]),
+    additional("SportsGame", "A sports game", &[&["Game"]]),
+    additional("StrategyGame", "A strategy game", &[&["Game"]]),
+    additional("Art", "Software to teach arts", EDUCATION_OR_SCIENCE),
+    additional(
+        "Construction",
+        "Construction application",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional(
+        "Music",
+        "Musical software",
+        &[&["AudioVideo"], &["Education"]],
+    ),
+    additional(
+        "Languages",
+        "Software to learn foreign languages",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional(
+        "ArtificialIntelligence",
+        "Artificial Intelligence software",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional("Astronomy", "Astronomy software", EDUCATION_OR_SCIENCE),
+    additional("Biology", "Biology software", EDUCATION_OR_SCIENCE),
+    additional("Chemistry", "Chemistry software", EDUCATION_OR_SCIENCE),
+    additional(
+        "ComputerScience",
+        "ComputerScience software",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional(
+        "DataVisualization",
+        "Data visualization software",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional("Economy", "Economy software", EDUCATION_OR_SCIENCE),
+    additional("Electricity", "Electricity software", EDUCATION_OR_SCIENCE),
+    additional("Geography", "Geography software", EDUCATION_OR_SCIENCE),
+    additional("Geology", "Geology software", EDUCATION_OR_SCIENCE),
+    additional(
+        "Geoscience",
+        "Geoscience software, GIS",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional("History", "History software", EDUCATION_OR_SCIENCE),
+    additional(
+        "Humanities",
+        "Software for philosophy, psychology and other humanities",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional(
+        "ImageProcessing",
+        "Image Processing software",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional("Literature", "Literature software", EDUCATION_OR_SCIENCE),
+    additional(
+        "Maps",
+        "Software for viewing maps, navigation, mapping, GPS",
+        &[&["Education"], &["Science"], &["Utility"]],
+    ),
+    additional("Math", "Math software", EDUCATION_OR_SCIENCE),
+    additional(
+        "NumericalAnalysis",
+        "Numerical analysis software",
+        &[&["Education", "Math"], &["Science", "Math"]],
+    ),
+    additional("MedicalSoftware", "Medical software", EDUCATION_OR_SCIENCE),
+    additional("Physics", "Physics software", EDUCATION_OR_SCIENCE),
+    additional("Robotics", "Robotics software", EDUCATION_OR_SCIENCE),
+    additional(
+        "Spirituality",
+        "Religious and spiritual software, theology",
+        &[&["Education"], &["Science"], &["Utility"]],
+    ),
+    additional("Sports", "Sports software", EDUCATION_OR_SCIENCE),
+    additional(
+        "ParallelComputing",
+        "Parallel computing software",
+        EDUCATION_OR_SCIENCE,
+    ),
+    additional("Amusement", "A simple amusement", &[]),
+    additional(
+        "Archiving",
+        "A tool to archive/backup data",
+        &[&["Utility"]],
+    ),
+    additional(
+        "Compression",
+        "A tool to manage compressed data/archives",
+        &[&["Utility"]],
+    ),
+    additional(
+        "Electronics",
+        "Electronics software, e.g. a circuit designer",
+        &[],
+    ),
+    additional(
+        "Emulator",
+        "Emulator of another platform, such as a DOS emulator",
+        &[&["System"], &["Game"]],
+    ),
+    additional(
+        "Engineering",
+        "Engineering software, e.g. CAD programs",
+        &[],
+    ),
+    additional(
+        "FileTools",
+        "A file tool utility",
+        &[&["Utility"], &["System"]],
+    ),
+    additional("FileManager", "A file manager", &[&["System", "FileTools"]]),
+    additional(
+        "TerminalEmulator",
+        "A terminal emulator application",
+        &[&["System"]],
+    ),
+    additional("Filesystem", "A file system tool", &[&["System"]]),
+    additional(
+        "Monitor",
+        "Monitor application/applet that monitors some resource or activity",
+        &[&["System"], &["Network"]],
+    ),
+    additional("Security", "A security tool", &[&["Settings"], &["System"]]),
+    additional(
+        "Accessibility",
+        "Accessibility",
+        &[&["Settings"], &["Utility"]],
+    ),
+    additional("Calculator", "A calculator", &[&["Utility"]]),
+    additional("Clock", "A clock application/applet", &[&["Utility"]]),
+    additional("TextEditor", "A text editor", &[&["Utility"]]),
+    additional("Documentation", "Help or documentation", &[]),
+    additional(
+        "Adult",
+        "Application handles adult or explicit material",
+        &[],
+    ),
+    additional(
+        "Core",
+        "Important application, core to correct operation of the computer",
+        &[],
+    ),
+    additional("KDE", "Application based on KDE libraries", &[&["Qt"]]),
+    additional("GNOME", "Application based on GNOME libraries", &[&["GTK"]]),
+    additional("XFCE", "Application based on XFCE libraries", &[&["GTK"]]),
+    additional("DDE", "Application based on DDE libraries", &[&["Qt"]]),
+    additional("GTK", "Application based on GTK+ libraries", &[]),
+    additional("Qt", "Application based on Qt libraries", &[]),
+    additional("Motif", "Application based on Motif libraries", &[]),
+    additional(
+        "Java",
+        "Application based on Java GUI libraries, such as AWT or Swing",
+        &[],
+    ),
+    additional(
+        "ConsoleOnly",
+        "Application that only works inside a terminal (text-based or command line application)",
+        &[],
+    ),
+    reserved(
+        "Screensaver",
+        "A screen saver (launching this desktop entry should activate the screen saver)",
+    ),
+    reserved(
+        "TrayIcon",
+        "An application that is primarily an icon for the \"system tray\" or \"notification area\"",
+    ),
+    reserved(
+        "Applet",
+        "An application that is primarily an applet or widget for a panel or desktop",
+    ),
+    reserved(
+        "Shell",
+        "A shell (an actual specific shell such as bash or tcsh, not a TerminalEmulator)",
+    ),
+];
+
+pub fn find(name: &str) -> Option<&'static Category> {
+    CATEGORIES.iter().find(|c| c.name == name)
+}
+
+/// Extension categories, which must start with `X-`.
+pub fn is_custom(name: &str) -> bool {
+    name.starts_with("X-") && name.len() > 2
+}
+
+/// Main categories that do not need another main category, in spec order.
+pub fn top_level() -> Vec<&'static Category> {
+    CATEGORIES
+        .iter()
+        .filter(|c| c.kind == CategoryKind::Main && c.related.is_empty())
+        .collect()
+}
+
+/// Top-level categories a category is nested under in a tree: the first
+/// top-level category of each alternative of related categories.
+fn tree_parents(category: &Category) -> Vec<&'static str> {
+    let top = top_level();
+    category
+        .related
+        .iter()
+        .filter_map(|alt| {
+            alt.iter()
+                .find(|name| top.iter().any(|t| t.name == **name))
+                .copied()
+        })
+        .collect()
+}
+
+/// Categories nested under the top-level category `parent` in a tree.
+pub fn children(parent: &str) -> Vec<&'static Category> {
+    CATEGORIES
+        .iter()
+        .filter(|c| tree_parents(c).contains(&parent))
+        .collect()
+}
+
+/// Non-reserved categories that fit under no top-level category.
+pub fn unparented() -> Vec<&'static Category> {
+    CATEGORIES
+        .iter()
+        .filter(|c| {
+            c.kind != CategoryKind::Reserved
+                && !(c.kind == CategoryKind::Main && c.related.is_empty())
+                && tree_parents(c).is_empty()
+        })
+        .collect()
+}
+
+pub fn reserved_categories() -> Vec<&'static Category> {
+    CATEGORIES
+        .iter()
+        .filter(|c| c.kind == CategoryKind::Reserved)
+        .collect()
+}
+
+/// Problems with a set of categories: unknown names, additional categories
+/// without their related categories, a missing main category and reserved
+/// categories used without `OnlyShowIn`.
+pub fn check(categories: &[String], has_only_show_in: bool) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if categories.is_empty() {
+        return warnings;
+    }
+
+    let present = |name: &str| categories.iter().any(|c| c == name);
+
+    for name in categories {
+        if is_custom(name) {
+            continue;
+        }
+        let Some(category) = find(name) else {
+            warnings.push(format!(
+                "Unknown category '{name}'; custom categories must start with X-"
+            ));
+            continue;
+        };
+        if !category.related.is_empty()
+            && !category
+                .related
+                .iter()
+                .any(|alt| alt.iter().all(|r| present(r)))
+        {
+            let options: Vec<String> = category.related.iter().map(|alt| alt.join(" + ")).collect();
+            warnings.push(format!(
+                "'{name}' should be used together with {}",
+                options.join(" or ")
+            ));
+        }
+        if category.kind == CategoryKind::Reserved && !has_only_show_in {
+            warnings.push(format!("'{name}' is reserved and requires Only Show In"));
+        }
+    }
+
+    if !categories
+        .iter()
+        .any(|c| find(c).is_some_and(|c| c.kind == CategoryKind::Main))
+    {
+        warnings.push("No main category; most menus will show the entry under Other".to_string());
+    }
+
+    warnings
+}
@@ -0,0 +1,216 @@
+use gtk4 as gtk;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::categories::{self, Category};
+
+const ACTIVE_COLUMN: u32 = 0;
+const NAME_COLUMN: u32 = 1;
+const DESCRIPTION_COLUMN: u32 = 2;
+const SELECTABLE_COLUMN: u32 = 3;
+
+type ToggledCallback = Rc<RefCell<Option<Box<dyn Fn(&str, bool)>>>>;
+
+/// Checkable tree of the registered categories, with additional categories
+/// nested under the main categories they belong to, plus an entry for `X-`
+/// extension categories.
+#[derive(Clone)]
+pub struct CategoryTree {
+    pub widget: gtk::Box,
+    store: gtk::TreeStore,
+    on_toggled: ToggledCallback,
+}
+
+impl CategoryTree {
+    pub fn new() -> Self {
+        let store = gtk::TreeStore::new(&[
+            glib::Type::BOOL,
+            glib::Type::STRING,
+            glib::Type::STRING,
+            glib::Type::BOOL,
+        ]);
+
+        for main in categories::top_level() {
+            let parent = Self::append(&store, None, main.name, main.description, true);
+            for child in categories::children(main.name) {
+                Self::append_category(&store, &parent, child);
+            }
+        }
+        let other = Self::append(
+            &store,
+            None,
+            "Other",
+            "Categories that do not belong to a main category",
+            false,
+        );
+        for category in categories::unparented() {
+            Self::append_category(&store, &other, category);
+        }
+        let reserved = Self::append(
+            &store,
+            None,
+            "Reserved",
+            "Desktop-specific categories that require Only Show In",
+            false,
+        );
+        for category in categories::reserved_categories() {
+            Self::append_category(&store, &reserved, category);
+        }
+
+        let tree_view = gtk::TreeView::with_model(&store);
+        tree_view.set_headers_visible(false);
+        tree_view.set_tooltip_column(DESCRIPTION_COLUMN as i32);
+        tree_view.set_enable_search(true);
+        tree_view.set_search_column(NAME_COLUMN as i32);
+
+        let toggle = gtk::CellRendererToggle::new();
+        let text = gtk::CellRendererText::new();
+        let column = gtk::TreeViewColumn::new();
+        column.pack_start(&toggle, false);
+        column.add_attribute(&toggle, "active", ACTIVE_COLUMN as i32);
+        column.add_attribute(&toggle, "visible", SELECTABLE_COLUMN as i32);
+        column.pack_start(&text, true);
+        column.add_attribute(&text, "text", NAME_COLUMN as i32);
+        tree_view.append_column(&column);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_child(Some(&tree_view));
+        scrolled.set_min_content_width(320);
+        scrolled.set_min_content_height(320);
+        scrolled.set_max_content_height(480);
+        scrolled.set_propagate_natural_height(true);
+
+        let custom_entry = gtk::Entry::new();
+        custom_entry.set_placeholder_text(Some("Custom category (X-...)"));
+        custom_entry.set_hexpand(true);
+        let add_button = gtk::Button::with_label("Add");
+
+        let custom_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        custom_box.append(&custom_entry);
+        custom_box.append(&add_button);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&scrolled);
+        widget.append(&custom_box);
+
+        let tree = Self {
+            widget,
+            store,
+            on_toggled: Rc::new(RefCell::new(None)),
+        };
+
+        {
+            let tree = tree.clone();
+            toggle.connect_toggled(move |_, path| {
+                let Some(iter) = tree.store.iter(&path) else {
+                    return;
+                };
+                let name = tree.store.get::<String>(&iter, NAME_COLUMN as i32);
+                let active = !tree.store.get::<bool>(&iter, ACTIVE_COLUMN as i32);
+                tree.set_active(&name, active);
+                tree.notify_toggled(&name, active);
+            });
+        }
+
+        {
+            let tree = tree.clone();
+            let entry = custom_entry.clone();
+            let add_custom = move || {
+                let text = entry.text().trim().to_string();
+                if text.is_empty() {
+                    return;
+                }
+                let name = if text.starts_with("X-") {
+                    text
+                } else {
+                    format!("X-{text}")
+                };
+                tree.notify_toggled(&name, true);
+                entry.set_text("");
+            };
+            let add_custom = Rc::new(add_custom);
+            {
+                let add_custom = add_custom.clone();
+                add_button.connect_clicked(move |_| add_custom());
+            }
+            custom_entry.connect_activate(move |_| add_custom());
+        }
+
+        tree
+    }
+
+    /// Called with the category name and its new state when the user checks
+    /// or unchecks a category, or adds a custom one.
+    pub fn connect_toggled<F: Fn(&str, bool) + 'static>(&self, f: F) {
+        *self.on_toggled.borrow_mut() = Some(Box::new(f));
+    }
+
+    /// Checks exactly the categories of a `Categories` value.
+    pub fn set_categories(&self, value: &str) {
+        let selected: Vec<&str> = value
+            .split(';')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        let store = self.store.clone();
+        self.store.foreach(move |_, _, iter| {
+            if store.get::<bool>(iter, SELECTABLE_COLUMN as i32) {
+                let name = store.get::<String>(iter, NAME_COLUMN as i32);
+                let active = selected.contains(&name.as_str());
+                store.set_value(iter, ACTIVE_COLUMN, &active.to_value());
+            }
+            false
+        });
+    }
+
+    /// Updates every row of `name`; categories with several parents appear
+    /// more than once.
+    fn set_active(&self, name: &str, active: bool) {
+        let store = self.store.clone();
+        let name = name.to_string();
+        self.store.foreach(move |_, _, iter| {
+            if store.get::<String>(iter, NAME_COLUMN as i32) == name
+                && store.get::<bool>(iter, SELECTABLE_COLUMN as i32)
+            {
+                store.set_value(iter, ACTIVE_COLUMN, &active.to_value());
+            }
+            false
+        });
+    }
+
+    fn append(
+        store: &gtk::TreeStore,
+        parent: Option<&gtk::TreeIter>,
+        name: &str,
+        description: &str,
+        selectable: bool,
+    ) -> gtk::TreeIter {
+        store.insert_with_values(
+            parent,
+            None,
+            &[
+                (ACTIVE_COLUMN, &false),
+                (NAME_COLUMN, &name),
+                (DESCRIPTION_COLUMN, &description),
+                (SELECTABLE_COLUMN, &selectable),
+            ],
+        )
+    }
+
+    fn append_category(store: &gtk::TreeStore, parent: &gtk::TreeIter, category: &Category) {
+        Self::append(
+            store,
+            Some(parent),
+            category.name,
+            category.description,
+            true,
+        );
+    }
+
+    fn notify_toggled(&self, name: &str, active: bool) {
+        if let Some(ref callback) = *self.on_toggled.borrow() {
+            callback(name, active);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::broken_entries::{self, OrphanedLauncher};
+use crate::desktop_file::DesktopFileError;
+use crate::feedback::{self, Feedback};
+use crate::logging;
+
+/// Check boxes of the listed launchers, with the file each one stands for.
+type Selection = Rc<RefCell<Vec<(gtk::CheckButton, PathBuf)>>>;
+
+/// Opens the wizard that offers to hide or delete user launchers whose
+/// program is no longer installed. `on_changed` is called after files were
+/// modified.
+pub fn present(parent: &adw::ApplicationWindow, on_changed: Rc<dyn Fn()>) {
+    let window = adw::Window::new();
+    window.set_title(Some("Clean Up Launchers"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(700, 560);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Clean Up Launchers"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let description = gtk::Label::new(Some(
+        "These launchers in your applications folder start programs that are not \
+         installed, usually because the application was removed. Hiding sets \
+         NoDisplay=true and keeps the file; deleting moves it to the trash.",
+    ));
+    description.add_css_class("dim-label");
+    description.set_wrap(true);
+    description.set_halign(gtk::Align::Start);
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+
+    let hide_button = gtk::Button::with_label("Hide Selected");
+    let delete_button = gtk::Button::with_label("Delete Selected");
+    delete_button.add_css_class("destructive-action");
+
+    let buttons = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    buttons.set_halign(gtk::Align::End);
+    buttons.append(&hide_button);
+    buttons.append(&delete_button);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&description);
+    content.append(&list);
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&content));
+    scrolled.set_vexpand(true);
+
+    let footer = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    footer.set_margin_start(12);
+    footer.set_margin_end(12);
+    footer.set_margin_top(6);
+    footer.set_margin_bottom(12);
+    footer.append(&buttons);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&scrolled);
+    main_box.append(&footer);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    let selection: Selection = Rc::new(RefCell::new(Vec::new()));
+    populate(&list, &selection, &buttons);
+
+    {
+        let list = list.clone();
+        let selection = selection.clone();
+        let buttons = buttons.clone();
+        let on_changed = on_changed.clone();
+        let feedback = feedback.clone();
+        hide_button.connect_clicked(move |_| {
+            let paths = selected(&selection);
+            apply_each(&feedback, &paths, ("hide", "Hid"), broken_entries::hide);
+            populate(&list, &selection, &buttons);
+            on_changed();
+        });
+    }
+
+    {
+        let window = window.clone();
+        delete_button.connect_clicked(move |_| {
+            let paths = selected(&selection);
+            if paths.is_empty() {
+                return;
+            }
+            let names: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+            let dialog = gtk::MessageDialog::builder()
+                .transient_for(&window)
+                .modal(true)
+                .message_type(gtk::MessageType::Warning)
+                .buttons(gtk::ButtonsType::OkCancel)
+                .text(format!("Delete {} Launchers?", paths.len()))
+                .secondary_text(names.join("\n"))
+                .build();
+            let list = list.clone();
+            let selection = selection.clone();
+            let buttons = buttons.clone();
+            let on_changed = on_changed.clone();
+            let feedback = feedback.clone();
+            dialog.connect_response(move |dialog, response| {
+                if response == gtk::ResponseType::Ok {
+                    apply_each(
+                        &feedback,
+                        &paths,
+                        ("delete", "Deleted"),
+                        broken_entries::delete,
+                    );
+                    populate(&list, &selection, &buttons);
+                    on_changed();
+                }
+                dialog.close();
+            });
+            dialog.show();
+        });
+    }
+
+    window.present();
+}
+
+/// Runs `action` on every path, logging each outcome, and sums them up in
+/// one toast. `verbs` are the infinitive and past tense, e.g. "hide", "Hid".
+fn apply_each(
+    feedback: &Feedback,
+    paths: &[PathBuf],
+    verbs: (&str, &str),
+    action: fn(&Path) -> Result<(), DesktopFileError>,
+) {
+    let (verb, done) = verbs;
+    let mut failed = 0;
+    for path in paths {
+        match action(path) {
+            Ok(()) => logging::info("cleanup", format!("{done} {}", path.display())),
+            Err(e) => {
+                failed += 1;
+                logging::error(
+                    "cleanup",
+                    format!(
+                        "Could not {verb} {}: {}",
+                        path.display(),
+                        feedback::explain(&e)
+                    ),
+                );
+            }
+        }
+    }
+    if failed == 0 {
+        feedback.success("cleanup", format!("{done} {} launchers", paths.len()));
+    } else {
+        feedback.problem(
+            "cleanup",
+            format!("Could not {verb} {failed} of {} launchers", paths.len()),
+        );
+    }
+}
+
+fn selected(selection: &Selection) -> Vec<PathBuf> {
+    selection
+        .borrow()
+        .iter()
+        .filter(|(check, _)| check.is_active())
+        .map(|(_, path)| path.clone())
+        .collect()
+}
+
+fn populate(list: &gtk::ListBox, selection: &Selection, buttons: &gtk::Box) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    selection.borrow_mut().clear();
+
+    let launchers = broken_entries::orphaned_launchers();
+    buttons.set_sensitive(!launchers.is_empty());
+    if launchers.is_empty() {
+        let row = adw::ActionRow::new();
+        row.set_title("No orphaned launchers");
+        row.set_subtitle("Every user launcher starts an installed program");
+        list.append(&row);
+        return;
+    }
+
+    for launcher in launchers {
+        let (row, check) = create_row(&launcher);
+        list.append(&row);
+        selection.borrow_mut().push((check, launcher.path));
+    }
+}
+
+fn create_row(launcher: &OrphanedLauncher) -> (adw::ActionRow, gtk::CheckButton) {
+    let row = adw::ActionRow::new();
+    row.set_title(&glib::markup_escape_text(&launcher.name));
+    let mut subtitle = vec![launcher.id.clone()];
+    subtitle.extend(launcher.problems.iter().cloned());
+    if launcher.overrides_system {
+        subtitle.push("Overrides a system entry, which is shown again when deleted".to_string());
+    }
+    if launcher.no_display {
+        subtitle.push("Already hidden".to_string());
+    }
+    row.set_subtitle(&glib::markup_escape_text(&subtitle.join("\n")));
+    row.set_tooltip_text(Some(&launcher.path.display().to_string()));
+
+    let check = gtk::CheckButton::new();
+    check.set_active(!launcher.no_display);
+    check.set_valign(gtk::Align::Center);
+    row.add_prefix(&check);
+    row.set_activatable_widget(Some(&check));
+
+    (row, check)
+}
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::health::{self, Severity};
+
+const USAGE: &str =
+    "Usage: menu-entry-manager --health-report [--format json|html] [--output FILE]";
+
+enum Format {
+    Json,
+    Html,
+}
+
+/// Runs the command given on the command line, if any, and returns the exit
+/// code. `None` means the GUI should start.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("--health-report") => Some(health_report(&args[1..])),
+        _ => None,
+    }
+}
+
+/// Writes the health report to standard output or a file. Exits with 1 when
+/// the report contains errors, 2 on invalid arguments and 3 when the report
+/// cannot be serialized or written.
+fn health_report(args: &[String]) -> i32 {
+    let mut format = Format::Json;
+    let mut output: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().map(String::as_str) {
+                Some("json") => format = Format::Json,
+                Some("html") => format = Format::Html,
+                _ => {
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            },
+            "--output" | "-o" => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => {
+                    eprintln!("{USAGE}");
+                    return 2;
+                }
+            },
+            _ => {
+                eprintln!("Unknown argument '{arg}'\n{USAGE}");
+                return 2;
+            }
+        }
+    }
+
+    let report = health::scan();
+    let content = match format {
+        Format::Json => match report.to_json() {
+            Ok(json) => json,
+            Err(e) => {
+                eprintln!("Error serializing report: {e}");
+                return 3;
+            }
+        },
+        Format::Html => report.to_html(),
+    };
+
+    match output {
+        Some(path) => {
+            if let Err(e) = fs::write(&path, content) {
+                eprintln!("Error writing {}: {e}", path.display());
+                return 3;
+            }
+            eprintln!(
+                "{} files checked: {} errors, {} warnings. Report written to {}",
+                report.files,
+                report.count(Severity::Error),
+                report.count(Severity::Warning),
+                path.display()
+            );
+        }
+        None => println!("{content}"),
+    }
+
+    if report.count(Severity::Error) > 0 {
+        1
+    } else {
+        0
+    }
+}
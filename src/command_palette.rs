@@ -0,0 +1,215 @@
+use gtk::gdk;
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::shortcuts::{self, Command, COMMANDS};
+
+/// Results shown at most, so typing stays fast with many entries.
+const MAX_RESULTS: usize = 50;
+
+/// An entry of the file list: display name and path.
+pub type PaletteEntry = (String, String);
+
+enum Item {
+    Command(&'static Command),
+    Entry(PaletteEntry),
+}
+
+/// Opens the palette searching commands and entries. Commands run as
+/// actions of `parent`; `on_entry` is called with the path of a chosen entry.
+pub fn present(
+    parent: &adw::ApplicationWindow,
+    entries: Vec<PaletteEntry>,
+    on_entry: Rc<dyn Fn(&str)>,
+) {
+    let window = adw::Window::new();
+    window.set_title(Some("Command Palette"));
+    window.set_transient_for(Some(parent));
+    window.set_modal(true);
+    window.set_default_size(560, 440);
+
+    let search_entry = gtk::SearchEntry::new();
+    search_entry.set_placeholder_text(Some("Search commands and entries…"));
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::Single);
+    list.add_css_class("boxed-list");
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&list));
+    scrolled.set_vexpand(true);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&search_entry);
+    content.append(&scrolled);
+    window.set_content(Some(&content));
+
+    let results: Rc<RefCell<Vec<Item>>> = Rc::new(RefCell::new(Vec::new()));
+    let entries = Rc::new(entries);
+    populate(&list, &results, &entries, "");
+
+    {
+        let list = list.clone();
+        let results = results.clone();
+        search_entry.connect_search_changed(move |entry| {
+            populate(&list, &results, &entries, &entry.text());
+        });
+    }
+
+    let run = {
+        let window = window.clone();
+        let parent = parent.clone();
+        let results = results.clone();
+        Rc::new(move |index: usize| {
+            let item = match results.borrow().get(index) {
+                Some(Item::Command(command)) => Item::Command(command),
+                Some(Item::Entry(entry)) => Item::Entry(entry.clone()),
+                None => return,
+            };
+            window.close();
+            match item {
+                Item::Command(command) => {
+                    let _ = WidgetExt::activate_action(&parent, command.action, None);
+                }
+                Item::Entry((_, path)) => on_entry(&path),
+            }
+        })
+    };
+
+    {
+        let run = run.clone();
+        list.connect_row_activated(move |_, row| run(row.index() as usize));
+    }
+
+    {
+        let list = list.clone();
+        search_entry.connect_activate(move |_| {
+            if let Some(row) = list.selected_row() {
+                run(row.index() as usize);
+            }
+        });
+    }
+
+    {
+        let window = window.clone();
+        search_entry.connect_stop_search(move |_| window.close());
+    }
+
+    // Up and Down move through the results while typing
+    {
+        let list = list.clone();
+        let entry = search_entry.clone();
+        let controller = gtk::EventControllerKey::new();
+        controller.connect_key_pressed(move |_, key, _, _| {
+            let step = match key {
+                gdk::Key::Down => 1,
+                gdk::Key::Up => -1,
+                _ => return glib::Propagation::Proceed,
+            };
+            let current = list.selected_row().map(|row| row.index()).unwrap_or(-1);
+            if let Some(row) = list.row_at_index(current + step) {
+                list.select_row(Some(&row));
+                // Focusing the row scrolls it into view
+                row.grab_focus();
+                entry.grab_focus();
+                entry.set_position(-1);
+            }
+            glib::Propagation::Stop
+        });
+        search_entry.add_controller(controller);
+    }
+
+    window.present();
+    search_entry.grab_focus();
+}
+
+fn populate(
+    list: &gtk::ListBox,
+    results: &Rc<RefCell<Vec<Item>>>,
+    entries: &[PaletteEntry],
+    query: &str,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let mut scored: Vec<(i32, Item)> = COMMANDS
+        .iter()
+        .filter_map(|command| {
+            fuzzy_score(query, command.title).map(|score| (score, Item::Command(command)))
+        })
+        .collect();
+    scored.extend(entries.iter().filter_map(|(name, path)| {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        fuzzy_score(query, name)
+            .max(fuzzy_score(query, file_name))
+            .map(|score| (score, Item::Entry((name.clone(), path.clone()))))
+    }));
+    // Stable, so commands stay ahead of entries with the same score
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.truncate(MAX_RESULTS);
+
+    for (_, item) in &scored {
+        let row = adw::ActionRow::new();
+        match item {
+            Item::Command(command) => {
+                row.set_title(&glib::markup_escape_text(command.title));
+                row.set_subtitle(&glib::markup_escape_text(command.section));
+                row.add_prefix(&gtk::Image::from_icon_name("system-run-symbolic"));
+                if let Some(label) = shortcuts::accel_label(command) {
+                    let accel = gtk::Label::new(Some(&label));
+                    accel.add_css_class("dim-label");
+                    row.add_suffix(&accel);
+                }
+            }
+            Item::Entry((name, path)) => {
+                row.set_title(&glib::markup_escape_text(name));
+                row.set_subtitle(&glib::markup_escape_text(path));
+                row.add_prefix(&gtk::Image::from_icon_name(
+                    "application-x-executable-symbolic",
+                ));
+            }
+        }
+        list.append(&row);
+    }
+
+    *results.borrow_mut() = scored.into_iter().map(|(_, item)| item).collect();
+    if let Some(row) = list.row_at_index(0) {
+        list.select_row(Some(&row));
+    }
+}
+
+/// Scores `text` when all characters of `query` appear in it in order.
+/// Consecutive characters and characters at word starts score higher, and
+/// shorter texts win ties. Without a query everything matches equally.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    if query.trim().is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut last: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (next..text.len()).find(|&i| text[i] == wanted)?;
+        score += 1;
+        if last.is_some_and(|last| last + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        last = Some(found);
+        next = found + 1;
+    }
+
+    Some(score * 8 - (text.len() as i32).min(64) / 8)
+}
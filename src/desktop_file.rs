@@ -1,16 +1,53 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::env;
 use std::fs;
 use std::path::Path;
 
+use crate::{flatpak, origin, xdg};
+
+/// Keys of the `[Desktop Entry]` group that map onto `DesktopEntry` fields.
+const KNOWN_KEYS: &[&str] = &[
+    "Type",
+    "Version",
+    "Name",
+    "GenericName",
+    "Comment",
+    "Icon",
+    "Exec",
+    "Path",
+    "Terminal",
+    "Categories",
+    "Keywords",
+    "StartupWMClass",
+    "URL",
+    "MimeType",
+    "Hidden",
+    "OnlyShowIn",
+    "NotShowIn",
+    "DBusActivatable",
+    "TryExec",
+    "Actions",
+    "NoDisplay",
+    "Implements",
+    "StartupNotify",
+    "PrefersNonDefaultGPU",
+    "SingleMainWindow",
+];
+
 #[derive(Debug, thiserror::Error)]
 pub enum DesktopFileError {
     #[error("Failed to read file: {0}")]
     IoError(#[from] std::io::Error),
-    #[error("Invalid desktop file format: {0}")]
-    #[allow(dead_code)]
-    ParseError(String),
+    #[error(
+        "{}line {line}, column {column}: {message}",
+        .path.as_ref().map(|p| format!("{p}: ")).unwrap_or_default()
+    )]
+    ParseError {
+        path: Option<String>,
+        line: usize,
+        column: usize,
+        message: String,
+    },
     #[error("Missing required field: {0}")]
     MissingField(String),
     #[error("Invalid value for field {0}: {1}")]
@@ -18,12 +55,74 @@ pub enum DesktopFileError {
     InvalidValue(String, String),
 }
 
+impl DesktopFileError {
+    fn parse_error(line: usize, column: usize, message: impl Into<String>) -> Self {
+        DesktopFileError::ParseError {
+            path: None,
+            line,
+            column,
+            message: message.into(),
+        }
+    }
+
+    /// Attaches the file a parse error was found in.
+    pub fn with_path(self, file: &Path) -> Self {
+        match self {
+            DesktopFileError::ParseError {
+                line,
+                column,
+                message,
+                ..
+            } => DesktopFileError::ParseError {
+                path: Some(file.to_string_lossy().to_string()),
+                line,
+                column,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// The 1-based line a parse error points at, if any.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            DesktopFileError::ParseError { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DesktopFile {
     pub desktop_entry: DesktopEntry,
+    /// `[Desktop Action <id>]` groups, in file order.
+    #[serde(default)]
+    pub desktop_actions: Vec<DesktopAction>,
+    /// Any other groups (e.g. vendor extension groups), kept verbatim.
+    #[serde(default)]
+    pub other_groups: Vec<DesktopGroup>,
     pub icon_data: Option<Vec<u8>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesktopAction {
+    pub id: String,
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Icon")]
+    pub icon: Option<String>,
+    #[serde(rename = "Exec")]
+    pub exec: Option<String>,
+    #[serde(default)]
+    pub extra_keys: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DesktopGroup {
+    pub name: String,
+    pub entries: Vec<(String, String)>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DesktopEntry {
     #[serde(rename = "Type")]
@@ -66,6 +165,64 @@ pub struct DesktopEntry {
     pub try_exec: Option<String>,
     #[serde(rename = "Actions")]
     pub actions: Option<String>,
+    #[serde(rename = "NoDisplay")]
+    pub no_display: Option<bool>,
+    #[serde(rename = "Implements")]
+    pub implements: Option<String>,
+    #[serde(rename = "StartupNotify")]
+    pub startup_notify: Option<bool>,
+    #[serde(rename = "PrefersNonDefaultGPU")]
+    pub prefers_non_default_gpu: Option<bool>,
+    #[serde(rename = "SingleMainWindow")]
+    pub single_main_window: Option<bool>,
+    /// Keys without a dedicated field (localized values, `X-` extensions, ...)
+    /// in the order they appeared in the file.
+    #[serde(default)]
+    pub extra_keys: Vec<(String, String)>,
+}
+
+impl DesktopEntry {
+    /// Value of a key kept in `extra_keys`.
+    pub fn extra_key(&self, key: &str) -> Option<&str> {
+        self.extra_keys
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Sets a key kept in `extra_keys`, or removes it when `value` is `None`.
+    pub fn set_extra_key(&mut self, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => match self.extra_keys.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value.to_string(),
+                None => self.extra_keys.push((key.to_string(), value.to_string())),
+            },
+            None => self.extra_keys.retain(|(k, _)| k != key),
+        }
+    }
+
+    /// Drops the keys the specification only allows for other entry types,
+    /// e.g. `Exec` of an entry that was switched to `Directory`.
+    pub fn clear_inapplicable_keys(&mut self) {
+        if self.entry_type != "Application" {
+            self.exec = None;
+            self.path = None;
+            self.terminal = None;
+            self.categories = None;
+            self.startup_wm_class = None;
+            self.mime_type = None;
+            self.dbus_activatable = None;
+            self.try_exec = None;
+            self.actions = None;
+            self.implements = None;
+            self.startup_notify = None;
+            self.prefers_non_default_gpu = None;
+            self.single_main_window = None;
+        }
+        if self.entry_type != "Link" {
+            self.url = None;
+        }
+    }
 }
 
 impl DesktopFile {
@@ -92,42 +249,196 @@ impl DesktopFile {
                 dbus_activatable: Some(false),
                 try_exec: None,
                 actions: None,
+                no_display: None,
+                implements: None,
+                startup_notify: None,
+                prefers_non_default_gpu: None,
+                single_main_window: None,
+                extra_keys: Vec::new(),
             },
+            desktop_actions: Vec::new(),
+            other_groups: Vec::new(),
             icon_data: None,
         }
     }
 
+    /// Lenient load used for display: invalid UTF-8 is replaced and malformed
+    /// lines are skipped.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, DesktopFileError> {
-        let content = fs::read_to_string(path)?;
-        Self::from_string(&content)
+        let bytes = fs::read(path)?;
+        Self::from_string(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Lenient load that also returns the first line the specification does
+    /// not allow (or invalid UTF-8), with the file path, line and column.
+    pub fn from_file_checked<P: AsRef<Path>>(
+        path: P,
+    ) -> (Result<Self, DesktopFileError>, Option<DesktopFileError>) {
+        let path = path.as_ref();
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) => return (Err(e.into()), None),
+        };
+        let content = match String::from_utf8(bytes) {
+            Ok(content) => content,
+            Err(e) => {
+                let valid = &e.as_bytes()[..e.utf8_error().valid_up_to()];
+                let valid = String::from_utf8_lossy(valid);
+                let line = valid.matches('\n').count() + 1;
+                let column = valid.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+                let problem = DesktopFileError::parse_error(line, column, "invalid UTF-8 sequence")
+                    .with_path(path);
+                let result = Self::from_string(&String::from_utf8_lossy(e.as_bytes()));
+                return (result, Some(problem));
+            }
+        };
+        let (result, problems) = Self::parse(&content);
+        let problem = problems.into_iter().next().map(|e| e.with_path(path));
+        (result, problem)
     }
 
     pub fn from_string(content: &str) -> Result<Self, DesktopFileError> {
-        let lines = content.lines();
-        let mut current_section = None;
+        Self::parse(content).0
+    }
+
+    /// All problems a strict parse reports, in line order, followed by any
+    /// error that prevents even a lenient load.
+    pub fn diagnostics(content: &str) -> Vec<DesktopFileError> {
+        let (result, mut problems) = Self::parse(content);
+        if let Err(e) = result {
+            problems.push(e);
+        }
+        problems
+    }
+
+    /// Lenient parse that also collects everything a strict parse rejects.
+    fn parse(content: &str) -> (Result<Self, DesktopFileError>, Vec<DesktopFileError>) {
+        let mut problems = Vec::new();
+        let mut current_section: Option<String> = None;
+        let mut seen_groups: HashMap<String, usize> = HashMap::new();
+        let mut seen_keys: HashMap<String, usize> = HashMap::new();
         let mut desktop_entry = HashMap::new();
+        let mut extra_keys = Vec::new();
+        let mut desktop_actions: Vec<DesktopAction> = Vec::new();
+        let mut other_groups: Vec<DesktopGroup> = Vec::new();
 
-        for line in lines {
-            let line = line.trim();
+        for (index, raw_line) in content.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw_line.trim();
+            let indent = raw_line.chars().count() - raw_line.trim_start().chars().count();
 
             if line.is_empty() || line.starts_with('#') {
                 continue;
             }
 
+            if line.starts_with('[') && !line.ends_with(']') {
+                problems.push(DesktopFileError::parse_error(
+                    line_no,
+                    indent + line.chars().count() + 1,
+                    "group header is missing its closing `]`",
+                ));
+                continue;
+            }
+
             if line.starts_with('[') && line.ends_with(']') {
-                current_section = Some(line[1..line.len() - 1].to_string());
+                let section = line[1..line.len() - 1].to_string();
+                if let Some(bad) = section.find(|c: char| c == '[' || c == ']' || c.is_control()) {
+                    problems.push(DesktopFileError::parse_error(
+                        line_no,
+                        indent + section[..bad].chars().count() + 2,
+                        "invalid character in group name",
+                    ));
+                }
+                if seen_groups.is_empty() && section != "Desktop Entry" {
+                    problems.push(DesktopFileError::parse_error(
+                        line_no,
+                        indent + 1,
+                        "the first group must be [Desktop Entry]",
+                    ));
+                }
+                if let Some(first) = seen_groups.get(&section) {
+                    problems.push(DesktopFileError::parse_error(
+                        line_no,
+                        indent + 1,
+                        format!("duplicate group [{section}] (first defined on line {first})"),
+                    ));
+                } else {
+                    seen_groups.insert(section.clone(), line_no);
+                }
+                seen_keys.clear();
+                if let Some(id) = section.strip_prefix("Desktop Action ") {
+                    desktop_actions.push(DesktopAction {
+                        id: id.to_string(),
+                        name: String::new(),
+                        icon: None,
+                        exec: None,
+                        extra_keys: Vec::new(),
+                    });
+                } else if section != "Desktop Entry" {
+                    other_groups.push(DesktopGroup {
+                        name: section.clone(),
+                        entries: Vec::new(),
+                    });
+                }
+                current_section = Some(section);
                 continue;
             }
 
-            if let Some(section) = &current_section {
-                if let Some(pos) = line.find('=') {
-                    let key = line[..pos].trim();
-                    let value = line[pos + 1..].trim();
+            let Some(pos) = line.find('=') else {
+                problems.push(DesktopFileError::parse_error(
+                    line_no,
+                    indent + 1,
+                    "expected `Key=Value`, a group header or a comment",
+                ));
+                continue;
+            };
+
+            let Some(section) = &current_section else {
+                problems.push(DesktopFileError::parse_error(
+                    line_no,
+                    indent + 1,
+                    "key outside of any group",
+                ));
+                continue;
+            };
+
+            let key = line[..pos].trim();
+            let value = line[pos + 1..].trim();
 
-                    if section == "Desktop Entry" {
-                        desktop_entry.insert(key.to_string(), value.to_string());
+            if let Some(column) = Self::invalid_key_column(key) {
+                problems.push(DesktopFileError::parse_error(
+                    line_no,
+                    indent + column,
+                    format!("invalid key name `{key}`"),
+                ));
+            }
+            if let Some(first) = seen_keys.get(key) {
+                problems.push(DesktopFileError::parse_error(
+                    line_no,
+                    indent + 1,
+                    format!("duplicate key `{key}` (first defined on line {first})"),
+                ));
+            } else {
+                seen_keys.insert(key.to_string(), line_no);
+            }
+
+            if section == "Desktop Entry" {
+                if KNOWN_KEYS.contains(&key) {
+                    desktop_entry.insert(key.to_string(), value.to_string());
+                } else {
+                    extra_keys.push((key.to_string(), value.to_string()));
+                }
+            } else if section.starts_with("Desktop Action ") {
+                if let Some(action) = desktop_actions.last_mut() {
+                    match key {
+                        "Name" => action.name = value.to_string(),
+                        "Icon" => action.icon = Some(value.to_string()),
+                        "Exec" => action.exec = Some(value.to_string()),
+                        _ => action.extra_keys.push((key.to_string(), value.to_string())),
                     }
                 }
+            } else if let Some(group) = other_groups.last_mut() {
+                group.entries.push((key.to_string(), value.to_string()));
             }
         }
 
@@ -137,10 +448,15 @@ impl DesktopFile {
                 .cloned()
                 .unwrap_or_else(|| "Application".to_string()),
             version: desktop_entry.get("Version").cloned(),
-            name: desktop_entry
-                .get("Name")
-                .cloned()
-                .ok_or_else(|| DesktopFileError::MissingField("Name".to_string()))?,
+            name: match desktop_entry.get("Name") {
+                Some(name) => name.clone(),
+                None => {
+                    return (
+                        Err(DesktopFileError::MissingField("Name".to_string())),
+                        problems,
+                    )
+                }
+            },
             generic_name: desktop_entry.get("GenericName").cloned(),
             comment: desktop_entry.get("Comment").cloned(),
             icon: desktop_entry.get("Icon").cloned(),
@@ -158,12 +474,48 @@ impl DesktopFile {
             dbus_activatable: desktop_entry.get("DBusActivatable").map(|v| v == "true"),
             try_exec: desktop_entry.get("TryExec").cloned(),
             actions: desktop_entry.get("Actions").cloned(),
+            no_display: desktop_entry.get("NoDisplay").map(|v| v == "true"),
+            implements: desktop_entry.get("Implements").cloned(),
+            startup_notify: desktop_entry.get("StartupNotify").map(|v| v == "true"),
+            prefers_non_default_gpu: desktop_entry
+                .get("PrefersNonDefaultGPU")
+                .map(|v| v == "true"),
+            single_main_window: desktop_entry.get("SingleMainWindow").map(|v| v == "true"),
+            extra_keys,
         };
 
-        Ok(Self {
+        let file = Self {
             desktop_entry: entry,
+            desktop_actions,
+            other_groups,
             icon_data: None,
-        })
+        };
+        (Ok(file), problems)
+    }
+
+    /// Returns the 1-based column of the first character not allowed in a key
+    /// name (`A-Za-z0-9-`, optionally followed by a `[locale]` suffix).
+    fn invalid_key_column(key: &str) -> Option<usize> {
+        let (name, locale) = match key.find('[') {
+            Some(pos) => (&key[..pos], Some(&key[pos..])),
+            None => (key, None),
+        };
+        if name.is_empty() {
+            return Some(1);
+        }
+        if let Some(bad) = name.find(|c: char| !(c.is_ascii_alphanumeric() || c == '-')) {
+            return Some(name[..bad].chars().count() + 1);
+        }
+        match locale {
+            Some(locale)
+                if !locale.ends_with(']')
+                    || locale.len() < 3
+                    || locale[1..locale.len() - 1].contains(['[', ']']) =>
+            {
+                Some(name.chars().count() + 1)
+            }
+            _ => None,
+        }
     }
 
     #[allow(clippy::inherent_to_string)]
@@ -237,6 +589,61 @@ impl DesktopFile {
         if let Some(ref actions) = self.desktop_entry.actions {
             content.push_str(&format!("Actions={actions}\n"));
         }
+        if let Some(no_display) = self.desktop_entry.no_display {
+            content.push_str(&format!(
+                "NoDisplay={}\n",
+                if no_display { "true" } else { "false" }
+            ));
+        }
+        if let Some(ref implements) = self.desktop_entry.implements {
+            content.push_str(&format!("Implements={implements}\n"));
+        }
+        if let Some(startup_notify) = self.desktop_entry.startup_notify {
+            content.push_str(&format!(
+                "StartupNotify={}\n",
+                if startup_notify { "true" } else { "false" }
+            ));
+        }
+        if let Some(prefers_non_default_gpu) = self.desktop_entry.prefers_non_default_gpu {
+            content.push_str(&format!(
+                "PrefersNonDefaultGPU={}\n",
+                if prefers_non_default_gpu {
+                    "true"
+                } else {
+                    "false"
+                }
+            ));
+        }
+        if let Some(single_main_window) = self.desktop_entry.single_main_window {
+            content.push_str(&format!(
+                "SingleMainWindow={}\n",
+                if single_main_window { "true" } else { "false" }
+            ));
+        }
+        for (key, value) in &self.desktop_entry.extra_keys {
+            content.push_str(&format!("{key}={value}\n"));
+        }
+
+        for action in &self.desktop_actions {
+            content.push_str(&format!("\n[Desktop Action {}]\n", action.id));
+            content.push_str(&format!("Name={}\n", action.name));
+            if let Some(ref icon) = action.icon {
+                content.push_str(&format!("Icon={icon}\n"));
+            }
+            if let Some(ref exec) = action.exec {
+                content.push_str(&format!("Exec={exec}\n"));
+            }
+            for (key, value) in &action.extra_keys {
+                content.push_str(&format!("{key}={value}\n"));
+            }
+        }
+
+        for group in &self.other_groups {
+            content.push_str(&format!("\n[{}]\n", group.name));
+            for (key, value) in &group.entries {
+                content.push_str(&format!("{key}={value}\n"));
+            }
+        }
 
         content
     }
@@ -271,6 +678,15 @@ impl DesktopFile {
             return Err(DesktopFileError::MissingField("URL".to_string()));
         }
 
+        for action in &self.desktop_actions {
+            if action.name.is_empty() {
+                return Err(DesktopFileError::MissingField(format!(
+                    "Name (Desktop Action {})",
+                    action.id
+                )));
+            }
+        }
+
         Ok(())
     }
 }
@@ -288,8 +704,8 @@ pub fn get_desktop_file_paths() -> Vec<String> {
         }
     }
 
-    if let Ok(home) = env::var("HOME") {
-        let user_apps = format!("{home}/.local/share/applications");
+    if let Some(data_home) = xdg::data_home() {
+        let user_apps = data_home.join("applications");
         if let Ok(entries) = fs::read_dir(user_apps) {
             for entry in entries.flatten() {
                 if let Some(ext) = entry.path().extension() {
@@ -301,5 +717,260 @@ pub fn get_desktop_file_paths() -> Vec<String> {
         }
     }
 
+    let package_dirs = flatpak::export_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+        .chain([origin::snap_applications_dir()]);
+    for dir in package_dirs {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry.path().extension().is_some_and(|ext| ext == "desktop") {
+                    paths.push(entry.path().to_string_lossy().to_string());
+                }
+            }
+        }
+    }
+
     paths
 }
+
+/// `.directory` files in `desktop-directories` under the XDG data
+/// directories, system directories first.
+pub fn get_directory_file_paths() -> Vec<String> {
+    let mut dirs = xdg::data_dirs();
+    dirs.reverse();
+    dirs.extend(xdg::data_home());
+
+    let mut paths = Vec::new();
+    for dir in dirs {
+        if let Ok(entries) = fs::read_dir(dir.join("desktop-directories")) {
+            let mut found: Vec<String> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "directory"))
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            found.sort();
+            paths.extend(found);
+        }
+    }
+    paths
+}
+
+/// Keys whose value is a `;`-separated list. When both sides change one, the
+/// items each side added or removed are combined instead of conflicting.
+const LIST_KEYS: &[&str] = &[
+    "Categories",
+    "Keywords",
+    "MimeType",
+    "OnlyShowIn",
+    "NotShowIn",
+    "Actions",
+    "Implements",
+];
+
+/// Groups with their keys and raw values in file order. Comments and blank
+/// lines are dropped.
+fn key_file_groups(content: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut groups: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            groups.push((name.to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, keys))) =
+            (line.split_once('='), groups.last_mut())
+        {
+            keys.push((key.trim().to_string(), value.trim_start().to_string()));
+        }
+    }
+    groups
+}
+
+/// Splits `Name[de_DE]` into `("Name", Some("de_DE"))`.
+fn split_locale(key: &str) -> (&str, Option<&str>) {
+    match key.split_once('[') {
+        Some((name, rest)) => (name, rest.strip_suffix(']')),
+        None => (key, None),
+    }
+}
+
+fn list_items(value: &str) -> Vec<&str> {
+    value.split(';').filter(|item| !item.is_empty()).collect()
+}
+
+/// Combines the changes both sides made to a list value relative to `base`:
+/// items either side removed are dropped, items either side added are kept.
+fn merge_lists(base: &str, ours: &str, theirs: &str) -> String {
+    let base = list_items(base);
+    let theirs = list_items(theirs);
+    let mut items: Vec<&str> = list_items(ours)
+        .into_iter()
+        .filter(|item| !base.contains(item) || theirs.contains(item))
+        .collect();
+    for item in theirs {
+        if !base.contains(&item) && !items.contains(&item) {
+            items.push(item);
+        }
+    }
+    items.iter().map(|item| format!("{item};")).collect()
+}
+
+/// A key the two sides of a merge changed in different ways. `None` means
+/// the side does not have the key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+}
+
+impl MergeConflict {
+    /// What happened to the key on each side, e.g. "changed on both sides".
+    pub fn describe(&self) -> &'static str {
+        match (&self.base, &self.ours, &self.theirs) {
+            (None, _, _) => "different values on both sides",
+            (Some(_), None, _) => "removed in ours, changed in theirs",
+            (Some(_), _, None) => "changed in ours, removed in theirs",
+            _ => "changed on both sides",
+        }
+    }
+}
+
+/// A key in the result of a three-way merge.
+#[derive(Debug, Clone)]
+pub struct MergedKey {
+    pub group: String,
+    /// Key name without locale, e.g. `Name` for `Name[de]`.
+    pub key: String,
+    pub locale: Option<String>,
+    /// Merged value; `None` leaves the key out. For conflicts this is our
+    /// value until the conflict is resolved.
+    pub value: Option<String>,
+    pub conflict: Option<MergeConflict>,
+}
+
+impl MergedKey {
+    /// The key as written in the file, with its locale.
+    pub fn full_key(&self) -> String {
+        match &self.locale {
+            Some(locale) => format!("{}[{locale}]", self.key),
+            None => self.key.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub keys: Vec<MergedKey>,
+}
+
+impl MergeResult {
+    /// Indices into `keys` of the conflicting keys.
+    pub fn conflicts(&self) -> Vec<usize> {
+        (0..self.keys.len())
+            .filter(|&i| self.keys[i].conflict.is_some())
+            .collect()
+    }
+
+    /// The merged file content.
+    pub fn content(&self) -> String {
+        let mut content = String::new();
+        let mut current_group: Option<&str> = None;
+        for key in &self.keys {
+            let Some(value) = &key.value else {
+                continue;
+            };
+            if current_group != Some(key.group.as_str()) {
+                if current_group.is_some() {
+                    content.push('\n');
+                }
+                content.push_str(&format!("[{}]\n", key.group));
+                current_group = Some(&key.group);
+            }
+            content.push_str(&format!("{}={value}\n", key.full_key()));
+        }
+        content
+    }
+}
+
+/// Key-level three-way merge of two files derived from `base`; without a
+/// base every key present on both sides with different values conflicts.
+/// Groups and localized keys (`Name[de]`) are merged independently, list
+/// values such as `Categories` item by item. A key changed on one side only
+/// takes that side's value. The result follows the order of `ours`, with keys
+/// only `theirs` has placed after the key preceding them there.
+pub fn merge(base: Option<&str>, ours: &str, theirs: &str) -> MergeResult {
+    let base = key_file_groups(base.unwrap_or_default());
+    let ours = key_file_groups(ours);
+    let theirs = key_file_groups(theirs);
+
+    // Every (group, key) in output order
+    let mut order: Vec<(String, Vec<String>)> = Vec::new();
+    for (group, keys) in ours.iter().chain(&theirs).chain(&base) {
+        let index = match order.iter().position(|(name, _)| name == group) {
+            Some(index) => index,
+            None => {
+                order.push((group.clone(), Vec::new()));
+                order.len() - 1
+            }
+        };
+        let mut insert_at = 0;
+        for (key, _) in keys {
+            match order[index].1.iter().position(|k| k == key) {
+                Some(position) => insert_at = position + 1,
+                None => {
+                    order[index].1.insert(insert_at, key.clone());
+                    insert_at += 1;
+                }
+            }
+        }
+    }
+
+    let lookup = |groups: &[(String, Vec<(String, String)>)], group: &str, key: &str| {
+        groups
+            .iter()
+            .find(|(name, _)| name == group)
+            .and_then(|(_, keys)| keys.iter().find(|(k, _)| k == key))
+            .map(|(_, v)| v.clone())
+    };
+
+    let mut keys = Vec::new();
+    for (group, names) in order {
+        for full_key in names {
+            let b = lookup(&base, &group, &full_key);
+            let o = lookup(&ours, &group, &full_key);
+            let t = lookup(&theirs, &group, &full_key);
+            let (key, locale) = split_locale(&full_key);
+
+            let (value, conflict) = if o == t || b == t {
+                (o, None)
+            } else if b == o {
+                (t, None)
+            } else if let (Some(o), Some(t), true) = (&o, &t, LIST_KEYS.contains(&key)) {
+                (
+                    Some(merge_lists(b.as_deref().unwrap_or_default(), o, t)),
+                    None,
+                )
+            } else {
+                let conflict = MergeConflict {
+                    base: b,
+                    ours: o.clone(),
+                    theirs: t,
+                };
+                (o, Some(conflict))
+            };
+
+            keys.push(MergedKey {
+                group: group.clone(),
+                key: key.to_string(),
+                locale: locale.map(String::from),
+                value,
+                conflict,
+            });
+        }
+    }
+
+    MergeResult { keys }
+}
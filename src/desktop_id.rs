@@ -0,0 +1,363 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::desktop_file::{get_directory_file_paths, DesktopFileError};
+use crate::mime::MimeApps;
+use crate::{logging, origin, xdg};
+
+/// Desktop file ID of a file: its path below the `applications` directory it
+/// is in, with subdirectories joined by `-` as in `files_in`, or its file
+/// name for files elsewhere.
+pub fn from_path(path: &Path) -> Option<String> {
+    let relative = xdg::data_home()
+        .into_iter()
+        .chain(origin::data_dirs())
+        .find_map(|dir| path.strip_prefix(dir.join("applications")).ok());
+    match relative {
+        Some(relative) if relative.components().count() > 1 => Some(
+            relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("-"),
+        ),
+        _ => path.file_name().map(|n| n.to_string_lossy().to_string()),
+    }
+}
+
+/// Suffix of `.directory` files, which name menus rather than launchers.
+pub const DIRECTORY_SUFFIX: &str = ".directory";
+
+/// IDs and paths of the `.desktop` files under an `applications` directory.
+/// Subdirectory names become part of the ID: `kde/foo.desktop` is
+/// `kde-foo.desktop`.
+pub fn files_in(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut files = Vec::new();
+    collect_files(dir, "", &mut files);
+    files.sort();
+    files
+}
+
+fn collect_files(dir: &Path, prefix: &str, files: &mut Vec<(String, PathBuf)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_files(&path, &format!("{prefix}{name}-"), files);
+        } else if name.ends_with(".desktop") {
+            files.push((format!("{prefix}{name}"), path));
+        }
+    }
+}
+
+/// Adds the `.desktop` suffix if neither it nor `.directory` is present.
+pub fn normalize(id: &str) -> String {
+    let id = id.trim();
+    if id.ends_with(".desktop") || id.ends_with(DIRECTORY_SUFFIX) {
+        id.to_string()
+    } else {
+        format!("{id}.desktop")
+    }
+}
+
+/// File name for an entry of `entry_type`: `Directory` entries use the
+/// `.directory` suffix, all others `.desktop`.
+pub fn normalize_for(id: &str, entry_type: &str) -> String {
+    let id = id.trim();
+    let stem = id
+        .strip_suffix(".desktop")
+        .or_else(|| id.strip_suffix(DIRECTORY_SUFFIX))
+        .unwrap_or(id);
+    if entry_type == "Directory" {
+        format!("{stem}{DIRECTORY_SUFFIX}")
+    } else {
+        format!("{stem}.desktop")
+    }
+}
+
+/// The user directory files with this ID are saved in:
+/// `desktop-directories` for `.directory` files, `applications` otherwise.
+pub fn user_dir(id: &str) -> Option<PathBuf> {
+    let subdir = if id.ends_with(DIRECTORY_SUFFIX) {
+        "desktop-directories"
+    } else {
+        "applications"
+    };
+    xdg::data_home().map(|d| d.join(subdir))
+}
+
+/// Checks that `id` only uses characters allowed in desktop file IDs and, for
+/// reverse-DNS IDs, that no element is empty or starts with a digit.
+pub fn validate(id: &str) -> Result<(), String> {
+    let Some(stem) = id
+        .strip_suffix(".desktop")
+        .or_else(|| id.strip_suffix(DIRECTORY_SUFFIX))
+    else {
+        return Err("The ID must end with .desktop or .directory".to_string());
+    };
+    if stem.is_empty() {
+        return Err("The ID is empty".to_string());
+    }
+    if let Some(c) = stem
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+    {
+        return Err(format!("'{c}' is not allowed in a desktop file ID"));
+    }
+    if stem.contains('.') {
+        for element in stem.split('.') {
+            if element.is_empty() {
+                return Err("Reverse-DNS elements must not be empty".to_string());
+            }
+            if element.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(format!(
+                    "Reverse-DNS element '{element}' must not start with a digit"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// IDs and paths of every entry in the `applications` and
+/// `desktop-directories` directories of the XDG data directories.
+fn existing() -> Vec<(String, String)> {
+    let launchers = xdg::data_home()
+        .into_iter()
+        .chain(origin::data_dirs())
+        .flat_map(|dir| files_in(&dir.join("applications")))
+        .map(|(id, path)| (id, path.to_string_lossy().to_string()));
+    let directories = get_directory_file_paths()
+        .into_iter()
+        .filter_map(|path| Some((from_path(Path::new(&path))?, path)));
+    launchers.chain(directories).collect()
+}
+
+/// Paths of every discovered entry with this desktop file ID.
+pub fn find_existing(id: &str) -> Vec<String> {
+    existing()
+        .into_iter()
+        .filter(|(existing_id, _)| existing_id == id)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// IDs of every discovered entry, to check candidates against without
+/// scanning the directories again for each.
+pub fn taken_ids() -> HashSet<String> {
+    existing().into_iter().map(|(id, _)| id).collect()
+}
+
+/// Candidate IDs for an entry called `name`: a plain kebab-case ID first,
+/// followed by reverse-DNS variants. None of the candidates is in `taken`.
+pub fn suggestions(name: &str, taken: &HashSet<String>) -> Vec<String> {
+    let words: Vec<String> = ascii_fold(name)
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_string())
+        .collect();
+
+    let kebab = if words.is_empty() {
+        "application".to_string()
+    } else {
+        words.join("-").to_lowercase()
+    };
+    let mut camel: String = words
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if camel.is_empty() || camel.starts_with(|c: char| c.is_ascii_digit()) {
+        camel = format!("App{camel}");
+    }
+    let user: String = env::var("USER")
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    let user = if user.is_empty() || user.starts_with(|c: char| c.is_ascii_digit()) {
+        "user".to_string()
+    } else {
+        user
+    };
+
+    [
+        format!("{kebab}.desktop"),
+        format!("local.{user}.{camel}.desktop"),
+        format!("io.github.{user}.{camel}.desktop"),
+        format!("org.example.{camel}.desktop"),
+    ]
+    .iter()
+    .map(|id| unique(id, taken))
+    .collect()
+}
+
+/// Returns `id`, or `id` with a numeric suffix if that ID is in `taken`.
+pub fn unique(id: &str, taken: &HashSet<String>) -> String {
+    let stem = id.strip_suffix(".desktop").unwrap_or(id);
+    let mut candidate = normalize(stem);
+    let mut n = 2;
+    while taken.contains(&candidate) {
+        candidate = format!("{stem}-{n}.desktop");
+        n += 1;
+    }
+    candidate
+}
+
+/// An ID outside `taken` for a copy of the entry `id`: `id` with the next
+/// free numeric suffix, which replaces a suffix the ID already has, so
+/// copying `foo-2.desktop` gives `foo-3.desktop` rather than `foo-2-2.desktop`.
+pub fn for_copy(id: &str, taken: &HashSet<String>) -> String {
+    let id = normalize(id);
+    let suffix = if id.ends_with(DIRECTORY_SUFFIX) {
+        DIRECTORY_SUFFIX
+    } else {
+        ".desktop"
+    };
+    let stem = id.strip_suffix(suffix).unwrap_or(&id);
+    let base = match stem.rsplit_once('-') {
+        Some((base, n))
+            if !base.is_empty() && !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => stem,
+    };
+    (2..)
+        .map(|n| format!("{base}-{n}{suffix}"))
+        .find(|candidate| *candidate != id && !taken.contains(candidate))
+        .unwrap_or(id.clone())
+}
+
+/// Renames a user launcher or `.directory` file to `new_id` and updates
+/// everything that refers to it by ID: `mimeapps.list`, the autostart copy
+/// and GNOME Shell favorites.
+pub fn rename(old_path: &Path, new_id: &str) -> Result<PathBuf, DesktopFileError> {
+    validate(new_id).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+    let user_apps = user_dir(new_id).ok_or_else(|| io::Error::other("No home directory"))?;
+    if old_path.parent() != Some(user_apps.as_path()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is not a user launcher and cannot be renamed",
+                old_path.display()
+            ),
+        )
+        .into());
+    }
+
+    let old_id = from_path(old_path).unwrap_or_default();
+    let new_path = user_apps.join(new_id);
+    if new_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", new_path.display()),
+        )
+        .into());
+    }
+
+    fs::rename(old_path, &new_path)?;
+
+    if let Some(config) = xdg::config_home() {
+        let mimeapps = [
+            config.join("mimeapps.list"),
+            user_apps.join("mimeapps.list"),
+        ];
+        for list in mimeapps.iter().filter(|p| p.exists()) {
+            let result = MimeApps::load(list).and_then(|mut apps| {
+                if apps.replace_id(&old_id, new_id) {
+                    apps.save()?;
+                }
+                Ok(())
+            });
+            if let Err(e) = result {
+                logging::warn(
+                    "rename",
+                    format!("Could not update {}: {e}", list.display()),
+                );
+            }
+        }
+
+        let autostart = config.join("autostart");
+        let old_autostart = autostart.join(&old_id);
+        let new_autostart = autostart.join(new_id);
+        if old_autostart.exists() && !new_autostart.exists() {
+            if let Err(e) = fs::rename(&old_autostart, &new_autostart) {
+                logging::warn(
+                    "rename",
+                    format!("Could not rename the autostart entry: {e}"),
+                );
+            }
+        }
+    }
+
+    update_gnome_favorites(&old_id, new_id);
+
+    Ok(new_path)
+}
+
+fn update_gnome_favorites(old_id: &str, new_id: &str) {
+    let Ok(output) = Command::new("gsettings")
+        .args(["get", "org.gnome.shell", "favorite-apps"])
+        .output()
+    else {
+        return;
+    };
+    if !output.status.success() {
+        return;
+    }
+
+    let favorites = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let quoted_old = format!("'{old_id}'");
+    if !favorites.contains(&quoted_old) {
+        return;
+    }
+
+    let updated = favorites.replace(&quoted_old, &format!("'{new_id}'"));
+    if let Err(e) = Command::new("gsettings")
+        .args(["set", "org.gnome.shell", "favorite-apps", &updated])
+        .status()
+    {
+        logging::warn("rename", format!("Could not update the favorites: {e}"));
+    }
+}
+
+/// Replaces common accented Latin letters with their ASCII base letter.
+fn ascii_fold(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+            'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+            'ç' => 'c',
+            'Ç' => 'C',
+            'è' | 'é' | 'ê' | 'ë' => 'e',
+            'È' | 'É' | 'Ê' | 'Ë' => 'E',
+            'ì' | 'í' | 'î' | 'ï' => 'i',
+            'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+            'ñ' => 'n',
+            'Ñ' => 'N',
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+            'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'O',
+            'ù' | 'ú' | 'û' | 'ü' => 'u',
+            'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+            'ý' | 'ÿ' => 'y',
+            'Ý' => 'Y',
+            'ß' => 's',
+            _ => c,
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+use gtk4 as gtk;
+use libadwaita::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::exec::{self, EnvPrefix};
+use crate::key_table::{KeyTable, Labels};
+
+/// Edits the variables of the `env NAME=value ...` prefix of an `Exec` value
+/// as a table, keeping the prefix of `exec_entry` in sync with it.
+#[derive(Clone)]
+pub struct EnvEditor {
+    pub widget: gtk::Box,
+    table: KeyTable,
+    status: gtk::Label,
+    exec_entry: gtk::Entry,
+    /// Set while the editor writes `exec_entry`, so the change is not read
+    /// back into the table that is being typed in.
+    writing: Rc<Cell<bool>>,
+}
+
+impl EnvEditor {
+    pub fn new(exec_entry: &gtk::Entry) -> Self {
+        let table = KeyTable::with_labels(Labels {
+            add: "Add Variable",
+            remove: "Remove Variable",
+            key_placeholder: "Name",
+            new_key: "",
+        });
+
+        let status = gtk::Label::new(None);
+        status.add_css_class("warning");
+        status.set_wrap(true);
+        status.set_halign(gtk::Align::Start);
+        status.set_visible(false);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&table.widget);
+        widget.append(&status);
+
+        let editor = Self {
+            widget,
+            table,
+            status,
+            exec_entry: exec_entry.clone(),
+            writing: Rc::new(Cell::new(false)),
+        };
+
+        {
+            let editor = editor.clone();
+            exec_entry.connect_changed(move |_| {
+                if !editor.writing.get() {
+                    editor.load();
+                }
+            });
+        }
+
+        {
+            let editor_clone = editor.clone();
+            editor
+                .table
+                .connect_changed(move |vars| editor_clone.store(vars));
+        }
+
+        editor.load();
+        editor
+    }
+
+    /// Shows the variables of the current `Exec` value.
+    fn load(&self) {
+        match EnvPrefix::parse(&self.exec_entry.text()) {
+            Ok(prefix) => {
+                self.table.set_keys(&prefix.vars);
+                self.table.widget.set_sensitive(true);
+                self.set_status(None);
+            }
+            Err(e) => {
+                self.table.set_keys(&[]);
+                self.table.widget.set_sensitive(false);
+                self.set_status(Some(&format!("{e}; edit Exec directly")));
+            }
+        }
+    }
+
+    /// Writes `vars` into the prefix of `Exec`, leaving the command as it is.
+    fn store(&self, vars: Vec<(String, String)>) {
+        let Ok(mut prefix) = EnvPrefix::parse(&self.exec_entry.text()) else {
+            return;
+        };
+
+        let (valid, invalid): (Vec<_>, Vec<_>) = vars
+            .into_iter()
+            .partition(|(name, _)| exec::is_env_name(name));
+        match invalid.first() {
+            Some((name, _)) => self.set_status(Some(&format!(
+                "'{name}' is not a valid variable name and is not saved"
+            ))),
+            None => self.set_status(None),
+        }
+
+        prefix.vars = valid;
+        self.writing.set(true);
+        self.exec_entry.set_text(&prefix.to_exec());
+        self.writing.set(false);
+    }
+
+    fn set_status(&self, message: Option<&str>) {
+        self.status.set_text(message.unwrap_or_default());
+        self.status.set_visible(message.is_some());
+    }
+}
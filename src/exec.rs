@@ -0,0 +1,290 @@
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Characters that force an `Exec` argument to be quoted.
+const RESERVED_CHARS: &[char] = &[
+    ' ', '\t', '\n', '"', '\'', '\\', '>', '<', '~', '|', '&', ';', '$', '*', '?', '#', '(', ')',
+    '`',
+];
+
+/// Quotes a single argument for use in an `Exec` key, following the rules of
+/// the Desktop Entry Specification. The result is the text as written in the
+/// file, i.e. with the key-file level backslash escaping already applied.
+pub fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains(RESERVED_CHARS) {
+        return arg.replace('%', "%%");
+    }
+
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            '"' | '`' | '$' => {
+                quoted.push_str("\\\\");
+                quoted.push(c);
+            }
+            '\\' => quoted.push_str("\\\\\\\\"),
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Field codes (`%f`, `%U`, ...) used in an `Exec` value, in order. `%%` is a
+/// literal percent sign and not reported.
+pub fn field_codes(exec: &str) -> Vec<char> {
+    let mut codes = Vec::new();
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            match chars.next() {
+                Some('%') | None => {}
+                Some(code) => codes.push(code),
+            }
+        }
+    }
+    codes
+}
+
+/// Locates `program` the way a shell would: names containing a `/` are used
+/// as they are, others are looked up in `$PATH`. Only executable files count.
+pub fn find_program(program: &str) -> Option<PathBuf> {
+    let is_executable = |path: &Path| {
+        fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    };
+
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return is_executable(&path).then_some(path);
+    }
+    if program.is_empty() {
+        return None;
+    }
+    let path_var = env::var_os("PATH")?;
+    env::split_paths(&path_var)
+        .map(|dir| dir.join(program))
+        .find(|path| is_executable(path))
+}
+
+/// Undoes the escapes of the key file format (`\s`, `\n`, `\t`, `\r`, `\\`).
+/// Each character comes with the byte offset in `value` it was written at.
+fn unescape_value(value: &str) -> Vec<(char, usize)> {
+    let mut result = Vec::with_capacity(value.len());
+    let mut chars = value.char_indices();
+    while let Some((offset, c)) = chars.next() {
+        if c != '\\' {
+            result.push((c, offset));
+            continue;
+        }
+        match chars.next() {
+            Some((_, 's')) => result.push((' ', offset)),
+            Some((_, 'n')) => result.push(('\n', offset)),
+            Some((_, 't')) => result.push(('\t', offset)),
+            Some((_, 'r')) => result.push(('\r', offset)),
+            Some((_, '\\')) => result.push(('\\', offset)),
+            Some((other_offset, other)) => {
+                result.push(('\\', offset));
+                result.push((other, other_offset));
+            }
+            None => result.push(('\\', offset)),
+        }
+    }
+    result
+}
+
+/// An argument of an `Exec` value and the byte range it covers in the
+/// value as written, quotes included.
+pub struct Token {
+    pub arg: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Like `split_args`, but also says where each argument was written, so
+/// parts of the value can be replaced without requoting the rest.
+pub fn tokenize(exec: &str) -> Result<Vec<Token>, String> {
+    let value = unescape_value(exec);
+    let mut tokens = Vec::new();
+    let mut current: Option<Token> = None;
+    let mut i = 0;
+    while i < value.len() {
+        let (c, offset) = value[i];
+        i += 1;
+        match c {
+            ' ' | '\t' | '\n' => {
+                if let Some(mut token) = current.take() {
+                    token.end = offset;
+                    tokens.push(token);
+                }
+            }
+            '"' => {
+                let token = current.get_or_insert_with(|| Token {
+                    arg: String::new(),
+                    start: offset,
+                    end: offset,
+                });
+                loop {
+                    let Some(&(c, _)) = value.get(i) else {
+                        return Err("Unterminated quoted argument".to_string());
+                    };
+                    i += 1;
+                    match c {
+                        '"' => break,
+                        '\\' => {
+                            let Some(&(e, _)) = value.get(i) else {
+                                return Err("Unterminated quoted argument".to_string());
+                            };
+                            i += 1;
+                            if !matches!(e, '"' | '`' | '$' | '\\') {
+                                token.arg.push('\\');
+                            }
+                            token.arg.push(e);
+                        }
+                        c => token.arg.push(c),
+                    }
+                }
+            }
+            _ => {
+                current
+                    .get_or_insert_with(|| Token {
+                        arg: String::new(),
+                        start: offset,
+                        end: offset,
+                    })
+                    .arg
+                    .push(c);
+            }
+        }
+    }
+    if let Some(mut token) = current {
+        token.end = exec.len();
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+/// Splits an `Exec` value as written in the file into its arguments, undoing
+/// the key file escapes and the quoting rules of the specification. Percent
+/// signs are left as written, so field codes and `%%` stay recognizable.
+pub fn split_args(exec: &str) -> Result<Vec<String>, String> {
+    Ok(tokenize(exec)?.into_iter().map(|token| token.arg).collect())
+}
+
+fn is_env(arg: &str) -> bool {
+    arg == "env" || arg.ends_with("/env")
+}
+
+/// Whether `name` can be set through `env NAME=value`.
+pub fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// An `Exec` value taken apart into the variables an `env` prefix sets and
+/// the command that follows it.
+#[derive(Debug, Clone, Default)]
+pub struct EnvPrefix {
+    /// The `env` program as written, e.g. `env` or `/usr/bin/env`.
+    program: Option<String>,
+    pub vars: Vec<(String, String)>,
+    /// The rest of the value, exactly as written.
+    pub command: String,
+}
+
+impl EnvPrefix {
+    /// Reads the `NAME=value` assignments of a leading `env`. Values without
+    /// such a prefix have no variables. Options of `env` are not supported,
+    /// as reordering them around the assignments would change their meaning.
+    pub fn parse(exec: &str) -> Result<Self, String> {
+        let tokens = tokenize(exec)?;
+        let Some(first) = tokens.first().filter(|token| is_env(&token.arg)) else {
+            return Ok(Self {
+                program: None,
+                vars: Vec::new(),
+                command: exec.trim().to_string(),
+            });
+        };
+
+        let mut vars = Vec::new();
+        let mut command_start = exec.len();
+        for token in &tokens[1..] {
+            if token.arg.starts_with('-') {
+                return Err(format!("env option '{}' is not supported", token.arg));
+            }
+            match token.arg.split_once('=') {
+                Some((name, value)) if is_env_name(name) => {
+                    vars.push((name.to_string(), value.replace("%%", "%")));
+                }
+                _ => {
+                    command_start = token.start;
+                    break;
+                }
+            }
+        }
+
+        Ok(Self {
+            program: Some(exec[first.start..first.end].to_string()),
+            vars,
+            command: exec[command_start..].trim_end().to_string(),
+        })
+    }
+
+    /// Writes the value back, with an `env` prefix only when variables are
+    /// set.
+    pub fn to_exec(&self) -> String {
+        if self.vars.is_empty() {
+            return self.command.clone();
+        }
+        let mut exec = self.program.clone().unwrap_or_else(|| "env".to_string());
+        for (name, value) in &self.vars {
+            exec.push(' ');
+            exec.push_str(&quote_arg(&format!("{name}={value}")));
+        }
+        if !self.command.is_empty() {
+            exec.push(' ');
+            exec.push_str(&self.command);
+        }
+        exec
+    }
+}
+
+/// The program an `Exec` value runs. A leading `env` with its options and
+/// `NAME=value` assignments is skipped, since it only sets up the
+/// environment for the actual program.
+pub fn program(exec: &str) -> Option<String> {
+    let args = split_args(exec).ok()?;
+    let mut args = args.into_iter().peekable();
+    if args.peek().is_some_and(|first| is_env(first)) {
+        args.next();
+        while let Some(arg) = args.next_if(|arg| arg.starts_with('-') || arg.contains('=')) {
+            // Options whose value is the next argument
+            if matches!(arg.as_str(), "-u" | "--unset" | "-C" | "--chdir") {
+                args.next();
+            }
+        }
+    }
+    args.next()
+}
+
+/// Like `find_program`, but says why the program could not be used.
+pub fn resolve(program: &str) -> Result<PathBuf, String> {
+    if let Some(path) = find_program(program) {
+        return Ok(path);
+    }
+    if program.contains('/') {
+        if Path::new(program).is_file() {
+            Err(format!("'{program}' is not executable"))
+        } else {
+            Err(format!("'{program}' does not exist"))
+        }
+    } else {
+        Err(format!("'{program}' was not found in $PATH"))
+    }
+}
@@ -0,0 +1,130 @@
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::io::ErrorKind;
+
+use crate::desktop_file::DesktopFileError;
+use crate::logging;
+
+/// Opens the log viewer; offered on error toasts.
+pub const SHOW_LOG_ACTION: &str = "app.show-log";
+
+/// Shows the outcome of user actions as toasts and records it in the log.
+#[derive(Clone)]
+pub struct Feedback {
+    overlay: adw::ToastOverlay,
+}
+
+impl Feedback {
+    pub fn new(overlay: &adw::ToastOverlay) -> Self {
+        // Toast buttons run application actions from any window, including
+        // those not attached to the application
+        if let Some(app) = gio::Application::default() {
+            overlay.insert_action_group("app", Some(&app));
+        }
+        Self {
+            overlay: overlay.clone(),
+        }
+    }
+
+    /// Reports an action that completed.
+    pub fn success(&self, source: &str, message: impl Into<String>) {
+        let message = message.into();
+        logging::info(source, message.as_str());
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(3);
+        self.overlay.add_toast(toast);
+    }
+
+    /// Reports a completed action with a button that runs the detailed
+    /// action name `action`, e.g. to undo it.
+    pub fn success_with_action(
+        &self,
+        source: &str,
+        message: impl Into<String>,
+        button_label: &str,
+        action: &str,
+    ) {
+        let message = message.into();
+        logging::info(source, message.as_str());
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(8);
+        toast.set_button_label(Some(button_label));
+        toast.set_detailed_action_name(Some(action));
+        self.overlay.add_toast(toast);
+    }
+
+    /// Reports input that cannot be used, e.g. an invalid ID; the message
+    /// says how to correct it.
+    pub fn warning(&self, source: &str, message: impl Into<String>) {
+        let message = message.into();
+        logging::warn(source, message.as_str());
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(5);
+        self.overlay.add_toast(toast);
+    }
+
+    /// Reports a failed action. `summary` says what failed, e.g.
+    /// "Could not save foo.desktop"; the error is explained after it.
+    pub fn error(&self, source: &str, summary: &str, error: &DesktopFileError) {
+        self.problem(source, format!("{summary}: {}", explain(error)));
+    }
+
+    /// Reports a problem that kept an action from completing, with a button
+    /// opening the log.
+    pub fn problem(&self, source: &str, message: impl Into<String>) {
+        self.problem_with_action(source, message, "Details", SHOW_LOG_ACTION);
+    }
+
+    /// Reports a problem with a button that runs the detailed action name
+    /// `action`, e.g. to retry in a way that can succeed.
+    pub fn problem_with_action(
+        &self,
+        source: &str,
+        message: impl Into<String>,
+        button_label: &str,
+        action: &str,
+    ) {
+        let message = message.into();
+        logging::error(source, message.as_str());
+        let toast = adw::Toast::new(&message);
+        toast.set_priority(adw::ToastPriority::High);
+        toast.set_timeout(8);
+        toast.set_button_label(Some(button_label));
+        toast.set_detailed_action_name(Some(action));
+        self.overlay.add_toast(toast);
+    }
+}
+
+/// Explains an error in terms of what the user can do about it.
+pub fn explain(error: &DesktopFileError) -> String {
+    match error {
+        DesktopFileError::IoError(e) => match e.kind() {
+            ErrorKind::PermissionDenied => "permission denied".to_string(),
+            ErrorKind::NotFound => "the file or its folder no longer exists".to_string(),
+            ErrorKind::AlreadyExists => "a file with that name already exists".to_string(),
+            ErrorKind::ReadOnlyFilesystem => "the file system is read-only".to_string(),
+            ErrorKind::StorageFull => "the disk is full".to_string(),
+            _ => e.to_string(),
+        },
+        DesktopFileError::ParseError {
+            line,
+            column,
+            message,
+            ..
+        } => format!("line {line}, column {column}: {message}; see the Source tab"),
+        DesktopFileError::MissingField(field) => format!("{field} is required"),
+        DesktopFileError::InvalidValue(field, value) => {
+            format!("'{value}' is not a valid value for {field}")
+        }
+    }
+}
+
+/// Whether an error means the file cannot be written by the user, so saving
+/// a user override is the way out.
+pub fn is_not_writable(error: &DesktopFileError) -> bool {
+    matches!(
+        error,
+        DesktopFileError::IoError(e)
+            if matches!(e.kind(), ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem)
+    )
+}
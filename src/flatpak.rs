@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+
+use crate::exec::{self, Token};
+use crate::xdg;
+
+/// Where Flatpak exports the desktop files of installed applications. They
+/// are rewritten whenever the application is updated.
+const SYSTEM_EXPORTS: &str = "/var/lib/flatpak/exports/share";
+
+/// Marker tokens of `--file-forwarding`: `@@u` starts URIs, `@@` files, and
+/// a second `@@` ends either.
+const FORWARD_URIS: &str = "@@u";
+const FORWARD_FILES: &str = "@@";
+
+/// Options of `flatpak run` that take a value, which may also follow as the
+/// next argument, e.g. `--branch stable`.
+const VALUE_OPTIONS: &[&str] = &[
+    "--arch",
+    "--branch",
+    "--command",
+    "--commit",
+    "--cwd",
+    "--runtime",
+    "--runtime-commit",
+    "--runtime-version",
+    "--share",
+    "--unshare",
+    "--socket",
+    "--nosocket",
+    "--device",
+    "--nodevice",
+    "--allow",
+    "--disallow",
+    "--filesystem",
+    "--nofilesystem",
+    "--env",
+    "--unset-env",
+    "--own-name",
+    "--talk-name",
+    "--no-talk-name",
+    "--system-own-name",
+    "--system-talk-name",
+    "--system-no-talk-name",
+    "--add-policy",
+    "--remove-policy",
+    "--persist",
+];
+
+/// The `share` directories Flatpak exports to, user installation first, in
+/// the order Flatpak adds them to `$XDG_DATA_DIRS`.
+pub fn export_dirs() -> Vec<PathBuf> {
+    xdg::data_home()
+        .map(|dir| dir.join("flatpak/exports/share"))
+        .into_iter()
+        .chain([PathBuf::from(SYSTEM_EXPORTS)])
+        .collect()
+}
+
+/// `$XDG_DATA_DIRS` with the Flatpak export directories it lacks, e.g. when
+/// the session was not started with Flatpak's profile script.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = xdg::data_dirs();
+    for dir in export_dirs() {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// Whether `path` was exported by Flatpak, so changes to it are lost with
+/// the next `flatpak update`.
+pub fn is_export(path: &Path) -> bool {
+    export_dirs().iter().any(|dir| path.starts_with(dir))
+}
+
+/// The position of the application ID in the arguments of `flatpak run`.
+pub fn app_id_index(args: &[String]) -> Option<usize> {
+    let program = args.first()?;
+    if program != "flatpak" && !program.ends_with("/flatpak") {
+        return None;
+    }
+    if args.get(1).map(String::as_str) != Some("run") {
+        return None;
+    }
+    let mut i = 2;
+    while let Some(arg) = args.get(i) {
+        if !arg.starts_with('-') {
+            return Some(i);
+        }
+        // Skip the value of options written as two arguments
+        i += if VALUE_OPTIONS.contains(&arg.as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    None
+}
+
+/// An `Exec` value running a Flatpak application, taken apart into the
+/// options of `flatpak run` and the arguments of the application.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlatpakCommand {
+    /// `flatpak` as written, usually `/usr/bin/flatpak`.
+    program: String,
+    pub app_id: String,
+    /// `--command`, the program to run inside the sandbox.
+    pub command: String,
+    /// `--branch`.
+    pub branch: String,
+    /// `--arch`.
+    pub arch: String,
+    /// Whether `--file-forwarding` passes the files of field codes into the
+    /// sandbox, marked with `@@u`/`@@` around them.
+    pub file_forwarding: bool,
+    /// Other options of `flatpak run`, as written.
+    pub options: String,
+    /// Arguments of the application as written, without forwarding markers.
+    pub args: String,
+}
+
+impl FlatpakCommand {
+    /// Reads a `flatpak run` command. Returns `None` for other commands.
+    pub fn parse(exec: &str) -> Option<Self> {
+        let tokens = exec::tokenize(exec).ok()?;
+        let args: Vec<String> = tokens.iter().map(|t| t.arg.clone()).collect();
+        let app_index = app_id_index(&args)?;
+
+        let mut parsed = Self {
+            program: exec[tokens[0].start..tokens[0].end].to_string(),
+            app_id: args[app_index].clone(),
+            ..Self::default()
+        };
+
+        let mut options = Vec::new();
+        let mut option_tokens = tokens[2..app_index].iter();
+        while let Some(token) = option_tokens.next() {
+            let (name, value) = match token.arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (token.arg.as_str(), None),
+            };
+            let field = match name {
+                "--command" => Some(&mut parsed.command),
+                "--branch" => Some(&mut parsed.branch),
+                "--arch" => Some(&mut parsed.arch),
+                _ => None,
+            };
+            if let Some(field) = field {
+                // `app_id_index` has made sure a separate value follows
+                *field = value
+                    .or_else(|| option_tokens.next().map(|t| t.arg.clone()))
+                    .unwrap_or_default();
+            } else if token.arg == "--file-forwarding" {
+                parsed.file_forwarding = true;
+            } else if value.is_none() && VALUE_OPTIONS.contains(&name) {
+                let end = option_tokens.next().map_or(token.end, |t| t.end);
+                options.push(&exec[token.start..end]);
+            } else {
+                options.push(&exec[token.start..token.end]);
+            }
+        }
+        parsed.options = options.join(" ");
+        parsed.args = without_markers(exec, &tokens[app_index + 1..]);
+        Some(parsed)
+    }
+
+    /// Writes the command back. With file forwarding, each file or URL field
+    /// code of the arguments is wrapped in markers.
+    pub fn to_exec(&self) -> String {
+        let mut parts = vec![self.program.clone(), "run".to_string()];
+        for (option, value) in [
+            ("--branch=", &self.branch),
+            ("--arch=", &self.arch),
+            ("--command=", &self.command),
+        ] {
+            if !value.is_empty() {
+                parts.push(exec::quote_arg(&format!("{option}{value}")));
+            }
+        }
+        if self.file_forwarding {
+            parts.push("--file-forwarding".to_string());
+        }
+        if !self.options.trim().is_empty() {
+            parts.push(self.options.trim().to_string());
+        }
+        parts.push(exec::quote_arg(&self.app_id));
+
+        let mut last = 0;
+        let mut args = String::new();
+        if self.file_forwarding {
+            for token in exec::tokenize(&self.args).unwrap_or_default() {
+                let marker = match token.arg.as_str() {
+                    "%u" | "%U" => FORWARD_URIS,
+                    "%f" | "%F" => FORWARD_FILES,
+                    _ => continue,
+                };
+                args.push_str(&self.args[last..token.start]);
+                args.push_str(&format!("{marker} {} {FORWARD_FILES}", token.arg));
+                last = token.end;
+            }
+        }
+        args.push_str(&self.args[last..]);
+        if !args.trim().is_empty() {
+            parts.push(args.trim().to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+/// The arguments covered by `tokens` as written, with the marker tokens of
+/// file forwarding removed.
+fn without_markers(exec: &str, tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .filter(|token| token.arg != FORWARD_URIS && token.arg != FORWARD_FILES)
+        .map(|token| &exec[token.start..token.end])
+        .collect::<Vec<_>>()
+        .join(" ")
+}
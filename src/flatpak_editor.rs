@@ -0,0 +1,209 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::exec::{self, EnvPrefix};
+use crate::flatpak::{self, FlatpakCommand};
+use crate::launch_options::LaunchOptions;
+
+/// Fields for the parts of a `flatpak run` command in `exec_entry`, inside
+/// any `env` prefix and launch wrappers. Only shown for such commands.
+#[derive(Clone)]
+pub struct FlatpakEditor {
+    pub widget: adw::PreferencesGroup,
+    app_id_entry: gtk::Entry,
+    command_entry: gtk::Entry,
+    branch_entry: gtk::Entry,
+    arch_entry: gtk::Entry,
+    forwarding_switch: gtk::Switch,
+    options_entry: gtk::Entry,
+    args_entry: gtk::Entry,
+    exec_entry: gtk::Entry,
+    /// Set while the editor writes `exec_entry`.
+    writing: Rc<Cell<bool>>,
+    /// Set while the fields are updated from `exec_entry`.
+    loading: Rc<Cell<bool>>,
+}
+
+impl FlatpakEditor {
+    pub fn new(exec_entry: &gtk::Entry) -> Self {
+        let widget = adw::PreferencesGroup::new();
+        widget.set_title("Flatpak");
+        widget.set_description(Some(
+            "How flatpak run starts the application. Exported entries are replaced on \
+             every update, so saving one creates a user override instead",
+        ));
+
+        let new_entry = |placeholder: &str| {
+            let entry = gtk::Entry::new();
+            entry.set_placeholder_text(Some(placeholder));
+            entry.set_valign(gtk::Align::Center);
+            entry
+        };
+        let app_id_entry = new_entry("e.g. org.mozilla.firefox");
+        let command_entry = new_entry("Default command of the application");
+        let branch_entry = new_entry("e.g. stable");
+        let arch_entry = new_entry("e.g. x86_64");
+        let options_entry = new_entry("e.g. --env=GDK_SCALE=2 --filesystem=home");
+        let args_entry = new_entry("e.g. %U");
+        let forwarding_switch = gtk::Switch::new();
+        forwarding_switch.set_valign(gtk::Align::Center);
+
+        let add_row = |title: &str, subtitle: &str, suffix: &gtk::Widget| {
+            let row = adw::ActionRow::new();
+            row.set_title(title);
+            row.set_subtitle(&glib::markup_escape_text(subtitle));
+            row.add_suffix(suffix);
+            widget.add(&row);
+        };
+        add_row("Application ID", "", app_id_entry.upcast_ref());
+        add_row(
+            "Command",
+            "--command, the program run in the sandbox",
+            command_entry.upcast_ref(),
+        );
+        add_row("Branch", "--branch", branch_entry.upcast_ref());
+        add_row("Architecture", "--arch", arch_entry.upcast_ref());
+        add_row(
+            "Forward Files",
+            "--file-forwarding, which opens the files and URLs of %f, %F, %u and %U \
+             to the sandbox by marking them with @@ or @@u",
+            forwarding_switch.upcast_ref(),
+        );
+        add_row(
+            "Other Options",
+            "More options of flatpak run, quoted as in Exec",
+            options_entry.upcast_ref(),
+        );
+        add_row(
+            "Arguments",
+            "Passed to the application, quoted as in Exec",
+            args_entry.upcast_ref(),
+        );
+
+        let editor = Self {
+            widget,
+            app_id_entry,
+            command_entry,
+            branch_entry,
+            arch_entry,
+            forwarding_switch,
+            options_entry,
+            args_entry,
+            exec_entry: exec_entry.clone(),
+            writing: Rc::new(Cell::new(false)),
+            loading: Rc::new(Cell::new(false)),
+        };
+
+        {
+            let editor = editor.clone();
+            exec_entry.connect_changed(move |_| {
+                if !editor.writing.get() {
+                    editor.load();
+                }
+            });
+        }
+
+        for entry in [
+            &editor.app_id_entry,
+            &editor.command_entry,
+            &editor.branch_entry,
+            &editor.arch_entry,
+            &editor.options_entry,
+            &editor.args_entry,
+        ] {
+            let editor = editor.clone();
+            entry.connect_changed(move |_| editor.changed());
+        }
+        {
+            let editor_clone = editor.clone();
+            editor
+                .forwarding_switch
+                .connect_active_notify(move |_| editor_clone.changed());
+        }
+
+        editor.load();
+        editor
+    }
+
+    /// Whether `Exec` runs a Flatpak application.
+    pub fn is_flatpak(&self) -> bool {
+        self.parse().is_some()
+    }
+
+    fn parse(&self) -> Option<(EnvPrefix, LaunchOptions, FlatpakCommand)> {
+        let prefix = EnvPrefix::parse(&self.exec_entry.text()).ok()?;
+        let options = LaunchOptions::parse(&prefix.command).ok()?;
+        let command = FlatpakCommand::parse(&options.command)?;
+        Some((prefix, options, command))
+    }
+
+    fn load(&self) {
+        let parsed = self.parse();
+        self.widget.set_visible(parsed.is_some());
+        let Some((_, _, command)) = parsed else {
+            return;
+        };
+
+        self.loading.set(true);
+        for (entry, value) in [
+            (&self.app_id_entry, &command.app_id),
+            (&self.command_entry, &command.command),
+            (&self.branch_entry, &command.branch),
+            (&self.arch_entry, &command.arch),
+            (&self.options_entry, &command.options),
+            (&self.args_entry, &command.args),
+        ] {
+            // Keeps the cursor in place when the value did not change
+            if entry.text() != value.as_str() {
+                entry.set_text(value);
+            }
+        }
+        self.forwarding_switch.set_active(command.file_forwarding);
+        self.loading.set(false);
+    }
+
+    fn changed(&self) {
+        if self.loading.get() {
+            return;
+        }
+        let Some((mut prefix, mut options, mut command)) = self.parse() else {
+            return;
+        };
+        let app_id = self.app_id_entry.text().trim().to_string();
+        if app_id.is_empty() || app_id.starts_with('-') {
+            return;
+        }
+        // Anything but options and their values would be taken for the
+        // application ID
+        let options_text = self.options_entry.text();
+        let Ok(options_args) = exec::split_args(&options_text) else {
+            return;
+        };
+        let probe: Vec<String> = ["flatpak", "run"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(options_args)
+            .chain([app_id.clone()])
+            .collect();
+        if flatpak::app_id_index(&probe) != Some(probe.len() - 1) {
+            return;
+        }
+
+        command.app_id = app_id;
+        command.command = self.command_entry.text().trim().to_string();
+        command.branch = self.branch_entry.text().trim().to_string();
+        command.arch = self.arch_entry.text().trim().to_string();
+        command.file_forwarding = self.forwarding_switch.is_active();
+        command.options = options_text.to_string();
+        command.args = self.args_entry.text().to_string();
+
+        options.command = command.to_exec();
+        prefix.command = options.to_command();
+        self.writing.set(true);
+        self.exec_entry.set_text(&prefix.to_exec());
+        self.writing.set(false);
+    }
+}
@@ -0,0 +1,429 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{get_directory_file_paths, DesktopFile};
+use crate::overrides::{self, Status};
+use crate::{broken_entries, categories, desktop_id, origin, xdg};
+
+/// File extensions icon themes and `pixmaps` use.
+const ICON_EXTENSIONS: &[&str] = &["png", "svg", "svgz", "xpm"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Info => "Info",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IssueKind {
+    /// The file does not follow the key file syntax.
+    Syntax,
+    /// A required key is missing or has an invalid value.
+    Invalid,
+    MissingProgram,
+    MissingIcon,
+    Categories,
+    DuplicateName,
+    /// Several files share a desktop file ID; only the first one is used.
+    DuplicateId,
+    /// A user copy with the same content as the system entry it overrides.
+    StaleOverride,
+    /// A user copy of a system entry that was updated since.
+    OutdatedOverride,
+}
+
+impl IssueKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            IssueKind::Syntax => "Syntax",
+            IssueKind::Invalid => "Invalid",
+            IssueKind::MissingProgram => "Missing program",
+            IssueKind::MissingIcon => "Missing icon",
+            IssueKind::Categories => "Categories",
+            IssueKind::DuplicateName => "Duplicate name",
+            IssueKind::DuplicateId => "Duplicate ID",
+            IssueKind::StaleOverride => "Stale override",
+            IssueKind::OutdatedOverride => "Outdated override",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+    pub id: String,
+    pub path: PathBuf,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    /// Number of files that were checked.
+    pub files: usize,
+    pub issues: Vec<Issue>,
+}
+
+impl Report {
+    pub fn count(&self, severity: Severity) -> usize {
+        self.issues
+            .iter()
+            .filter(|i| i.severity == severity)
+            .count()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// A standalone HTML page with the issues in a table that sorts by the
+    /// clicked column.
+    pub fn to_html(&self) -> String {
+        let mut rows = String::new();
+        for issue in &self.issues {
+            rows.push_str(&format!(
+                "<tr class=\"{}\"><td data-sort=\"{} {}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                html_escape(&format!("{:?}", issue.severity).to_lowercase()),
+                // Severities sort by rank rather than by name, then by path
+                issue.severity as u8,
+                html_escape(&issue.path.display().to_string()),
+                issue.severity.label(),
+                issue.kind.label(),
+                html_escape(&issue.id),
+                html_escape(&issue.message),
+                html_escape(&issue.path.display().to_string()),
+            ));
+        }
+
+        format!(
+            r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Desktop Entry Health Report</title>
+<style>
+body {{ font-family: sans-serif; margin: 2em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border-bottom: 1px solid #ddd; padding: 4px 8px; text-align: left; vertical-align: top; }}
+th {{ cursor: pointer; background: #f4f4f4; }}
+tr.error td:first-child {{ color: #c01c28; font-weight: bold; }}
+tr.warning td:first-child {{ color: #ae7b03; }}
+td:last-child {{ font-family: monospace; font-size: smaller; }}
+</style>
+</head>
+<body>
+<h1>Desktop Entry Health Report</h1>
+<p>{files} files checked: {errors} errors, {warnings} warnings, {infos} notes.</p>
+<table id="issues">
+<thead><tr><th>Severity</th><th>Kind</th><th>ID</th><th>Message</th><th>Path</th></tr></thead>
+<tbody>
+{rows}</tbody>
+</table>
+<script>
+document.querySelectorAll("#issues th").forEach((th, column) => {{
+  th.addEventListener("click", () => {{
+    const body = document.querySelector("#issues tbody");
+    const ascending = th.dataset.order !== "asc";
+    th.dataset.order = ascending ? "asc" : "desc";
+    const rows = Array.from(body.rows);
+    rows.sort((a, b) => {{
+      const key = (cell) => cell.dataset.sort ?? cell.textContent;
+      const result = key(a.cells[column]).localeCompare(key(b.cells[column]));
+      return ascending ? result : -result;
+    }});
+    rows.forEach((row) => body.appendChild(row));
+  }});
+}});
+</script>
+</body>
+</html>
+"##,
+            files = self.files,
+            errors = self.count(Severity::Error),
+            warnings = self.count(Severity::Warning),
+            infos = self.count(Severity::Info),
+        )
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Names of the icons installed in the icon themes and `pixmaps` folders.
+struct IconIndex {
+    names: HashSet<String>,
+}
+
+impl IconIndex {
+    fn load() -> Self {
+        let mut dirs: Vec<PathBuf> = Vec::new();
+        if let Ok(home) = std::env::var("HOME") {
+            dirs.push(Path::new(&home).join(".icons"));
+        }
+        let data_dirs: Vec<PathBuf> = xdg::data_home()
+            .into_iter()
+            .chain(origin::data_dirs())
+            .collect();
+        dirs.extend(data_dirs.iter().map(|d| d.join("icons")));
+        dirs.extend(data_dirs.iter().map(|d| d.join("pixmaps")));
+
+        let mut names = HashSet::new();
+        for dir in dirs {
+            collect_icons(&dir, &mut names);
+        }
+        IconIndex { names }
+    }
+
+    /// Whether an `Icon` value can be shown: an existing file for absolute
+    /// paths, otherwise an icon of that name in any theme.
+    fn contains(&self, icon: &str) -> bool {
+        if icon.starts_with('/') {
+            return Path::new(icon).is_file();
+        }
+        if self.names.contains(icon) {
+            return true;
+        }
+        // Some entries name the file, e.g. `Icon=foo.png`
+        Path::new(icon)
+            .extension()
+            .is_some_and(|ext| ICON_EXTENSIONS.iter().any(|e| ext == *e))
+            && Path::new(icon)
+                .file_stem()
+                .is_some_and(|stem| self.names.contains(stem.to_string_lossy().as_ref()))
+    }
+}
+
+fn collect_icons(dir: &Path, names: &mut HashSet<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_icons(&path, names);
+        } else if path
+            .extension()
+            .is_some_and(|ext| ICON_EXTENSIONS.iter().any(|e| ext == *e))
+        {
+            if let Some(stem) = path.file_stem() {
+                names.insert(stem.to_string_lossy().to_string());
+            }
+        }
+    }
+}
+
+/// Checks every desktop entry in the `applications` folders of the XDG data
+/// directories and every `.directory` file: syntax and required keys, the
+/// programs entries run, icons, categories, entries sharing a name or ID, and
+/// user copies that no longer differ from the system entry.
+pub fn scan() -> Report {
+    let icons = IconIndex::load();
+    let mut issues = Vec::new();
+    let mut files = 0;
+
+    // All files per ID, most important directory first
+    let mut by_id: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for dir in xdg::data_home().into_iter().chain(origin::data_dirs()) {
+        for (id, path) in desktop_id::files_in(&dir.join("applications")) {
+            by_id.entry(id).or_default().push(path);
+        }
+    }
+
+    let mut by_name: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (id, paths) in &by_id {
+        for (index, path) in paths.iter().enumerate() {
+            files += 1;
+            let Some(file) = check_file(id, path, &icons, &mut issues) else {
+                continue;
+            };
+            let entry = &file.desktop_entry;
+            if index == 0
+                && entry.entry_type == "Application"
+                && entry.hidden != Some(true)
+                && entry.no_display != Some(true)
+            {
+                by_name
+                    .entry(entry.name.trim().to_lowercase())
+                    .or_default()
+                    .push(id.clone());
+            }
+        }
+        if paths.len() > 1 {
+            check_override(id, paths, &mut issues);
+        }
+    }
+
+    for ids in by_name.values().filter(|ids| ids.len() > 1) {
+        for id in ids {
+            let others: Vec<&str> = ids
+                .iter()
+                .filter(|other| *other != id)
+                .map(String::as_str)
+                .collect();
+            issues.push(Issue {
+                severity: Severity::Warning,
+                kind: IssueKind::DuplicateName,
+                id: id.clone(),
+                path: by_id[id][0].clone(),
+                message: format!("Has the same name as {}", others.join(", ")),
+            });
+        }
+    }
+
+    for entry in overrides::list() {
+        if entry.status == Status::SystemChanged {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                kind: IssueKind::OutdatedOverride,
+                id: entry.id,
+                path: entry.user_path,
+                message: format!(
+                    "{} was updated since this override was made; merge the changes in User Overrides",
+                    entry.system_path.display()
+                ),
+            });
+        }
+    }
+
+    for path in get_directory_file_paths().into_iter().map(PathBuf::from) {
+        files += 1;
+        let id = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        check_file(&id, &path, &icons, &mut issues);
+    }
+
+    issues.sort_by(|a, b| {
+        (a.severity, a.kind, &a.id, &a.path).cmp(&(b.severity, b.kind, &b.id, &b.path))
+    });
+    Report { files, issues }
+}
+
+/// Checks a single file and returns it when it could be loaded.
+fn check_file(
+    id: &str,
+    path: &Path,
+    icons: &IconIndex,
+    issues: &mut Vec<Issue>,
+) -> Option<DesktopFile> {
+    let mut issue = |severity, kind, message: String| {
+        issues.push(Issue {
+            severity,
+            kind,
+            id: id.to_string(),
+            path: path.to_path_buf(),
+            message,
+        })
+    };
+
+    let content = match fs::read(path).map(String::from_utf8) {
+        Ok(Ok(content)) => content,
+        Ok(Err(_)) => {
+            issue(Severity::Error, IssueKind::Syntax, "Not valid UTF-8".into());
+            return None;
+        }
+        Err(e) => {
+            issue(Severity::Error, IssueKind::Syntax, e.to_string());
+            return None;
+        }
+    };
+    for problem in DesktopFile::diagnostics(&content) {
+        issue(Severity::Error, IssueKind::Syntax, problem.to_string());
+    }
+    let file = DesktopFile::from_string(&content).ok()?;
+    let entry = &file.desktop_entry;
+
+    // A hidden entry only removes the entry it overrides
+    if entry.hidden == Some(true) {
+        return Some(file);
+    }
+
+    if let Err(e) = file.validate() {
+        issue(Severity::Error, IssueKind::Invalid, e.to_string());
+    }
+    for problem in broken_entries::program_problems(&file) {
+        issue(Severity::Error, IssueKind::MissingProgram, problem);
+    }
+    if let Some(icon) = entry.icon.as_deref().map(str::trim) {
+        if !icon.is_empty() && !icons.contains(icon) {
+            issue(
+                Severity::Warning,
+                IssueKind::MissingIcon,
+                format!("Icon '{icon}' is not installed"),
+            );
+        }
+    }
+    if entry.entry_type == "Application" {
+        let categories: Vec<String> = entry
+            .categories
+            .iter()
+            .flat_map(|c| c.split(';'))
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect();
+        let has_only_show_in = entry
+            .only_show_in
+            .as_deref()
+            .is_some_and(|v| !v.trim().is_empty());
+        for warning in categories::check(&categories, has_only_show_in) {
+            issue(Severity::Warning, IssueKind::Categories, warning);
+        }
+    }
+
+    Some(file)
+}
+
+/// Reports the files shadowed by the first file of an ID, and user copies
+/// identical to the entry they override.
+fn check_override(id: &str, paths: &[PathBuf], issues: &mut Vec<Issue>) {
+    let normalized = |path: &Path| DesktopFile::from_file(path).ok().map(|f| f.to_string());
+    let user_apps = xdg::data_home().map(|d| d.join("applications"));
+    let active = &paths[0];
+
+    let is_user = user_apps
+        .as_deref()
+        .is_some_and(|dir| active.starts_with(dir));
+    if is_user {
+        let content = normalized(active);
+        if content.is_some() && content == normalized(&paths[1]) {
+            issues.push(Issue {
+                severity: Severity::Warning,
+                kind: IssueKind::StaleOverride,
+                id: id.to_string(),
+                path: active.clone(),
+                message: format!("Identical to {} and can be removed", paths[1].display()),
+            });
+            return;
+        }
+    }
+
+    let shadowed: Vec<String> = paths[1..].iter().map(|p| p.display().to_string()).collect();
+    issues.push(Issue {
+        severity: Severity::Info,
+        kind: IssueKind::DuplicateId,
+        id: id.to_string(),
+        path: active.clone(),
+        message: format!("Takes precedence over {}", shadowed.join(", ")),
+    });
+}
@@ -0,0 +1,186 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::health::{self, Severity};
+
+const ICON_COLUMN: u32 = 0;
+const SEVERITY_COLUMN: u32 = 1;
+/// Sort key of the severity, so errors sort before warnings.
+const RANK_COLUMN: u32 = 2;
+const KIND_COLUMN: u32 = 3;
+const ID_COLUMN: u32 = 4;
+const MESSAGE_COLUMN: u32 = 5;
+const PATH_COLUMN: u32 = 6;
+
+/// Entries of the severity filter; each shows the severities up to its rank.
+const FILTERS: &[&str] = &["Errors", "Errors and Warnings", "All Issues"];
+
+/// Opens the window with the health report of all installed entries.
+pub fn present(parent: &adw::ApplicationWindow) {
+    let window = adw::Window::new();
+    window.set_title(Some("Health Report"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(1000, 640);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Health Report"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let refresh_button = gtk::Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.set_tooltip_text(Some("Check Again"));
+    header.pack_start(&refresh_button);
+
+    let filter_dropdown = gtk::DropDown::from_strings(FILTERS);
+    filter_dropdown.set_selected(1);
+    filter_dropdown.set_tooltip_text(Some("Severities to Show"));
+    header.pack_end(&filter_dropdown);
+
+    let summary = gtk::Label::new(None);
+    summary.add_css_class("dim-label");
+    summary.set_wrap(true);
+    summary.set_halign(gtk::Align::Start);
+
+    let store = gtk::ListStore::new(&[
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::U32,
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::STRING,
+    ]);
+
+    let max_rank = Rc::new(Cell::new(filter_dropdown.selected()));
+    let filter = gtk::TreeModelFilter::new(&store, None);
+    {
+        let max_rank = max_rank.clone();
+        filter.set_visible_func(move |model, iter| {
+            model.get::<u32>(iter, RANK_COLUMN as i32) <= max_rank.get()
+        });
+    }
+    let sorted = gtk::TreeModelSort::with_model(&filter);
+    sorted.set_sort_column_id(
+        gtk::SortColumn::Index(RANK_COLUMN),
+        gtk::SortType::Ascending,
+    );
+
+    let tree_view = gtk::TreeView::with_model(&sorted);
+    tree_view.set_tooltip_column(PATH_COLUMN as i32);
+    tree_view.set_enable_search(true);
+    tree_view.set_search_column(ID_COLUMN as i32);
+
+    let severity_column = gtk::TreeViewColumn::new();
+    severity_column.set_title("Severity");
+    let icon = gtk::CellRendererPixbuf::new();
+    severity_column.pack_start(&icon, false);
+    severity_column.add_attribute(&icon, "icon-name", ICON_COLUMN as i32);
+    let text = gtk::CellRendererText::new();
+    severity_column.pack_start(&text, true);
+    severity_column.add_attribute(&text, "text", SEVERITY_COLUMN as i32);
+    severity_column.set_sort_column_id(RANK_COLUMN as i32);
+    tree_view.append_column(&severity_column);
+
+    for (title, column, expand) in [
+        ("Kind", KIND_COLUMN, false),
+        ("ID", ID_COLUMN, false),
+        ("Message", MESSAGE_COLUMN, true),
+    ] {
+        let text = gtk::CellRendererText::new();
+        if expand {
+            text.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            text.set_wrap_width(420);
+        }
+        let view_column = gtk::TreeViewColumn::new();
+        view_column.set_title(title);
+        view_column.pack_start(&text, true);
+        view_column.add_attribute(&text, "text", column as i32);
+        view_column.set_sort_column_id(column as i32);
+        view_column.set_resizable(true);
+        view_column.set_expand(expand);
+        tree_view.append_column(&view_column);
+    }
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&tree_view));
+    scrolled.set_vexpand(true);
+    scrolled.add_css_class("card");
+
+    let export_hint = gtk::Label::new(Some(
+        "Export from the command line with \
+         menu-entry-manager --health-report --format html --output report.html",
+    ));
+    export_hint.add_css_class("dim-label");
+    export_hint.add_css_class("caption");
+    export_hint.set_wrap(true);
+    export_hint.set_halign(gtk::Align::Start);
+    export_hint.set_selectable(true);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&summary);
+    content.append(&scrolled);
+    content.append(&export_hint);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&content);
+    window.set_content(Some(&main_box));
+
+    populate(&store, &summary);
+
+    {
+        let store = store.clone();
+        let summary = summary.clone();
+        refresh_button.connect_clicked(move |_| populate(&store, &summary));
+    }
+
+    filter_dropdown.connect_selected_notify(move |dropdown| {
+        max_rank.set(dropdown.selected());
+        filter.refilter();
+    });
+
+    window.present();
+}
+
+fn populate(store: &gtk::ListStore, summary: &gtk::Label) {
+    store.clear();
+    let report = health::scan();
+
+    for issue in &report.issues {
+        let icon = match issue.severity {
+            Severity::Error => "dialog-error-symbolic",
+            Severity::Warning => "dialog-warning-symbolic",
+            Severity::Info => "dialog-information-symbolic",
+        };
+        store.insert_with_values(
+            None,
+            &[
+                (ICON_COLUMN, &icon),
+                (SEVERITY_COLUMN, &issue.severity.label()),
+                (RANK_COLUMN, &(issue.severity as u32)),
+                (KIND_COLUMN, &issue.kind.label()),
+                (ID_COLUMN, &issue.id),
+                (MESSAGE_COLUMN, &issue.message),
+                (
+                    PATH_COLUMN,
+                    &glib::markup_escape_text(&issue.path.display().to_string()).to_string(),
+                ),
+            ],
+        );
+    }
+
+    summary.set_text(&format!(
+        "{} files checked: {} errors, {} warnings, {} notes",
+        report.files,
+        report.count(Severity::Error),
+        report.count(Severity::Warning),
+        report.count(Severity::Info),
+    ));
+}
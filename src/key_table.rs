@@ -0,0 +1,158 @@
+use gtk4 as gtk;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type ChangedCallback = Rc<RefCell<Option<Box<dyn Fn(Vec<(String, String)>)>>>>;
+
+/// Editable table of free-form `Key=Value` pairs, used for keys that have no
+/// dedicated form field so they survive a round trip through the editor.
+#[derive(Clone)]
+pub struct KeyTable {
+    pub widget: gtk::Box,
+    list: gtk::ListBox,
+    labels: Labels,
+    on_changed: ChangedCallback,
+}
+
+/// Texts of a table, so it can hold things other than desktop file keys.
+#[derive(Clone, Copy)]
+pub struct Labels {
+    pub add: &'static str,
+    pub remove: &'static str,
+    pub key_placeholder: &'static str,
+    /// Key of newly added rows.
+    pub new_key: &'static str,
+}
+
+const KEY_LABELS: Labels = Labels {
+    add: "Add Key",
+    remove: "Remove Key",
+    key_placeholder: "Key",
+    new_key: "X-",
+};
+
+impl KeyTable {
+    pub fn new() -> Self {
+        Self::with_labels(KEY_LABELS)
+    }
+
+    pub fn with_labels(labels: Labels) -> Self {
+        let list = gtk::ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+
+        let add_button = gtk::Button::with_label(labels.add);
+        add_button.set_halign(gtk::Align::Start);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&list);
+        widget.append(&add_button);
+
+        let table = Self {
+            widget,
+            list,
+            labels,
+            on_changed: Rc::new(RefCell::new(None)),
+        };
+
+        {
+            let table = table.clone();
+            add_button.connect_clicked(move |_| {
+                table.append_row(table.labels.new_key, "");
+                table.notify_changed();
+            });
+        }
+
+        table
+    }
+
+    pub fn connect_changed<F: Fn(Vec<(String, String)>) + 'static>(&self, f: F) {
+        *self.on_changed.borrow_mut() = Some(Box::new(f));
+    }
+
+    pub fn set_keys(&self, keys: &[(String, String)]) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+        for (key, value) in keys {
+            self.append_row(key, value);
+        }
+    }
+
+    /// Returns the non-empty keys currently in the table, in display order.
+    pub fn keys(&self) -> Vec<(String, String)> {
+        let mut keys = Vec::new();
+        let mut row = self.list.first_child();
+        while let Some(child) = row {
+            row = child.next_sibling();
+            let Some(row_box) = child
+                .downcast_ref::<gtk::ListBoxRow>()
+                .and_then(|r| r.child())
+            else {
+                continue;
+            };
+            let key_entry = row_box.first_child().and_downcast::<gtk::Entry>();
+            let value_entry = key_entry
+                .as_ref()
+                .and_then(|e| e.next_sibling())
+                .and_downcast::<gtk::Entry>();
+            if let (Some(key_entry), Some(value_entry)) = (key_entry, value_entry) {
+                let key = key_entry.text().trim().to_string();
+                if !key.is_empty() {
+                    keys.push((key, value_entry.text().to_string()));
+                }
+            }
+        }
+        keys
+    }
+
+    fn append_row(&self, key: &str, value: &str) {
+        let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        row_box.set_margin_start(6);
+        row_box.set_margin_end(6);
+        row_box.set_margin_top(6);
+        row_box.set_margin_bottom(6);
+
+        let key_entry = gtk::Entry::new();
+        key_entry.set_placeholder_text(Some(self.labels.key_placeholder));
+        key_entry.set_text(key);
+
+        let value_entry = gtk::Entry::new();
+        value_entry.set_placeholder_text(Some("Value"));
+        value_entry.set_text(value);
+        value_entry.set_hexpand(true);
+
+        let remove_button = gtk::Button::from_icon_name("list-remove-symbolic");
+        remove_button.set_tooltip_text(Some(self.labels.remove));
+        remove_button.add_css_class("flat");
+
+        row_box.append(&key_entry);
+        row_box.append(&value_entry);
+        row_box.append(&remove_button);
+
+        let row = gtk::ListBoxRow::new();
+        row.set_child(Some(&row_box));
+        row.set_activatable(false);
+        self.list.append(&row);
+
+        for entry in [&key_entry, &value_entry] {
+            let table = self.clone();
+            entry.connect_changed(move |_| table.notify_changed());
+        }
+
+        {
+            let table = self.clone();
+            remove_button.connect_clicked(move |_| {
+                table.list.remove(&row);
+                table.notify_changed();
+            });
+        }
+    }
+
+    fn notify_changed(&self) {
+        if let Some(ref callback) = *self.on_changed.borrow() {
+            callback(self.keys());
+        }
+    }
+}
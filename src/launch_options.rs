@@ -0,0 +1,228 @@
+use crate::exec::{self, Token};
+use crate::flatpak;
+
+/// `nice` adjustment used when `nice` is given without `-n`.
+pub const DEFAULT_NICENESS: i32 = 10;
+
+/// Best-effort level `ionice` derives from a niceness of 0.
+pub const DEFAULT_IO_LEVEL: u8 = 4;
+
+/// I/O scheduling class set with `ionice`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoPriority {
+    /// `ionice -c 3`: only does I/O when nothing else does.
+    Idle,
+    /// `ionice -c 2 [-n level]`, 0 being the highest level and 7 the lowest.
+    BestEffort(Option<u8>),
+}
+
+/// Display server requested from Chromium and Electron applications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Wayland,
+    X11,
+}
+
+impl Platform {
+    const FLAG: &'static str = "--ozone-platform=";
+
+    fn name(self) -> &'static str {
+        match self {
+            Platform::Wayland => "wayland",
+            Platform::X11 => "x11",
+        }
+    }
+
+    fn from_flag(arg: &str) -> Option<Self> {
+        match arg.strip_prefix(Self::FLAG)? {
+            "wayland" => Some(Platform::Wayland),
+            "x11" => Some(Platform::X11),
+            _ => None,
+        }
+    }
+}
+
+/// Wrapper programs and flags around the command of an `Exec` value (after
+/// any `env` prefix). Wrappers are recognized in any order but always
+/// written as `nice`, `ionice`, `prime-run`, `gamemoderun`, from the outside
+/// in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchOptions {
+    /// `nice -n <niceness>`.
+    pub nice: Option<i32>,
+    pub io_priority: Option<IoPriority>,
+    /// `prime-run`, which offloads rendering to the discrete NVIDIA GPU.
+    pub gpu_offload: bool,
+    /// `gamemoderun` of Feral GameMode.
+    pub gamemode: bool,
+    /// `--ozone-platform=` right after the program, or the application ID
+    /// of `flatpak run`.
+    pub platform: Option<Platform>,
+    /// The wrapped command as written, without the platform flag.
+    pub command: String,
+}
+
+impl LaunchOptions {
+    /// Fails for wrappers with options not listed here, as rewriting them
+    /// would lose the options.
+    pub fn parse(command: &str) -> Result<Self, String> {
+        let tokens = exec::tokenize(command)?;
+        let mut options = Self::default();
+        let mut i = 0;
+        while let Some(token) = tokens.get(i) {
+            let consumed = match program_name(&token.arg) {
+                "nice" if options.nice.is_none() => {
+                    parse_nice(&tokens[i + 1..]).map(|(niceness, used)| {
+                        options.nice = Some(niceness);
+                        used
+                    })
+                }
+                "ionice" if options.io_priority.is_none() => {
+                    parse_ionice(&tokens[i + 1..]).map(|(priority, used)| {
+                        options.io_priority = Some(priority);
+                        used
+                    })
+                }
+                "prime-run" if !options.gpu_offload => {
+                    options.gpu_offload = true;
+                    Some(0)
+                }
+                "gamemoderun" if !options.gamemode => {
+                    options.gamemode = true;
+                    Some(0)
+                }
+                _ => None,
+            };
+            match consumed {
+                Some(used) => i += 1 + used,
+                None => break,
+            }
+        }
+
+        let Some(program) = tokens.get(i) else {
+            return Ok(options);
+        };
+        let name = program_name(&program.arg);
+        if WRAPPERS.contains(&name) {
+            return Err(format!("The options of {name} are not supported"));
+        }
+        let mut wrapped = command[program.start..].to_string();
+        let flag = tokens[i + 1 + application_index(&tokens[i..])..]
+            .iter()
+            .find_map(|token| Platform::from_flag(&token.arg).map(|p| (p, token)));
+        if let Some((platform, token)) = flag {
+            options.platform = Some(platform);
+            let (start, end) = (token.start - program.start, token.end - program.start);
+            wrapped = format!("{}{}", wrapped[..start].trim_end(), &wrapped[end..]);
+        }
+        options.command = wrapped.trim_end().to_string();
+        Ok(options)
+    }
+
+    /// The command with the wrappers and flags of these options.
+    pub fn to_command(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(niceness) = self.nice {
+            parts.push(format!("nice -n {niceness}"));
+        }
+        match self.io_priority {
+            Some(IoPriority::Idle) => parts.push("ionice -c 3".to_string()),
+            Some(IoPriority::BestEffort(None)) => parts.push("ionice -c 2".to_string()),
+            Some(IoPriority::BestEffort(Some(level))) => {
+                parts.push(format!("ionice -c 2 -n {level}"))
+            }
+            None => {}
+        }
+        if self.gpu_offload {
+            parts.push("prime-run".to_string());
+        }
+        if self.gamemode {
+            parts.push("gamemoderun".to_string());
+        }
+
+        let mut command = self.command.clone();
+        if let Some(platform) = self.platform.filter(|_| !command.is_empty()) {
+            let flag = format!("{}{}", Platform::FLAG, platform.name());
+            if let Some(end) = exec::tokenize(&command).ok().and_then(|tokens| {
+                tokens
+                    .get(application_index(&tokens))
+                    .map(|token| token.end)
+            }) {
+                command.insert_str(end, &format!(" {flag}"));
+            }
+        }
+        if !command.is_empty() {
+            parts.push(command);
+        }
+        parts.join(" ")
+    }
+}
+
+/// The position of the application in a command: the application ID for
+/// `flatpak run`, otherwise the program. Its flags go behind it.
+fn application_index(tokens: &[Token]) -> usize {
+    let args: Vec<String> = tokens.iter().map(|token| token.arg.clone()).collect();
+    flatpak::app_id_index(&args).unwrap_or(0)
+}
+
+const WRAPPERS: &[&str] = &["nice", "ionice", "prime-run", "gamemoderun"];
+
+fn program_name(arg: &str) -> &str {
+    arg.rsplit('/').next().unwrap_or(arg)
+}
+
+/// The value of a short option written as `-x value` or `-xvalue`, and the
+/// number of arguments it took.
+fn short_option<'a>(tokens: &'a [Token], option: &str) -> Option<(&'a str, usize)> {
+    let arg = tokens.first()?.arg.strip_prefix(option)?;
+    if arg.is_empty() {
+        Some((tokens.get(1)?.arg.as_str(), 2))
+    } else {
+        Some((arg, 1))
+    }
+}
+
+/// Reads `-n <niceness>` after `nice`, if any. Other options are not
+/// recognized.
+fn parse_nice(tokens: &[Token]) -> Option<(i32, usize)> {
+    match tokens.first() {
+        Some(token) if token.arg.starts_with("-n") => {
+            let (value, used) = short_option(tokens, "-n")?;
+            Some((value.parse().ok()?, used))
+        }
+        Some(token) if token.arg.starts_with('-') => None,
+        _ => Some((DEFAULT_NICENESS, 0)),
+    }
+}
+
+/// Reads `-c <class>` and `-n <level>` after `ionice`, for the idle and
+/// best-effort classes.
+fn parse_ionice(tokens: &[Token]) -> Option<(IoPriority, usize)> {
+    let mut class = None;
+    let mut level = None;
+    let mut used = 0;
+    while let Some(token) = tokens.get(used) {
+        if token.arg.starts_with("-c") && class.is_none() {
+            let (value, n) = short_option(&tokens[used..], "-c")?;
+            class = Some(match value {
+                "3" | "idle" => 3,
+                "2" | "best-effort" => 2,
+                _ => return None,
+            });
+            used += n;
+        } else if token.arg.starts_with("-n") && level.is_none() {
+            let (value, n) = short_option(&tokens[used..], "-n")?;
+            level = Some(value.parse::<u8>().ok().filter(|l| *l <= 7)?);
+            used += n;
+        } else if token.arg.starts_with('-') {
+            return None;
+        } else {
+            break;
+        }
+    }
+    match (class?, level) {
+        (3, None) => Some((IoPriority::Idle, used)),
+        (2, level) => Some((IoPriority::BestEffort(level), used)),
+        _ => None,
+    }
+}
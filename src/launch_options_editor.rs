@@ -0,0 +1,258 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::exec::{self, EnvPrefix};
+use crate::launch_options::{
+    IoPriority, LaunchOptions, Platform, DEFAULT_IO_LEVEL, DEFAULT_NICENESS,
+};
+
+const PLATFORMS: &[&str] = &["Default", "Wayland", "X11"];
+const IO_CLASSES: &[&str] = &["Default", "Idle", "Best Effort"];
+
+/// Toggles for the wrappers and flags of `LaunchOptions`, composed around
+/// the command of `exec_entry` and read back from it when it changes.
+#[derive(Clone)]
+pub struct LaunchOptionsEditor {
+    pub widget: gtk::Box,
+    list: gtk::ListBox,
+    status: gtk::Label,
+    gpu_switch: gtk::Switch,
+    gamemode_switch: gtk::Switch,
+    platform_dropdown: gtk::DropDown,
+    nice_switch: gtk::Switch,
+    nice_spin: gtk::SpinButton,
+    io_dropdown: gtk::DropDown,
+    io_level_spin: gtk::SpinButton,
+    exec_entry: gtk::Entry,
+    prefers_non_default_gpu_switch: gtk::Switch,
+    /// Set while the editor writes `exec_entry`.
+    writing: Rc<Cell<bool>>,
+    /// Set while the controls are updated from `exec_entry`.
+    loading: Rc<Cell<bool>>,
+}
+
+impl LaunchOptionsEditor {
+    pub fn new(exec_entry: &gtk::Entry, prefers_non_default_gpu_switch: &gtk::Switch) -> Self {
+        let list = gtk::ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+
+        let gpu_switch = gtk::Switch::new();
+        let gamemode_switch = gtk::Switch::new();
+        let platform_dropdown = gtk::DropDown::from_strings(PLATFORMS);
+        let nice_switch = gtk::Switch::new();
+        let nice_spin = gtk::SpinButton::with_range(1.0, 19.0, 1.0);
+        nice_spin.set_tooltip_text(Some("Niceness, 19 yields the most"));
+        let io_dropdown = gtk::DropDown::from_strings(IO_CLASSES);
+        let io_level_spin = gtk::SpinButton::with_range(0.0, 7.0, 1.0);
+        io_level_spin.set_tooltip_text(Some("Level, 0 is the highest priority"));
+
+        let add_row = |title: &str, subtitle: &str, program: &str, suffixes: &[&gtk::Widget]| {
+            let row = adw::ActionRow::new();
+            row.set_title(title);
+            if exec::find_program(program).is_some() {
+                row.set_subtitle(subtitle);
+            } else {
+                row.set_subtitle(&format!("{subtitle} · ⚠ {program} is not installed"));
+            }
+            for suffix in suffixes {
+                suffix.set_valign(gtk::Align::Center);
+                row.add_suffix(*suffix);
+            }
+            list.append(&row);
+        };
+        add_row(
+            "Discrete GPU",
+            "Runs through prime-run and sets Prefers Non-Default GPU",
+            "prime-run",
+            &[gpu_switch.upcast_ref()],
+        );
+        add_row(
+            "GameMode",
+            "Runs through gamemoderun",
+            "gamemoderun",
+            &[gamemode_switch.upcast_ref()],
+        );
+        add_row(
+            "CPU Priority",
+            "Runs through nice, so other programs go first",
+            "nice",
+            &[nice_spin.upcast_ref(), nice_switch.upcast_ref()],
+        );
+        add_row(
+            "I/O Priority",
+            "Runs through ionice",
+            "ionice",
+            &[io_level_spin.upcast_ref(), io_dropdown.upcast_ref()],
+        );
+
+        let platform_row = adw::ActionRow::new();
+        platform_row.set_title("Display Server");
+        platform_row.set_subtitle("Passes --ozone-platform to Chromium and Electron applications");
+        platform_dropdown.set_valign(gtk::Align::Center);
+        platform_row.add_suffix(&platform_dropdown);
+        list.append(&platform_row);
+
+        let status = gtk::Label::new(None);
+        status.add_css_class("warning");
+        status.set_wrap(true);
+        status.set_halign(gtk::Align::Start);
+        status.set_visible(false);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&list);
+        widget.append(&status);
+
+        let editor = Self {
+            widget,
+            list,
+            status,
+            gpu_switch,
+            gamemode_switch,
+            platform_dropdown,
+            nice_switch,
+            nice_spin,
+            io_dropdown,
+            io_level_spin,
+            exec_entry: exec_entry.clone(),
+            prefers_non_default_gpu_switch: prefers_non_default_gpu_switch.clone(),
+            writing: Rc::new(Cell::new(false)),
+            loading: Rc::new(Cell::new(false)),
+        };
+
+        {
+            let editor = editor.clone();
+            exec_entry.connect_changed(move |_| {
+                if !editor.writing.get() {
+                    editor.load();
+                }
+            });
+        }
+
+        let changed = {
+            let editor = editor.clone();
+            Rc::new(move || {
+                if !editor.loading.get() {
+                    editor.store();
+                }
+            })
+        };
+        for switch in [
+            &editor.gpu_switch,
+            &editor.gamemode_switch,
+            &editor.nice_switch,
+        ] {
+            let changed = changed.clone();
+            switch.connect_active_notify(move |_| changed());
+        }
+        for dropdown in [&editor.platform_dropdown, &editor.io_dropdown] {
+            let changed = changed.clone();
+            dropdown.connect_selected_notify(move |_| changed());
+        }
+        for spin in [&editor.nice_spin, &editor.io_level_spin] {
+            let changed = changed.clone();
+            spin.connect_value_changed(move |_| changed());
+        }
+
+        editor.load();
+        editor
+    }
+
+    /// The options of the current `Exec` value, after its `env` prefix.
+    fn parse(&self) -> Result<(EnvPrefix, LaunchOptions), String> {
+        let prefix = EnvPrefix::parse(&self.exec_entry.text())?;
+        let options = LaunchOptions::parse(&prefix.command)?;
+        Ok((prefix, options))
+    }
+
+    fn load(&self) {
+        let options = match self.parse() {
+            Ok((_, options)) => {
+                self.list.set_sensitive(true);
+                self.set_status(None);
+                options
+            }
+            Err(e) => {
+                self.list.set_sensitive(false);
+                self.set_status(Some(&format!("{e}; edit Exec directly")));
+                LaunchOptions::default()
+            }
+        };
+
+        self.loading.set(true);
+        self.gpu_switch.set_active(options.gpu_offload);
+        self.gamemode_switch.set_active(options.gamemode);
+        self.platform_dropdown.set_selected(match options.platform {
+            None => 0,
+            Some(Platform::Wayland) => 1,
+            Some(Platform::X11) => 2,
+        });
+        self.nice_switch.set_active(options.nice.is_some());
+        self.nice_spin
+            .set_value(options.nice.unwrap_or(DEFAULT_NICENESS) as f64);
+        let (io_class, io_level) = match options.io_priority {
+            None => (0, None),
+            Some(IoPriority::Idle) => (1, None),
+            Some(IoPriority::BestEffort(level)) => (2, level),
+        };
+        self.io_dropdown.set_selected(io_class);
+        self.io_level_spin
+            .set_value(io_level.unwrap_or(DEFAULT_IO_LEVEL) as f64);
+        self.update_sensitivity();
+        self.loading.set(false);
+    }
+
+    /// Rewrites the wrappers of `Exec` from the controls.
+    fn store(&self) {
+        let Ok((mut prefix, mut options)) = self.parse() else {
+            return;
+        };
+
+        let gpu_offload = self.gpu_switch.is_active();
+        if gpu_offload != options.gpu_offload {
+            self.prefers_non_default_gpu_switch.set_active(gpu_offload);
+        }
+        options.gpu_offload = gpu_offload;
+        options.gamemode = self.gamemode_switch.is_active();
+        options.platform = match self.platform_dropdown.selected() {
+            1 => Some(Platform::Wayland),
+            2 => Some(Platform::X11),
+            _ => None,
+        };
+        options.nice = self
+            .nice_switch
+            .is_active()
+            .then(|| self.nice_spin.value_as_int());
+        options.io_priority = match self.io_dropdown.selected() {
+            1 => Some(IoPriority::Idle),
+            2 => {
+                let level = self.io_level_spin.value_as_int() as u8;
+                // Keep a missing level missing until it is changed
+                let unchanged = matches!(options.io_priority, Some(IoPriority::BestEffort(None)))
+                    && level == DEFAULT_IO_LEVEL;
+                Some(IoPriority::BestEffort((!unchanged).then_some(level)))
+            }
+            _ => None,
+        };
+        self.update_sensitivity();
+
+        prefix.command = options.to_command();
+        self.writing.set(true);
+        self.exec_entry.set_text(&prefix.to_exec());
+        self.writing.set(false);
+    }
+
+    fn update_sensitivity(&self) {
+        self.nice_spin.set_sensitive(self.nice_switch.is_active());
+        self.io_level_spin
+            .set_sensitive(self.io_dropdown.selected() == 2);
+    }
+
+    fn set_status(&self, message: Option<&str>) {
+        self.status.set_text(message.unwrap_or_default());
+        self.status.set_visible(message.is_some());
+    }
+}
@@ -0,0 +1,324 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::desktop_file::{DesktopFile, DesktopFileError};
+use crate::xdg;
+use crate::{exec, logging};
+
+/// Tokens that end the "name" part of a file name like `Foo-1.2-x86_64`.
+const ARCH_TOKENS: &[&str] = &[
+    "x86_64", "x86", "amd64", "i386", "i686", "aarch64", "arm64", "armhf", "x64", "linux",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LauncherKind {
+    AppImage,
+    Script,
+    Executable,
+}
+
+/// Identifies what kind of program `path` is from its header, extension and
+/// permissions.
+pub fn detect_kind(path: &Path) -> Option<LauncherKind> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() {
+        return None;
+    }
+
+    let mut header = [0u8; 16];
+    let read = fs::File::open(path)
+        .and_then(|mut f| f.read(&mut header))
+        .unwrap_or(0);
+    let header = &header[..read];
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    if header.starts_with(b"\x7fELF") {
+        // Type 1 and 2 AppImages carry "AI" followed by the type in the ELF
+        // padding; the name alone does not make a file an AppImage
+        if header.get(8..10) == Some(b"AI") {
+            Some(LauncherKind::AppImage)
+        } else {
+            Some(LauncherKind::Executable)
+        }
+    } else if header.starts_with(b"#!") || extension.as_deref() == Some("sh") {
+        Some(LauncherKind::Script)
+    } else if metadata.permissions().mode() & 0o111 != 0 {
+        Some(LauncherKind::Executable)
+    } else {
+        None
+    }
+}
+
+/// Adds the executable bits wherever the read bits are set. Returns whether
+/// the permissions had to be changed.
+pub fn ensure_executable(path: &Path) -> io::Result<bool> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    let wanted = mode | ((mode & 0o444) >> 2) | 0o100;
+    if wanted == mode {
+        return Ok(false);
+    }
+    permissions.set_mode(wanted);
+    fs::set_permissions(path, permissions)?;
+    Ok(true)
+}
+
+/// Builds an unsaved launcher for an executable, AppImage or script, making
+/// the file executable if needed.
+pub fn import_launcher(path: &Path) -> Result<DesktopFile, DesktopFileError> {
+    let kind = detect_kind(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} is not an executable, AppImage or script",
+                path.display()
+            ),
+        )
+    })?;
+    ensure_executable(path)?;
+
+    let path_str = path.to_string_lossy().to_string();
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let quoted = exec::quote_arg(&path_str);
+
+    let mut file = DesktopFile::new(guess_name(&stem), quoted.clone());
+    file.desktop_entry.try_exec = Some(path_str.clone());
+
+    match kind {
+        LauncherKind::AppImage => {
+            file.desktop_entry.exec = Some(format!("{quoted} %U"));
+            file.desktop_entry.startup_wm_class = Some(base_name(&stem));
+            file.desktop_entry.icon = Some("application-x-executable".to_string());
+
+            let (embedded, icon) = extract_appimage(path);
+            if let Some(embedded) = embedded {
+                let entry = embedded.desktop_entry;
+                file.desktop_entry.name = entry.name;
+                file.desktop_entry.generic_name = entry.generic_name;
+                file.desktop_entry.comment = entry.comment;
+                file.desktop_entry.categories = entry.categories;
+                file.desktop_entry.keywords = entry.keywords;
+                file.desktop_entry.mime_type = entry.mime_type;
+                file.desktop_entry.terminal = entry.terminal;
+                if entry.startup_wm_class.is_some() {
+                    file.desktop_entry.startup_wm_class = entry.startup_wm_class;
+                }
+                if let Some(icon) = entry.icon {
+                    file.desktop_entry.icon = Some(icon);
+                }
+                // Keep the embedded arguments/field codes, but run the AppImage itself.
+                if let Some(args) = entry
+                    .exec
+                    .as_deref()
+                    .and_then(|e| e.split_once(' '))
+                    .map(|(_, args)| args.to_string())
+                {
+                    file.desktop_entry.exec = Some(format!("{quoted} {args}"));
+                }
+            }
+            if let Some(icon) = icon {
+                file.desktop_entry.icon = Some(icon.to_string_lossy().to_string());
+            }
+        }
+        LauncherKind::Script => {
+            file.desktop_entry.terminal = Some(true);
+            file.desktop_entry.icon = Some("utilities-terminal".to_string());
+        }
+        LauncherKind::Executable => {
+            file.desktop_entry.startup_wm_class = Some(base_name(&stem));
+            file.desktop_entry.icon = Some("application-x-executable".to_string());
+        }
+    }
+
+    Ok(file)
+}
+
+/// Extracts the icon of an AppImage again, e.g. after it was updated, and
+/// returns where it was copied to.
+pub fn extract_icon(path: &Path) -> Option<PathBuf> {
+    extract_appimage(path).1
+}
+
+/// Turns `my_cool-app-1.2.3-x86_64` into `My Cool App`.
+fn guess_name(stem: &str) -> String {
+    base_name(stem)
+        .split(['-', '_', '.', ' '])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The file stem with version and architecture suffixes removed.
+fn base_name(stem: &str) -> String {
+    let mut parts = Vec::new();
+    for part in stem.split(['-', '_']) {
+        let lower = part.to_lowercase();
+        if part.starts_with(|c: char| c.is_ascii_digit())
+            || (lower.starts_with('v') && lower[1..].starts_with(|c: char| c.is_ascii_digit()))
+            || ARCH_TOKENS.contains(&lower.as_str())
+        {
+            break;
+        }
+        parts.push(part);
+    }
+    if parts.is_empty() {
+        stem.to_string()
+    } else {
+        parts.join("-")
+    }
+}
+
+/// Extracts the embedded desktop file and icon of an AppImage. The icon is
+/// copied to `$XDG_DATA_HOME/icons` so it outlives the extraction directory.
+fn extract_appimage(path: &Path) -> (Option<DesktopFile>, Option<PathBuf>) {
+    let dest = env::temp_dir().join(format!("menu-entry-manager-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dest);
+    if let Err(e) = fs::create_dir_all(&dest) {
+        logging::error(
+            "import",
+            format!("Could not create the extraction directory: {e}"),
+        );
+        return (None, None);
+    }
+
+    // The AppImage itself is never run to extract it
+    let root = dest.join("squashfs-root");
+    if !extract_with_unsquashfs(path, &root) {
+        logging::warn(
+            "import",
+            format!(
+                "Could not extract {} with unsquashfs; is squashfs-tools installed?",
+                path.display()
+            ),
+        );
+    }
+
+    let mut desktop = None;
+    if let Ok(entries) = fs::read_dir(&root) {
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            if entry_path.extension().is_some_and(|ext| ext == "desktop") {
+                match DesktopFile::from_file(&entry_path) {
+                    Ok(file) => {
+                        desktop = Some(file);
+                        break;
+                    }
+                    Err(e) => logging::warn(
+                        "import",
+                        format!("Could not read the embedded desktop file: {e}"),
+                    ),
+                }
+            }
+        }
+    }
+
+    let icon_name = desktop
+        .as_ref()
+        .and_then(|f| f.desktop_entry.icon.clone())
+        .unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| base_name(&s.to_string_lossy()))
+                .unwrap_or_default()
+        });
+    let icon = ["png", "svg", "xpm"]
+        .iter()
+        .map(|ext| (root.join(format!("{icon_name}.{ext}")), *ext))
+        .chain(std::iter::once((root.join(".DirIcon"), "png")))
+        .find(|(candidate, _)| candidate.exists())
+        .and_then(|(source, ext)| {
+            let icons_dir = xdg::data_home()?.join("icons");
+            fs::create_dir_all(&icons_dir).ok()?;
+            let target = icons_dir.join(format!("{icon_name}.{ext}"));
+            match fs::copy(&source, &target) {
+                Ok(_) => Some(target),
+                Err(e) => {
+                    logging::warn("import", format!("Could not copy the AppImage icon: {e}"));
+                    None
+                }
+            }
+        });
+
+    let _ = fs::remove_dir_all(&dest);
+    (desktop, icon)
+}
+
+/// Extracts the top-level desktop file and icons without running the AppImage,
+/// by pointing `unsquashfs` at the filesystem image appended to the ELF runtime.
+fn extract_with_unsquashfs(path: &Path, root: &Path) -> bool {
+    let Some(offset) = elf_size(path) else {
+        return false;
+    };
+    Command::new("unsquashfs")
+        .arg("-o")
+        .arg(offset.to_string())
+        .arg("-f")
+        .arg("-d")
+        .arg(root)
+        .arg(path)
+        .args(["*.desktop", ".DirIcon", "*.png", "*.svg", "*.xpm"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Size of the ELF runtime at the start of an AppImage, i.e. where the section
+/// header table ends.
+fn elf_size(path: &Path) -> Option<u64> {
+    let mut header = [0u8; 64];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if !header.starts_with(b"\x7fELF") {
+        return None;
+    }
+
+    let little_endian = header[5] == 1;
+    let read_u16 = |offset: usize| {
+        let bytes = [header[offset], header[offset + 1]];
+        if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        }
+    };
+
+    let (section_offset, entry_size, entry_count) = match header[4] {
+        1 => {
+            let bytes: [u8; 4] = header[0x20..0x24].try_into().ok()?;
+            let offset = if little_endian {
+                u32::from_le_bytes(bytes)
+            } else {
+                u32::from_be_bytes(bytes)
+            };
+            (offset as u64, read_u16(0x2E), read_u16(0x30))
+        }
+        2 => {
+            let bytes: [u8; 8] = header[0x28..0x30].try_into().ok()?;
+            let offset = if little_endian {
+                u64::from_le_bytes(bytes)
+            } else {
+                u64::from_be_bytes(bytes)
+            };
+            (offset, read_u16(0x3A), read_u16(0x3C))
+        }
+        _ => return None,
+    };
+
+    Some(section_offset + entry_size as u64 * entry_count as u64)
+}
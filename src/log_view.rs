@@ -0,0 +1,181 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::Cell;
+use std::rc::Rc;
+
+use crate::logging::{self, Level, Record};
+
+const ICON_COLUMN: u32 = 0;
+const TIME_COLUMN: u32 = 1;
+const LEVEL_COLUMN: u32 = 2;
+/// `Level` as a number, for filtering.
+const RANK_COLUMN: u32 = 3;
+const SOURCE_COLUMN: u32 = 4;
+const MESSAGE_COLUMN: u32 = 5;
+
+/// Entries of the level filter; each shows its level and the ones above.
+const FILTERS: &[&str] = &[
+    "Everything",
+    "Info and Above",
+    "Warnings and Errors",
+    "Errors",
+];
+
+/// Opens the window showing this session's log, newest first, updated as
+/// records come in.
+pub fn present(parent: &impl IsA<gtk::Window>) {
+    let window = adw::Window::new();
+    window.set_title(Some("Log"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(900, 560);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Log"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let clear_button = gtk::Button::from_icon_name("edit-clear-all-symbolic");
+    clear_button.set_tooltip_text(Some("Clear"));
+    header.pack_start(&clear_button);
+
+    let open_button = gtk::Button::from_icon_name("document-open-symbolic");
+    open_button.set_tooltip_text(Some("Open Log File"));
+    header.pack_start(&open_button);
+
+    let filter_dropdown = gtk::DropDown::from_strings(FILTERS);
+    filter_dropdown.set_selected(1);
+    filter_dropdown.set_tooltip_text(Some("Levels to Show"));
+    header.pack_end(&filter_dropdown);
+
+    let store = gtk::ListStore::new(&[
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::U32,
+        glib::Type::STRING,
+        glib::Type::STRING,
+    ]);
+
+    let min_rank = Rc::new(Cell::new(filter_dropdown.selected()));
+    let filter = gtk::TreeModelFilter::new(&store, None);
+    {
+        let min_rank = min_rank.clone();
+        filter.set_visible_func(move |model, iter| {
+            model.get::<u32>(iter, RANK_COLUMN as i32) >= min_rank.get()
+        });
+    }
+
+    let tree_view = gtk::TreeView::with_model(&filter);
+
+    let level_column = gtk::TreeViewColumn::new();
+    level_column.set_title("Level");
+    let icon = gtk::CellRendererPixbuf::new();
+    level_column.pack_start(&icon, false);
+    level_column.add_attribute(&icon, "icon-name", ICON_COLUMN as i32);
+    let text = gtk::CellRendererText::new();
+    level_column.pack_start(&text, true);
+    level_column.add_attribute(&text, "text", LEVEL_COLUMN as i32);
+    tree_view.append_column(&level_column);
+
+    for (title, column, expand) in [
+        ("Time", TIME_COLUMN, false),
+        ("Source", SOURCE_COLUMN, false),
+        ("Message", MESSAGE_COLUMN, true),
+    ] {
+        let text = gtk::CellRendererText::new();
+        if expand {
+            text.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+            text.set_wrap_width(480);
+        }
+        let view_column = gtk::TreeViewColumn::new();
+        view_column.set_title(title);
+        view_column.pack_start(&text, true);
+        view_column.add_attribute(&text, "text", column as i32);
+        view_column.set_resizable(true);
+        view_column.set_expand(expand);
+        tree_view.append_column(&view_column);
+    }
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&tree_view));
+    scrolled.set_vexpand(true);
+    scrolled.add_css_class("card");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&scrolled);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&content);
+    window.set_content(Some(&main_box));
+
+    for record in logging::records() {
+        prepend(&store, &record);
+    }
+
+    {
+        let store = store.downgrade();
+        logging::subscribe(move |record| match store.upgrade() {
+            Some(store) => {
+                prepend(&store, record);
+                true
+            }
+            None => false,
+        });
+    }
+
+    {
+        let store = store.clone();
+        clear_button.connect_clicked(move |_| {
+            logging::clear();
+            store.clear();
+        });
+    }
+
+    open_button.connect_clicked(|_| {
+        let Some(path) = logging::log_file().filter(|p| p.exists()) else {
+            return;
+        };
+        let uri = gio::File::for_path(&path).uri();
+        if let Err(e) = gio::AppInfo::launch_default_for_uri(&uri, None::<&gio::AppLaunchContext>) {
+            logging::error("log", format!("Could not open {}: {e}", path.display()));
+        }
+    });
+
+    filter_dropdown.connect_selected_notify(move |dropdown| {
+        min_rank.set(dropdown.selected());
+        filter.refilter();
+    });
+
+    window.present();
+}
+
+fn prepend(store: &gtk::ListStore, record: &Record) {
+    let icon = match record.level {
+        Level::Debug | Level::Info => "dialog-information-symbolic",
+        Level::Warning => "dialog-warning-symbolic",
+        Level::Error => "dialog-error-symbolic",
+    };
+    // Times are ISO 8601; the date is left out as the log covers one session
+    let time = record
+        .time
+        .split_once('T')
+        .map(|(_, time)| time.get(..8).unwrap_or(time))
+        .unwrap_or(&record.time);
+    store.insert_with_values(
+        Some(0),
+        &[
+            (ICON_COLUMN, &icon),
+            (TIME_COLUMN, &time),
+            (LEVEL_COLUMN, &record.level.label()),
+            (RANK_COLUMN, &(record.level as u32)),
+            (SOURCE_COLUMN, &record.source),
+            (MESSAGE_COLUMN, &record.message),
+        ],
+    );
+}
@@ -0,0 +1,161 @@
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::xdg;
+
+/// Records kept in memory for the log viewer.
+const MAX_RECORDS: usize = 1000;
+
+/// Size at which the log file is moved to `log.jsonl.1`, replacing the
+/// previous one, so at most twice this much is kept.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Set to print records below warnings to stderr as well.
+const VERBOSE_VAR: &str = "MENU_ENTRY_MANAGER_VERBOSE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Level {
+    pub fn label(self) -> &'static str {
+        match self {
+            Level::Debug => "Debug",
+            Level::Info => "Info",
+            Level::Warning => "Warning",
+            Level::Error => "Error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Record {
+    /// Local time in ISO 8601 format.
+    pub time: String,
+    pub level: Level,
+    /// Area of the application the record comes from, e.g. `save`.
+    pub source: String,
+    pub message: String,
+}
+
+/// Called for each new record; returning `false` unsubscribes.
+type Listener = Box<dyn Fn(&Record) -> bool>;
+
+#[derive(Default)]
+struct State {
+    records: VecDeque<Record>,
+    listeners: Vec<Listener>,
+}
+
+thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// `$XDG_STATE_HOME/menu-entry-manager/log.jsonl`, one JSON record per line.
+pub fn log_file() -> Option<PathBuf> {
+    xdg::app_state_dir().map(|dir| dir.join("log.jsonl"))
+}
+
+/// Records a message: kept for the log viewer, appended to the log file
+/// unless it is a debug record, and printed to stderr from warnings on.
+pub fn log(level: Level, source: &str, message: impl Into<String>) {
+    let record = Record {
+        time: glib::DateTime::now_local()
+            .ok()
+            .and_then(|now| now.format_iso8601().ok())
+            .map(|time| time.to_string())
+            .unwrap_or_default(),
+        level,
+        source: source.to_string(),
+        message: message.into(),
+    };
+
+    if level >= Level::Warning || std::env::var_os(VERBOSE_VAR).is_some() {
+        eprintln!(
+            "[{}] {}: {}",
+            record.level.label(),
+            record.source,
+            record.message
+        );
+    }
+    if level > Level::Debug {
+        append_to_file(&record);
+    }
+
+    // Listeners run without the state borrowed, so they may log themselves
+    let listeners = STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        if state.records.len() == MAX_RECORDS {
+            state.records.pop_front();
+        }
+        state.records.push_back(record.clone());
+        std::mem::take(&mut state.listeners)
+    });
+    let mut listeners: Vec<Listener> = listeners
+        .into_iter()
+        .filter(|listener| listener(&record))
+        .collect();
+    STATE.with(|state| {
+        let mut state = state.borrow_mut();
+        listeners.append(&mut state.listeners);
+        state.listeners = listeners;
+    });
+}
+
+fn append_to_file(record: &Record) {
+    let Some(path) = log_file() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    let Ok(line) = serde_json::to_string(record) else {
+        return;
+    };
+    // Logging must never fail the operation being logged
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_FILE_SIZE) {
+        let _ = std::fs::rename(&path, path.with_extension("jsonl.1"));
+    }
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+pub fn debug(source: &str, message: impl Into<String>) {
+    log(Level::Debug, source, message);
+}
+
+pub fn info(source: &str, message: impl Into<String>) {
+    log(Level::Info, source, message);
+}
+
+pub fn warn(source: &str, message: impl Into<String>) {
+    log(Level::Warning, source, message);
+}
+
+pub fn error(source: &str, message: impl Into<String>) {
+    log(Level::Error, source, message);
+}
+
+/// Records of this session, oldest first.
+pub fn records() -> Vec<Record> {
+    STATE.with(|state| state.borrow().records.iter().cloned().collect())
+}
+
+pub fn clear() {
+    STATE.with(|state| state.borrow_mut().records.clear());
+}
+
+/// Calls `listener` for every new record until it returns `false`.
+pub fn subscribe(listener: impl Fn(&Record) -> bool + 'static) {
+    STATE.with(|state| state.borrow_mut().listeners.push(Box::new(listener)));
+}
@@ -2,12 +2,56 @@ use gtk::prelude::*;
 use gtk4 as gtk;
 
 mod app;
+mod autostart;
+mod autostart_view;
+mod broken_entries;
+mod categories;
+mod category_tree;
+mod cleanup_view;
+mod cli;
+mod command_palette;
 mod desktop_file;
+mod desktop_id;
+mod env_editor;
+mod exec;
+mod feedback;
+mod flatpak;
+mod flatpak_editor;
+mod health;
+mod health_view;
+mod key_table;
+mod launch_options;
+mod launch_options_editor;
+mod launcher_import;
+mod log_view;
+mod logging;
+mod menu_file;
+mod menu_preview;
+mod menu_preview_view;
+mod menu_tree_view;
+mod merge_view;
+mod mime;
+mod mime_associations;
+mod origin;
+mod origin_view;
+mod overrides;
+mod overrides_view;
+mod shortcuts;
+mod source_editor;
+mod templates;
+mod trash;
 mod ui;
+mod url_handlers;
+mod xdg;
 
 use app::DesktopFileManagerApp;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+
     let app = gtk::Application::new(Some("com.example.desktopfilemanager"), Default::default());
     app.connect_activate(|app| {
         let manager = DesktopFileManagerApp::new(app);
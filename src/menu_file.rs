@@ -0,0 +1,1000 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{DesktopFile, DesktopFileError};
+use crate::desktop_id;
+use crate::menu_preview;
+use crate::trash::{self, Trashed};
+use crate::xdg;
+
+/// File name prefix of the merge and `.directory` files this application
+/// writes, so they can be told apart from ones written by other menu editors.
+pub const CUSTOM_PREFIX: &str = "menu-entry-manager-";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MenuError {
+    #[error("Failed to access {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("{0}: {1}")]
+    Xml(String, roxmltree::Error),
+    #[error("{0}: the root element is not <Menu>")]
+    NotAMenu(String),
+    #[error("No applications.menu found in the XDG config directories")]
+    NotFound,
+    #[error("{0}")]
+    Invalid(String),
+}
+
+/// A matching rule of an `<Include>` or `<Exclude>` element.
+#[derive(Debug, Clone)]
+pub enum Rule {
+    Filename(String),
+    Category(String),
+    All,
+    And(Vec<Rule>),
+    Or(Vec<Rule>),
+    /// Matches entries that match none of the rules.
+    Not(Vec<Rule>),
+}
+
+impl Rule {
+    pub fn matches(&self, id: &str, categories: &[String]) -> bool {
+        match self {
+            Rule::Filename(name) => name == id,
+            Rule::Category(name) => categories.iter().any(|c| c == name),
+            Rule::All => true,
+            Rule::And(rules) => {
+                !rules.is_empty() && rules.iter().all(|r| r.matches(id, categories))
+            }
+            Rule::Or(rules) => rules.iter().any(|r| r.matches(id, categories)),
+            Rule::Not(rules) => !rules.iter().any(|r| r.matches(id, categories)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKind {
+    Menus,
+    Files,
+    All,
+}
+
+/// An element of `<Layout>` or `<DefaultLayout>`.
+#[derive(Debug, Clone)]
+pub enum LayoutItem {
+    Filename(String),
+    Menuname(String),
+    Separator,
+    Merge(MergeKind),
+}
+
+#[derive(Debug, Clone)]
+struct Layout {
+    items: Vec<LayoutItem>,
+    show_empty: bool,
+}
+
+/// A `<Menu>` after merge files were spliced in, same-named menus were
+/// merged and `<Move>` elements were applied.
+#[derive(Debug, Clone, Default)]
+struct Menu {
+    name: String,
+    app_dirs: Vec<PathBuf>,
+    directory_dirs: Vec<PathBuf>,
+    directories: Vec<String>,
+    /// `(true, rule)` for `<Include>`, `(false, rule)` for `<Exclude>`, in
+    /// document order.
+    rules: Vec<(bool, Rule)>,
+    deleted: Option<bool>,
+    only_unallocated: Option<bool>,
+    layout: Option<Layout>,
+    default_layout: Option<Layout>,
+    moves: Vec<(String, String)>,
+    submenus: Vec<Menu>,
+}
+
+/// An application shown in a menu.
+#[derive(Debug, Clone)]
+pub struct MenuEntry {
+    pub id: String,
+    pub name: String,
+    pub icon: Option<String>,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub enum MenuItem {
+    Menu(MenuNode),
+    Entry(MenuEntry),
+    Separator,
+}
+
+/// A menu of the evaluated tree, with its contents in display order.
+#[derive(Debug, Clone)]
+pub struct MenuNode {
+    /// The `<Name>` of the menu, used in `<Move>` and merge files.
+    pub name: String,
+    /// The `Name` of its `.directory` file, or `name`.
+    pub title: String,
+    pub icon: Option<String>,
+    pub comment: Option<String>,
+    /// The `.directory` file describing the menu, if one was found.
+    pub directory: Option<PathBuf>,
+    pub items: Vec<MenuItem>,
+}
+
+impl MenuNode {
+    pub fn submenus(&self) -> impl Iterator<Item = &MenuNode> {
+        self.items.iter().filter_map(|item| match item {
+            MenuItem::Menu(menu) => Some(menu),
+            _ => None,
+        })
+    }
+
+    /// The merge file of a submenu created by this application.
+    pub fn custom_merge_file(&self) -> Option<PathBuf> {
+        let stem = self.directory.as_ref()?.file_stem()?.to_string_lossy();
+        let slug = stem.strip_prefix(CUSTOM_PREFIX)?;
+        merge_dir().map(|dir| dir.join(format!("{CUSTOM_PREFIX}{slug}.menu")))
+    }
+}
+
+/// The evaluated root menu and what was noticed while loading it.
+#[derive(Debug, Clone)]
+pub struct MenuTree {
+    pub path: PathBuf,
+    pub root: MenuNode,
+    /// Whether the root menu contains `<DefaultMergeDirs/>`, without which
+    /// user merge files are ignored.
+    pub merges_user_files: bool,
+    /// Merge files that could not be read, and other problems.
+    pub warnings: Vec<String>,
+}
+
+/// `${XDG_MENU_PREFIX}applications.menu` in the XDG config directories, the
+/// user's config directory first.
+pub fn find_root_menu() -> Option<PathBuf> {
+    let name = format!(
+        "{}applications.menu",
+        env::var("XDG_MENU_PREFIX").unwrap_or_default()
+    );
+    xdg::config_home()
+        .into_iter()
+        .chain(xdg::config_dirs())
+        .map(|dir| dir.join("menus").join(&name))
+        .find(|path| path.is_file())
+}
+
+/// The user merge directory of the root menu, `~/.config/menus/applications-merged`.
+pub fn merge_dir() -> Option<PathBuf> {
+    xdg::config_home().map(|dir| dir.join("menus/applications-merged"))
+}
+
+/// `$XDG_DATA_HOME/desktop-directories`
+pub fn user_directory_dir() -> Option<PathBuf> {
+    xdg::data_home().map(|dir| dir.join("desktop-directories"))
+}
+
+/// Loads and evaluates the root application menu for a session whose
+/// `XDG_CURRENT_DESKTOP` is `desktops`.
+pub fn load(desktops: &[String]) -> Result<MenuTree, MenuError> {
+    let path = find_root_menu().ok_or(MenuError::NotFound)?;
+    load_file(&path, desktops, None)
+}
+
+/// Like `load`, with `file` taking the place of the entry `id`, such as the
+/// unsaved state of an entry being edited. An `id` that is not installed yet
+/// is placed as if saved to the user's applications directory.
+pub fn load_with_entry(
+    desktops: &[String],
+    id: &str,
+    file: &DesktopFile,
+) -> Result<MenuTree, MenuError> {
+    let path = find_root_menu().ok_or(MenuError::NotFound)?;
+    load_file(&path, desktops, Some((id, file)))
+}
+
+pub fn load_file(
+    path: &Path,
+    desktops: &[String],
+    entry: Option<(&str, &DesktopFile)>,
+) -> Result<MenuTree, MenuError> {
+    let mut loader = Loader::default();
+    let element = loader.load(path)?;
+    let merges_user_files = contains(&element, "DefaultMergeDirs");
+    let element = loader.expand(element, path);
+    let mut menu = Menu::from_element(&element);
+    menu.consolidate();
+
+    let mut evaluator = Evaluator {
+        desktops,
+        entry,
+        files: HashMap::new(),
+        allocated: HashSet::new(),
+    };
+    let root = evaluator
+        .evaluate(&menu)
+        .unwrap_or_else(|| empty_node(&menu.name));
+
+    Ok(MenuTree {
+        path: path.to_path_buf(),
+        root,
+        merges_user_files,
+        warnings: loader.warnings,
+    })
+}
+
+fn empty_node(name: &str) -> MenuNode {
+    MenuNode {
+        name: name.to_string(),
+        title: name.to_string(),
+        icon: None,
+        comment: None,
+        directory: None,
+        items: Vec::new(),
+    }
+}
+
+/// An owned copy of an XML element; merging needs to move elements between
+/// documents.
+#[derive(Debug, Clone)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn from_node(node: roxmltree::Node) -> Self {
+        Element {
+            name: node.tag_name().name().to_string(),
+            attributes: node
+                .attributes()
+                .map(|a| (a.name().to_string(), a.value().to_string()))
+                .collect(),
+            text: node
+                .children()
+                .filter(|c| c.is_text())
+                .filter_map(|c| c.text())
+                .collect::<String>()
+                .trim()
+                .to_string(),
+            children: node
+                .children()
+                .filter(|c| c.is_element())
+                .map(Element::from_node)
+                .collect(),
+        }
+    }
+
+    fn new(name: &str, text: String) -> Self {
+        Element {
+            name: name.to_string(),
+            attributes: Vec::new(),
+            text,
+            children: Vec::new(),
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn contains(element: &Element, name: &str) -> bool {
+    element
+        .children
+        .iter()
+        .any(|c| c.name == name || (c.name == "Menu" && contains(c, name)))
+}
+
+/// Reads menu files and splices merge files into them, keeping track of the
+/// files already merged so that merge loops end.
+#[derive(Default)]
+struct Loader {
+    visited: HashSet<PathBuf>,
+    warnings: Vec<String>,
+}
+
+impl Loader {
+    fn load(&mut self, path: &Path) -> Result<Element, MenuError> {
+        let display = path.display().to_string();
+        let content = fs::read_to_string(path).map_err(|e| MenuError::Io(display.clone(), e))?;
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let document = roxmltree::Document::parse_with_options(&content, options)
+            .map_err(|e| MenuError::Xml(display.clone(), e))?;
+        let root = document.root_element();
+        if root.tag_name().name() != "Menu" {
+            return Err(MenuError::NotAMenu(display));
+        }
+        self.visited
+            .insert(fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf()));
+        Ok(Element::from_node(root))
+    }
+
+    /// Replaces `<MergeFile>`, `<MergeDir>` and the `Default*Dirs` elements
+    /// of `menu` and its submenus with what they stand for, and makes
+    /// relative directories absolute.
+    fn expand(&mut self, menu: Element, file: &Path) -> Element {
+        let base = file.parent().unwrap_or(Path::new("/")).to_path_buf();
+        let mut children = Vec::new();
+        for child in menu.children {
+            match child.name.as_str() {
+                "Menu" => children.push(self.expand(child, file)),
+                "AppDir" | "DirectoryDir" => {
+                    let text = base.join(&child.text).to_string_lossy().to_string();
+                    children.push(Element::new(&child.name, text));
+                }
+                "DefaultAppDirs" => {
+                    children.extend(data_dirs("applications").map(|d| Element::new("AppDir", d)))
+                }
+                "DefaultDirectoryDirs" => children.extend(
+                    data_dirs("desktop-directories").map(|d| Element::new("DirectoryDir", d)),
+                ),
+                "MergeFile" => {
+                    let path = if child.attribute("type") == Some("parent") {
+                        parent_menu_file(file)
+                    } else {
+                        Some(base.join(&child.text))
+                    };
+                    if let Some(path) = path {
+                        children.extend(self.merge(&path));
+                    }
+                }
+                "MergeDir" => children.extend(self.merge_dir(&base.join(&child.text))),
+                "DefaultMergeDirs" => {
+                    let name = merged_dir_name(file);
+                    let mut dirs: Vec<PathBuf> = xdg::config_dirs();
+                    dirs.reverse();
+                    dirs.extend(xdg::config_home());
+                    for dir in dirs {
+                        children.extend(self.merge_dir(&dir.join("menus").join(&name)));
+                    }
+                }
+                _ => children.push(child),
+            }
+        }
+        Element { children, ..menu }
+    }
+
+    /// The children of the root menu of `path`, except its `<Name>`.
+    fn merge(&mut self, path: &Path) -> Vec<Element> {
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        if self.visited.contains(&canonical) || !path.is_file() {
+            return Vec::new();
+        }
+        match self.load(path) {
+            Ok(element) => {
+                let element = self.expand(element, path);
+                element
+                    .children
+                    .into_iter()
+                    .filter(|c| c.name != "Name")
+                    .collect()
+            }
+            Err(e) => {
+                self.warnings.push(e.to_string());
+                Vec::new()
+            }
+        }
+    }
+
+    fn merge_dir(&mut self, dir: &Path) -> Vec<Element> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "menu"))
+            .collect();
+        paths.sort();
+        paths.iter().flat_map(|path| self.merge(path)).collect()
+    }
+}
+
+/// `<subdir>` under each XDG data directory, least important first, as
+/// later directories take precedence in menu files.
+fn data_dirs(subdir: &str) -> impl Iterator<Item = String> {
+    let mut dirs = xdg::data_dirs();
+    dirs.reverse();
+    dirs.extend(xdg::data_home());
+    let subdir = subdir.to_string();
+    dirs.into_iter()
+        .map(move |dir| dir.join(&subdir).to_string_lossy().to_string())
+}
+
+/// `applications-merged` for `applications.menu`; the `XDG_MENU_PREFIX` of a
+/// file such as `gnome-applications.menu` is not part of the name.
+fn merged_dir_name(file: &Path) -> String {
+    let stem = file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let prefix = env::var("XDG_MENU_PREFIX").unwrap_or_default();
+    let stem = stem.strip_prefix(prefix.as_str()).unwrap_or(&stem);
+    format!("{stem}-merged")
+}
+
+/// The file with the same path as `file` relative to the config directory
+/// it is in, in the next less important config directory.
+fn parent_menu_file(file: &Path) -> Option<PathBuf> {
+    let dirs: Vec<PathBuf> = xdg::config_home()
+        .into_iter()
+        .chain(xdg::config_dirs())
+        .collect();
+    let (index, relative) = dirs
+        .iter()
+        .enumerate()
+        .find_map(|(i, dir)| file.strip_prefix(dir).ok().map(|rel| (i, rel)))?;
+    dirs[index + 1..]
+        .iter()
+        .map(|dir| dir.join(relative))
+        .find(|path| path.is_file())
+}
+
+fn parse_rules(element: &Element) -> Vec<Rule> {
+    element.children.iter().filter_map(parse_rule).collect()
+}
+
+fn parse_rule(element: &Element) -> Option<Rule> {
+    Some(match element.name.as_str() {
+        "Filename" => Rule::Filename(element.text.clone()),
+        "Category" => Rule::Category(element.text.clone()),
+        "All" => Rule::All,
+        "And" => Rule::And(parse_rules(element)),
+        "Or" => Rule::Or(parse_rules(element)),
+        "Not" => Rule::Not(parse_rules(element)),
+        _ => return None,
+    })
+}
+
+fn parse_layout(element: &Element) -> Layout {
+    let items = element
+        .children
+        .iter()
+        .filter_map(|child| {
+            Some(match child.name.as_str() {
+                "Filename" => LayoutItem::Filename(child.text.clone()),
+                "Menuname" => LayoutItem::Menuname(child.text.clone()),
+                "Separator" => LayoutItem::Separator,
+                "Merge" => LayoutItem::Merge(match child.attribute("type") {
+                    Some("menus") => MergeKind::Menus,
+                    Some("files") => MergeKind::Files,
+                    _ => MergeKind::All,
+                }),
+                _ => return None,
+            })
+        })
+        .collect();
+    Layout {
+        items,
+        show_empty: element.attribute("show_empty") == Some("true"),
+    }
+}
+
+impl Menu {
+    fn from_element(element: &Element) -> Self {
+        let mut menu = Menu::default();
+        for child in &element.children {
+            match child.name.as_str() {
+                "Name" => menu.name = child.text.clone(),
+                "AppDir" => menu.app_dirs.push(PathBuf::from(&child.text)),
+                "DirectoryDir" => menu.directory_dirs.push(PathBuf::from(&child.text)),
+                "Directory" => menu.directories.push(child.text.clone()),
+                "Include" => menu.rules.push((true, Rule::Or(parse_rules(child)))),
+                "Exclude" => menu.rules.push((false, Rule::Or(parse_rules(child)))),
+                "Deleted" => menu.deleted = Some(true),
+                "NotDeleted" => menu.deleted = Some(false),
+                "OnlyUnallocated" => menu.only_unallocated = Some(true),
+                "NotOnlyUnallocated" => menu.only_unallocated = Some(false),
+                "Layout" => menu.layout = Some(parse_layout(child)),
+                "DefaultLayout" => menu.default_layout = Some(parse_layout(child)),
+                "Move" => {
+                    let text = |name: &str| {
+                        child
+                            .children
+                            .iter()
+                            .find(|c| c.name == name)
+                            .map(|c| c.text.clone())
+                    };
+                    if let (Some(old), Some(new)) = (text("Old"), text("New")) {
+                        menu.moves.push((old, new));
+                    }
+                }
+                "Menu" => menu.submenus.push(Menu::from_element(child)),
+                _ => {}
+            }
+        }
+        menu
+    }
+
+    /// Appends the contents of a later menu with the same name.
+    fn absorb(&mut self, other: Menu) {
+        self.app_dirs.extend(other.app_dirs);
+        self.directory_dirs.extend(other.directory_dirs);
+        self.directories.extend(other.directories);
+        self.rules.extend(other.rules);
+        self.deleted = other.deleted.or(self.deleted);
+        self.only_unallocated = other.only_unallocated.or(self.only_unallocated);
+        self.layout = other.layout.or(self.layout.take());
+        self.default_layout = other.default_layout.or(self.default_layout.take());
+        self.moves.extend(other.moves);
+        self.submenus.extend(other.submenus);
+    }
+
+    /// Merges same-named submenus and applies `<Move>`, recursively.
+    fn consolidate(&mut self) {
+        self.merge_duplicates();
+        for (old, new) in std::mem::take(&mut self.moves) {
+            if let Some(moved) = self.take_path(&old) {
+                self.path_mut(&new).absorb(moved);
+                self.merge_duplicates();
+            }
+        }
+        for submenu in &mut self.submenus {
+            submenu.consolidate();
+        }
+    }
+
+    fn merge_duplicates(&mut self) {
+        let mut merged: Vec<Menu> = Vec::new();
+        for submenu in std::mem::take(&mut self.submenus) {
+            match merged.iter_mut().find(|m| m.name == submenu.name) {
+                Some(existing) => existing.absorb(submenu),
+                None => merged.push(submenu),
+            }
+        }
+        self.submenus = merged;
+    }
+
+    /// Removes the submenu at a `/`-separated path.
+    fn take_path(&mut self, path: &str) -> Option<Menu> {
+        let (first, rest) = match path.split_once('/') {
+            Some((first, rest)) => (first, Some(rest)),
+            None => (path, None),
+        };
+        match rest {
+            Some(rest) => self
+                .submenus
+                .iter_mut()
+                .find(|m| m.name == first)?
+                .take_path(rest),
+            None => {
+                let index = self.submenus.iter().position(|m| m.name == first)?;
+                Some(self.submenus.remove(index))
+            }
+        }
+    }
+
+    /// The submenu at a `/`-separated path, created if missing.
+    fn path_mut(&mut self, path: &str) -> &mut Menu {
+        let mut menu = self;
+        for name in path.split('/').filter(|n| !n.is_empty()) {
+            let index = match menu.submenus.iter().position(|m| m.name == name) {
+                Some(index) => index,
+                None => {
+                    menu.submenus.push(Menu {
+                        name: name.to_string(),
+                        ..Default::default()
+                    });
+                    menu.submenus.len() - 1
+                }
+            };
+            menu = &mut menu.submenus[index];
+        }
+        menu
+    }
+}
+
+/// Fills menus with entries: first all menus without `<OnlyUnallocated>`,
+/// then those with it, from the entries no other menu took.
+struct Evaluator<'a> {
+    desktops: &'a [String],
+    /// An entry to use instead of the file installed under its ID.
+    entry: Option<(&'a str, &'a DesktopFile)>,
+    files: HashMap<PathBuf, Option<DesktopFile>>,
+    allocated: HashSet<String>,
+}
+
+/// Per-menu state inherited by submenus.
+#[derive(Clone, Default)]
+struct Scope {
+    app_dirs: Vec<PathBuf>,
+    directory_dirs: Vec<PathBuf>,
+    default_layout: Option<Layout>,
+}
+
+impl Evaluator<'_> {
+    fn evaluate(&mut self, menu: &Menu) -> Option<MenuNode> {
+        let mut matches = HashMap::new();
+        self.allocate(menu, &Scope::default(), "", false, &mut matches);
+        self.allocate(menu, &Scope::default(), "", true, &mut matches);
+        self.build(menu, &Scope::default(), "", &mut matches)
+    }
+
+    fn scope(&self, menu: &Menu, parent: &Scope) -> Scope {
+        let mut scope = parent.clone();
+        scope.app_dirs.extend(menu.app_dirs.iter().cloned());
+        scope
+            .directory_dirs
+            .extend(menu.directory_dirs.iter().cloned());
+        if menu.default_layout.is_some() {
+            scope.default_layout = menu.default_layout.clone();
+        }
+        scope
+    }
+
+    /// Records the entries of every menu whose `OnlyUnallocated` equals
+    /// `only_unallocated`, keyed by the menu's path.
+    fn allocate(
+        &mut self,
+        menu: &Menu,
+        parent: &Scope,
+        path: &str,
+        only_unallocated: bool,
+        matches: &mut HashMap<String, Vec<MenuEntry>>,
+    ) {
+        if menu.deleted == Some(true) {
+            return;
+        }
+        let scope = self.scope(menu, parent);
+        let path = format!("{path}/{}", menu.name);
+
+        if menu.only_unallocated.unwrap_or(false) == only_unallocated {
+            let mut entries = Vec::new();
+            let mut pool = pool(&scope.app_dirs);
+            if let Some((id, _)) = self.entry {
+                let user_dir = xdg::data_home().map(|dir| dir.join("applications"));
+                if let Some(user_dir) = user_dir.filter(|dir| scope.app_dirs.contains(dir)) {
+                    pool.entry(id.to_string())
+                        .or_insert_with(|| user_dir.join(id));
+                }
+            }
+            for (id, file_path) in pool {
+                let file = match self.entry {
+                    Some((entry_id, file)) if entry_id == id => file.clone(),
+                    _ => match self.file(&file_path) {
+                        Some(file) => file,
+                        None => continue,
+                    },
+                };
+                if !menu_preview::hidden_reasons(&file, self.desktops).is_empty() {
+                    continue;
+                }
+                if only_unallocated && self.allocated.contains(&id) {
+                    continue;
+                }
+                let categories: Vec<String> = file
+                    .desktop_entry
+                    .categories
+                    .as_deref()
+                    .unwrap_or_default()
+                    .split(';')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect();
+                let mut included = false;
+                for (include, rule) in &menu.rules {
+                    if rule.matches(&id, &categories) {
+                        included = *include;
+                    }
+                }
+                if included {
+                    entries.push(MenuEntry {
+                        id,
+                        name: file.desktop_entry.name.clone(),
+                        icon: file.desktop_entry.icon.clone(),
+                        path: file_path,
+                    });
+                }
+            }
+            if !only_unallocated {
+                self.allocated.extend(entries.iter().map(|e| e.id.clone()));
+            }
+            matches.insert(path.clone(), entries);
+        }
+
+        for submenu in &menu.submenus {
+            self.allocate(submenu, &scope, &path, only_unallocated, matches);
+        }
+    }
+
+    /// Builds the node of `menu`, or `None` if it is deleted, hidden by its
+    /// `.directory` file, or empty without `show_empty`.
+    fn build(
+        &mut self,
+        menu: &Menu,
+        parent: &Scope,
+        path: &str,
+        matches: &mut HashMap<String, Vec<MenuEntry>>,
+    ) -> Option<MenuNode> {
+        if menu.deleted == Some(true) {
+            return None;
+        }
+        let scope = self.scope(menu, parent);
+        let path = format!("{path}/{}", menu.name);
+
+        let mut node = empty_node(&menu.name);
+        if let Some(directory) = self.directory(menu, &scope) {
+            let entry = &self.files[&directory].as_ref()?.desktop_entry;
+            if entry.no_display == Some(true) || entry.hidden == Some(true) {
+                return None;
+            }
+            node.title = entry.name.clone();
+            node.icon = entry.icon.clone();
+            node.comment = entry.comment.clone();
+            node.directory = Some(directory);
+        }
+
+        let mut entries = matches.remove(&path).unwrap_or_default();
+        entries.sort_by_key(|e| e.name.to_lowercase());
+        let mut submenus: Vec<MenuNode> = menu
+            .submenus
+            .iter()
+            .filter_map(|submenu| self.build(submenu, &scope, &path, matches))
+            .collect();
+        submenus.sort_by_key(|m| m.title.to_lowercase());
+
+        let layout = menu.layout.clone().or(scope.default_layout.clone());
+        let show_empty = layout.as_ref().is_some_and(|l| l.show_empty);
+        let layout_items = layout.map(|l| l.items).unwrap_or_else(|| {
+            vec![
+                LayoutItem::Merge(MergeKind::Menus),
+                LayoutItem::Merge(MergeKind::Files),
+            ]
+        });
+        node.items = apply_layout(&layout_items, submenus, entries);
+
+        if node.items.is_empty() && !show_empty {
+            return None;
+        }
+        Some(node)
+    }
+
+    /// The `.directory` file of the last `<Directory>` that exists in one of
+    /// the menu's directory directories, searched from the last one.
+    fn directory(&mut self, menu: &Menu, scope: &Scope) -> Option<PathBuf> {
+        for name in menu.directories.iter().rev() {
+            for dir in scope.directory_dirs.iter().rev() {
+                let path = dir.join(name);
+                if path.is_file() && self.file(&path).is_some() {
+                    return Some(path);
+                }
+            }
+        }
+        None
+    }
+
+    fn file(&mut self, path: &Path) -> Option<DesktopFile> {
+        self.files
+            .entry(path.to_path_buf())
+            .or_insert_with(|| DesktopFile::from_file(path).ok())
+            .clone()
+    }
+}
+
+/// Desktop file IDs and paths available in `app_dirs`; files in later
+/// directories replace those with the same ID in earlier ones.
+fn pool(app_dirs: &[PathBuf]) -> BTreeMap<String, PathBuf> {
+    app_dirs
+        .iter()
+        .flat_map(|dir| desktop_id::files_in(dir))
+        .collect()
+}
+
+fn apply_layout(
+    layout: &[LayoutItem],
+    mut submenus: Vec<MenuNode>,
+    mut entries: Vec<MenuEntry>,
+) -> Vec<MenuItem> {
+    let mut items = Vec::new();
+    for item in layout {
+        match item {
+            LayoutItem::Filename(id) => {
+                if let Some(index) = entries.iter().position(|e| &e.id == id) {
+                    items.push(MenuItem::Entry(entries.remove(index)));
+                }
+            }
+            LayoutItem::Menuname(name) => {
+                if let Some(index) = submenus.iter().position(|m| &m.name == name) {
+                    items.push(MenuItem::Menu(submenus.remove(index)));
+                }
+            }
+            LayoutItem::Separator => items.push(MenuItem::Separator),
+            LayoutItem::Merge(MergeKind::Menus) => {
+                items.extend(submenus.drain(..).map(MenuItem::Menu))
+            }
+            LayoutItem::Merge(MergeKind::Files) => {
+                items.extend(entries.drain(..).map(MenuItem::Entry))
+            }
+            LayoutItem::Merge(MergeKind::All) => {
+                let mut all: Vec<(String, MenuItem)> = submenus
+                    .drain(..)
+                    .map(|m| (m.title.to_lowercase(), MenuItem::Menu(m)))
+                    .chain(
+                        entries
+                            .drain(..)
+                            .map(|e| (e.name.to_lowercase(), MenuItem::Entry(e))),
+                    )
+                    .collect();
+                all.sort_by(|a, b| a.0.cmp(&b.0));
+                items.extend(all.into_iter().map(|(_, item)| item));
+            }
+        }
+    }
+
+    // Separators are only shown between other items
+    let mut result: Vec<MenuItem> = Vec::new();
+    for item in items {
+        let is_separator = matches!(item, MenuItem::Separator);
+        let after_item =
+            matches!(result.last(), Some(last) if !matches!(last, MenuItem::Separator));
+        if !is_separator || after_item {
+            result.push(item);
+        }
+    }
+    if matches!(result.last(), Some(MenuItem::Separator)) {
+        result.pop();
+    }
+    result
+}
+
+/// A submenu to add to the application menu.
+#[derive(Debug, Clone)]
+pub struct CustomSubmenu {
+    pub title: String,
+    pub icon: Option<String>,
+    /// Entries with this category are shown in the submenu.
+    pub category: String,
+    /// `<Name>`s of the menus to create it in, below the root menu.
+    pub parent: Vec<String>,
+}
+
+/// Creates `submenu` by writing a `.directory` file and a merge file for the
+/// root menu called `root_name`. Returns the merge file.
+pub fn create_submenu(root_name: &str, submenu: &CustomSubmenu) -> Result<PathBuf, MenuError> {
+    let title = submenu.title.trim();
+    let slug = slug(title);
+    if slug.is_empty() {
+        return Err(MenuError::Invalid(
+            "The submenu name needs at least one letter or digit".to_string(),
+        ));
+    }
+    if submenu.category.trim().is_empty() {
+        return Err(MenuError::Invalid("The category is empty".to_string()));
+    }
+    let directory_dir = user_directory_dir().ok_or(MenuError::NotFound)?;
+    let merge_dir = merge_dir().ok_or(MenuError::NotFound)?;
+    let directory_name = format!("{CUSTOM_PREFIX}{slug}.directory");
+    let directory_path = directory_dir.join(&directory_name);
+    let merge_path = merge_dir.join(format!("{CUSTOM_PREFIX}{slug}.menu"));
+    if merge_path.exists() || directory_path.exists() {
+        return Err(MenuError::Invalid(format!(
+            "A submenu called '{title}' was already created"
+        )));
+    }
+
+    let mut directory = DesktopFile::new(title.to_string(), String::new());
+    let entry = &mut directory.desktop_entry;
+    entry.entry_type = "Directory".to_string();
+    entry.exec = None;
+    entry.terminal = None;
+    entry.hidden = None;
+    entry.dbus_activatable = None;
+    entry.icon = submenu.icon.clone().filter(|i| !i.trim().is_empty());
+
+    let mut menus = vec![root_name.to_string()];
+    menus.extend(submenu.parent.iter().cloned());
+    let mut xml = String::from(
+        "<!DOCTYPE Menu PUBLIC \"-//freedesktop//DTD Menu 1.0//EN\"\n \
+         \"http://www.freedesktop.org/standards/menu-spec/1.0/menu.dtd\">\n",
+    );
+    for (depth, name) in menus.iter().enumerate() {
+        let indent = "  ".repeat(depth);
+        xml.push_str(&format!(
+            "{indent}<Menu>\n{indent}  <Name>{}</Name>\n",
+            escape(name)
+        ));
+    }
+    let indent = "  ".repeat(menus.len());
+    xml.push_str(&format!(
+        "{indent}<Menu>\n\
+         {indent}  <Name>{}</Name>\n\
+         {indent}  <Directory>{}</Directory>\n\
+         {indent}  <Include>\n\
+         {indent}    <Category>{}</Category>\n\
+         {indent}  </Include>\n\
+         {indent}</Menu>\n",
+        escape(title),
+        escape(&directory_name),
+        escape(submenu.category.trim()),
+    ));
+    for depth in (0..menus.len()).rev() {
+        xml.push_str(&format!("{}</Menu>\n", "  ".repeat(depth)));
+    }
+
+    fs::create_dir_all(&directory_dir)
+        .map_err(|e| MenuError::Io(directory_dir.display().to_string(), e))?;
+    fs::create_dir_all(&merge_dir)
+        .map_err(|e| MenuError::Io(merge_dir.display().to_string(), e))?;
+    fs::write(&directory_path, directory.to_string())
+        .map_err(|e| MenuError::Io(directory_path.display().to_string(), e))?;
+    fs::write(&merge_path, xml).map_err(|e| MenuError::Io(merge_path.display().to_string(), e))?;
+    Ok(merge_path)
+}
+
+/// Moves a submenu created by `create_submenu`, given its merge file, to the
+/// trash. Returns what `trash::restore` needs to undo it.
+pub fn remove_submenu(merge_file: &Path) -> Result<Vec<Trashed>, MenuError> {
+    let stem = merge_file
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .filter(|s| s.starts_with(CUSTOM_PREFIX))
+        .ok_or_else(|| {
+            MenuError::Invalid(format!(
+                "{} was not created by this application",
+                merge_file.display()
+            ))
+        })?;
+    let directory = user_directory_dir().map(|dir| dir.join(format!("{stem}.directory")));
+    let mut trashed = Vec::new();
+    for path in [Some(merge_file.to_path_buf()), directory]
+        .into_iter()
+        .flatten()
+        .filter(|path| path.exists())
+    {
+        match trash::trash(&path) {
+            Ok(item) => trashed.extend(item),
+            Err(DesktopFileError::IoError(e)) => {
+                return Err(MenuError::Io(path.display().to_string(), e))
+            }
+            Err(e) => return Err(MenuError::Invalid(e.to_string())),
+        }
+    }
+    Ok(trashed)
+}
+
+/// Suggested category for a submenu, e.g. `X-My-Tools` for "My Tools".
+pub fn suggested_category(title: &str) -> String {
+    let words: Vec<String> = title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if words.is_empty() {
+        String::new()
+    } else {
+        format!("X-{}", words.join("-"))
+    }
+}
+
+fn slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
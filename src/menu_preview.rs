@@ -0,0 +1,345 @@
+use crate::desktop_file::DesktopFile;
+use crate::exec;
+use crate::menu_file::{self, MenuItem, MenuNode};
+use crate::xdg;
+
+/// A top-level menu of a desktop's default layout. Entries with any of the
+/// `include` categories and none of the `exclude` categories land in it.
+struct Submenu {
+    name: &'static str,
+    include: &'static [&'static str],
+    exclude: &'static [&'static str],
+}
+
+const fn submenu(
+    name: &'static str,
+    include: &'static [&'static str],
+    exclude: &'static [&'static str],
+) -> Submenu {
+    Submenu {
+        name,
+        include,
+        exclude,
+    }
+}
+
+/// The default application menu of a desktop environment, as shipped in its
+/// `applications.menu`. Used when no menu file can be loaded.
+pub struct DesktopLayout {
+    pub name: &'static str,
+    /// Desktop name as used in `XDG_CURRENT_DESKTOP` and `OnlyShowIn`.
+    pub desktop: &'static str,
+    root: &'static str,
+    submenus: &'static [Submenu],
+    /// Submenu for entries that match no other submenu; without one they are
+    /// shown in the root.
+    fallback: Option<&'static str>,
+}
+
+const MULTIMEDIA: &[&str] = &["AudioVideo", "Audio", "Video"];
+
+pub const LAYOUTS: &[DesktopLayout] = &[
+    DesktopLayout {
+        name: "GNOME",
+        desktop: "GNOME",
+        root: "App Grid",
+        submenus: &[submenu("Utilities", &["X-GNOME-Utilities"], &[])],
+        fallback: None,
+    },
+    DesktopLayout {
+        name: "KDE Plasma",
+        desktop: "KDE",
+        root: "Application Launcher",
+        submenus: &[
+            submenu("Development", &["Development"], &[]),
+            submenu("Education", &["Education"], &["Science", "Math"]),
+            submenu("Games", &["Game"], &[]),
+            submenu("Graphics", &["Graphics"], &[]),
+            submenu("Internet", &["Network"], &[]),
+            submenu("Multimedia", MULTIMEDIA, &[]),
+            submenu("Office", &["Office"], &[]),
+            submenu("Science & Math", &["Science", "Math"], &[]),
+            submenu("Settings", &["Settings"], &["System"]),
+            submenu("System", &["System"], &[]),
+            submenu("Utilities", &["Utility"], &[]),
+        ],
+        fallback: Some("Lost & Found"),
+    },
+    DesktopLayout {
+        name: "Xfce",
+        desktop: "XFCE",
+        root: "Applications",
+        submenus: &[
+            submenu("Settings", &["Settings"], &[]),
+            submenu("Accessories", &["Accessibility", "Core", "Utility"], &[]),
+            submenu("Development", &["Development"], &[]),
+            submenu("Education", &["Education"], &[]),
+            submenu("Games", &["Game"], &[]),
+            submenu("Graphics", &["Graphics"], &[]),
+            submenu("Internet", &["Network"], &[]),
+            submenu("Multimedia", MULTIMEDIA, &[]),
+            submenu("Office", &["Office"], &[]),
+            submenu("System", &["Emulator", "System"], &[]),
+        ],
+        fallback: Some("Other"),
+    },
+    DesktopLayout {
+        name: "Cinnamon",
+        desktop: "X-Cinnamon",
+        root: "Menu",
+        submenus: &[
+            submenu("Accessories", &["Utility"], &["Accessibility", "System"]),
+            submenu("Universal Access", &["Accessibility"], &[]),
+            submenu("Education", &["Education"], &[]),
+            submenu("Games", &["Game"], &[]),
+            submenu("Graphics", &["Graphics"], &[]),
+            submenu("Internet", &["Network"], &[]),
+            submenu("Office", &["Office"], &[]),
+            submenu("Programming", &["Development"], &[]),
+            submenu("Sound & Video", MULTIMEDIA, &[]),
+            submenu("Preferences", &["Settings"], &["System"]),
+            submenu("Administration", &["System"], &[]),
+        ],
+        fallback: Some("Other"),
+    },
+    DesktopLayout {
+        name: "MATE",
+        desktop: "MATE",
+        root: "Applications",
+        submenus: &[
+            submenu("Accessories", &["Utility"], &["Accessibility", "System"]),
+            submenu("Universal Access", &["Accessibility"], &[]),
+            submenu("Education", &["Education"], &[]),
+            submenu("Games", &["Game"], &[]),
+            submenu("Graphics", &["Graphics"], &[]),
+            submenu("Internet", &["Network"], &[]),
+            submenu("Office", &["Office"], &[]),
+            submenu("Programming", &["Development"], &[]),
+            submenu("Sound & Video", MULTIMEDIA, &[]),
+            submenu("System Tools", &["System"], &["Settings"]),
+            submenu("System › Preferences", &["Settings"], &[]),
+        ],
+        fallback: Some("Other"),
+    },
+    DesktopLayout {
+        name: "LXQt",
+        desktop: "LXQt",
+        root: "Applications",
+        submenus: &[
+            submenu("Accessories", &["Utility"], &["System"]),
+            submenu("Education", &["Education"], &[]),
+            submenu("Games", &["Game"], &[]),
+            submenu("Graphics", &["Graphics"], &[]),
+            submenu("Internet", &["Network"], &[]),
+            submenu("Office", &["Office"], &[]),
+            submenu("Programming", &["Development"], &[]),
+            submenu("Sound & Video", MULTIMEDIA, &[]),
+            submenu("System Tools", &["System"], &[]),
+            submenu("Preferences", &["Settings"], &[]),
+        ],
+        fallback: Some("Other"),
+    },
+];
+
+/// Layout of the freedesktop.org example menu, for unknown desktops.
+pub const GENERIC_LAYOUT: DesktopLayout = DesktopLayout {
+    name: "Other Desktop",
+    desktop: "",
+    root: "Applications",
+    submenus: &[
+        submenu("Accessories", &["Utility"], &[]),
+        submenu("Development", &["Development"], &[]),
+        submenu("Education", &["Education"], &[]),
+        submenu("Games", &["Game"], &[]),
+        submenu("Graphics", &["Graphics"], &[]),
+        submenu("Internet", &["Network"], &[]),
+        submenu("Multimedia", MULTIMEDIA, &[]),
+        submenu("Office", &["Office"], &[]),
+        submenu("Science", &["Science"], &[]),
+        submenu("Settings", &["Settings"], &[]),
+        submenu("System", &["System"], &[]),
+    ],
+    fallback: Some("Other"),
+};
+
+/// The layout of the first known desktop in `desktops`.
+pub fn layout_for(desktops: &[String]) -> &'static DesktopLayout {
+    desktops
+        .iter()
+        .find_map(|d| {
+            LAYOUTS
+                .iter()
+                .find(|layout| layout.desktop.eq_ignore_ascii_case(d))
+        })
+        .unwrap_or(&GENERIC_LAYOUT)
+}
+
+/// Desktop names for a layout, or those of the running session when `layout`
+/// is `None`.
+pub fn desktops_for(layout: Option<&DesktopLayout>) -> Vec<String> {
+    match layout {
+        Some(layout) if !layout.desktop.is_empty() => vec![layout.desktop.to_string()],
+        Some(_) => Vec::new(),
+        None => xdg::current_desktops(),
+    }
+}
+
+/// Where an entry shows up in a desktop's menu, or why it does not.
+#[derive(Debug, Default)]
+pub struct MenuPreview {
+    /// Menu paths such as `Applications › Internet`.
+    pub locations: Vec<String>,
+    pub hidden_reasons: Vec<String>,
+    /// The menu file the locations come from, or the default layout used
+    /// when none could be loaded.
+    pub source: String,
+}
+
+impl MenuPreview {
+    pub fn is_visible(&self) -> bool {
+        self.hidden_reasons.is_empty()
+    }
+}
+
+/// Places `file`, installed as `id`, in the root menu evaluated for a session
+/// whose `XDG_CURRENT_DESKTOP` is `desktops`. Without a loadable
+/// `applications.menu`, the category rules of `layout` are applied instead.
+pub fn preview(
+    file: &DesktopFile,
+    id: &str,
+    desktops: &[String],
+    layout: &DesktopLayout,
+) -> MenuPreview {
+    let mut preview = MenuPreview {
+        locations: Vec::new(),
+        hidden_reasons: hidden_reasons(file, desktops),
+        source: String::new(),
+    };
+
+    let tree = match menu_file::load_with_entry(desktops, id, file) {
+        Ok(tree) => tree,
+        Err(e) => {
+            preview.source = format!("{} default layout ({e})", layout.name);
+            if preview.is_visible() {
+                preview.locations = layout_locations(file, layout);
+            }
+            return preview;
+        }
+    };
+
+    preview.source = tree.path.display().to_string();
+    if preview.is_visible() {
+        locate(&tree.root, &tree.root.title, id, &mut preview.locations);
+        if preview.locations.is_empty() {
+            preview.hidden_reasons.push(format!(
+                "No menu in {} includes it or its categories",
+                preview.source
+            ));
+        }
+    }
+    preview
+}
+
+/// Adds the path of every menu below `node` that lists the entry `id`.
+fn locate(node: &MenuNode, path: &str, id: &str, locations: &mut Vec<String>) {
+    for item in &node.items {
+        match item {
+            MenuItem::Entry(entry) if entry.id == id => locations.push(path.to_string()),
+            MenuItem::Menu(menu) => {
+                locate(menu, &format!("{path} › {}", menu.title), id, locations)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The submenus of `layout` whose category rules match `file`.
+fn layout_locations(file: &DesktopFile, layout: &DesktopLayout) -> Vec<String> {
+    let categories = list(&file.desktop_entry.categories);
+    let has = |names: &[&str]| names.iter().any(|n| categories.iter().any(|c| c == n));
+    let mut locations: Vec<String> = layout
+        .submenus
+        .iter()
+        .filter(|menu| has(menu.include) && !has(menu.exclude))
+        .map(|menu| format!("{} › {}", layout.root, menu.name))
+        .collect();
+    if locations.is_empty() {
+        locations.push(match layout.fallback {
+            Some(fallback) => format!("{} › {fallback}", layout.root),
+            None => layout.root.to_string(),
+        });
+    }
+    locations
+}
+
+/// Why a session whose `XDG_CURRENT_DESKTOP` is `desktops` leaves `file` out
+/// of its menus, regardless of categories. Empty if the entry is shown.
+pub fn hidden_reasons(file: &DesktopFile, desktops: &[String]) -> Vec<String> {
+    let entry = &file.desktop_entry;
+    let mut reasons = Vec::new();
+
+    match entry.entry_type.as_str() {
+        "Application" => {}
+        "Directory" => {
+            reasons.push("Directory entries name menus and are not menu items".to_string())
+        }
+        other => reasons.push(format!(
+            "Type={other} entries are not listed in application menus"
+        )),
+    }
+    if entry.hidden == Some(true) {
+        reasons.push("Hidden=true: the entry is treated as deleted".to_string());
+    }
+    if entry.no_display == Some(true) {
+        reasons.push("NoDisplay=true: the entry is installed but not shown".to_string());
+    }
+
+    let only_show_in = list(&entry.only_show_in);
+    if !only_show_in.is_empty() && !only_show_in.iter().any(|d| desktops.contains(d)) {
+        reasons.push(format!(
+            "OnlyShowIn={} does not include {}",
+            only_show_in.join(";"),
+            describe_desktops(desktops)
+        ));
+    }
+    if let Some(desktop) = list(&entry.not_show_in)
+        .iter()
+        .find(|d| desktops.contains(d))
+    {
+        reasons.push(format!("NotShowIn contains {desktop}"));
+    }
+
+    if let Some(try_exec) = entry.try_exec.as_deref().filter(|t| !t.is_empty()) {
+        if exec::find_program(try_exec).is_none() {
+            reasons.push(format!(
+                "TryExec program '{try_exec}' is not installed or not executable"
+            ));
+        }
+    }
+    if entry.entry_type == "Application"
+        && entry.exec.as_deref().unwrap_or_default().is_empty()
+        && entry.dbus_activatable != Some(true)
+    {
+        reasons.push("Exec is missing and the entry is not D-Bus activatable".to_string());
+    }
+
+    reasons
+}
+
+fn list(value: &Option<String>) -> Vec<String> {
+    value
+        .as_deref()
+        .unwrap_or_default()
+        .split(';')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn describe_desktops(desktops: &[String]) -> String {
+    if desktops.is_empty() {
+        "the current desktop".to_string()
+    } else {
+        desktops.join(":")
+    }
+}
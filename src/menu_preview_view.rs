@@ -0,0 +1,138 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::desktop_file::DesktopFile;
+use crate::menu_preview::{self, LAYOUTS};
+use crate::xdg;
+
+/// Shows where the entry being edited appears in the menu of a chosen
+/// desktop environment, or why it is not shown.
+#[derive(Clone)]
+pub struct MenuPreviewView {
+    pub widget: gtk::ScrolledWindow,
+    desktop_dropdown: gtk::DropDown,
+    results: gtk::ListBox,
+    file: Rc<RefCell<Option<DesktopFile>>>,
+    /// The ID the entry is, or would be, installed as.
+    desktop_id: Rc<RefCell<String>>,
+}
+
+impl MenuPreviewView {
+    pub fn new(current_file: &Rc<RefCell<Option<DesktopFile>>>) -> Self {
+        let current = xdg::current_desktops().join(":");
+        let current_label = if current.is_empty() {
+            "Current Session".to_string()
+        } else {
+            format!("Current Session ({current})")
+        };
+        let mut names = vec![current_label.as_str()];
+        names.extend(LAYOUTS.iter().map(|layout| layout.name));
+        let desktop_dropdown = gtk::DropDown::from_strings(&names);
+        desktop_dropdown.set_valign(gtk::Align::Center);
+
+        let desktop_row = adw::ActionRow::new();
+        desktop_row.set_title("Desktop Environment");
+        desktop_row.set_subtitle("XDG_CURRENT_DESKTOP to simulate");
+        desktop_row.add_suffix(&desktop_dropdown);
+
+        let desktop_list = gtk::ListBox::new();
+        desktop_list.set_selection_mode(gtk::SelectionMode::None);
+        desktop_list.add_css_class("boxed-list");
+        desktop_list.append(&desktop_row);
+
+        let results = gtk::ListBox::new();
+        results.set_selection_mode(gtk::SelectionMode::None);
+        results.add_css_class("boxed-list");
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        content.set_margin_start(12);
+        content.set_margin_end(12);
+        content.set_margin_top(12);
+        content.set_margin_bottom(12);
+        content.append(&desktop_list);
+        content.append(&results);
+
+        let widget = gtk::ScrolledWindow::new();
+        widget.set_child(Some(&content));
+        widget.set_vexpand(true);
+
+        let view = Self {
+            widget,
+            desktop_dropdown,
+            results,
+            file: current_file.clone(),
+            desktop_id: Rc::new(RefCell::new(String::new())),
+        };
+
+        {
+            let view = view.clone();
+            view.desktop_dropdown
+                .clone()
+                .connect_selected_notify(move |_| view.update());
+        }
+
+        view.update();
+        view
+    }
+
+    /// Re-evaluates the current file, installed as `desktop_id`.
+    pub fn refresh(&self, desktop_id: &str) {
+        *self.desktop_id.borrow_mut() = desktop_id.to_string();
+        self.update();
+    }
+
+    fn update(&self) {
+        while let Some(child) = self.results.first_child() {
+            self.results.remove(&child);
+        }
+
+        let file = self.file.borrow();
+        let Some(file) = file.as_ref() else {
+            let row = adw::ActionRow::new();
+            row.set_title("No entry selected");
+            self.results.append(&row);
+            return;
+        };
+
+        let layout = match self.desktop_dropdown.selected() {
+            0 => None,
+            i => LAYOUTS.get(i as usize - 1),
+        };
+        let desktops = menu_preview::desktops_for(layout);
+        let layout = layout.unwrap_or_else(|| menu_preview::layout_for(&desktops));
+        let preview = menu_preview::preview(file, &self.desktop_id.borrow(), &desktops, layout);
+
+        let status = adw::ActionRow::new();
+        let (icon, title) = if preview.is_visible() {
+            ("emblem-ok-symbolic", "Shown in the menu")
+        } else {
+            ("action-unavailable-symbolic", "Not shown in the menu")
+        };
+        status.add_prefix(&gtk::Image::from_icon_name(icon));
+        status.set_title(title);
+        status.set_subtitle(&glib::markup_escape_text(&format!(
+            "{}, XDG_CURRENT_DESKTOP={}",
+            preview.source,
+            desktops.join(":")
+        )));
+        self.results.append(&status);
+
+        for location in &preview.locations {
+            let row = adw::ActionRow::new();
+            row.add_prefix(&gtk::Image::from_icon_name("folder-symbolic"));
+            row.set_title(&glib::markup_escape_text(location));
+            row.set_subtitle(&glib::markup_escape_text(&file.desktop_entry.name));
+            self.results.append(&row);
+        }
+
+        for reason in &preview.hidden_reasons {
+            let row = adw::ActionRow::new();
+            row.add_prefix(&gtk::Image::from_icon_name("dialog-warning-symbolic"));
+            row.set_title(&glib::markup_escape_text(reason));
+            self.results.append(&row);
+        }
+    }
+}
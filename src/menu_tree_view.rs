@@ -0,0 +1,388 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::feedback::Feedback;
+use crate::menu_file::{self, CustomSubmenu, MenuItem, MenuNode};
+use crate::trash::{self, Trashed};
+use crate::{logging, xdg};
+
+const ICON_COLUMN: u32 = 0;
+const TITLE_COLUMN: u32 = 1;
+const DETAIL_COLUMN: u32 = 2;
+/// Merge file of submenus created by this application, empty otherwise.
+const MERGE_FILE_COLUMN: u32 = 3;
+
+/// Opens the window showing the application menu as built from
+/// `applications.menu` and its merge files.
+pub fn present(parent: &adw::ApplicationWindow) {
+    let window = adw::Window::new();
+    window.set_title(Some("Application Menu"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(700, 700);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Application Menu"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let refresh_button = gtk::Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.set_tooltip_text(Some("Reload Menu Files"));
+    header.pack_start(&refresh_button);
+
+    let new_button = gtk::MenuButton::new();
+    new_button.set_icon_name("folder-new-symbolic");
+    new_button.set_tooltip_text(Some("New Submenu"));
+    header.pack_start(&new_button);
+
+    let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
+    remove_button.set_tooltip_text(Some("Remove Submenu"));
+    remove_button.set_sensitive(false);
+    header.pack_end(&remove_button);
+
+    let status = gtk::Label::new(None);
+    status.add_css_class("dim-label");
+    status.set_wrap(true);
+    status.set_halign(gtk::Align::Start);
+    status.set_selectable(true);
+
+    let warnings = gtk::Label::new(None);
+    warnings.add_css_class("warning");
+    warnings.set_wrap(true);
+    warnings.set_halign(gtk::Align::Start);
+    warnings.set_visible(false);
+
+    let store = gtk::TreeStore::new(&[
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::STRING,
+        glib::Type::STRING,
+    ]);
+    let tree_view = gtk::TreeView::with_model(&store);
+    tree_view.set_headers_visible(false);
+    tree_view.set_tooltip_column(DETAIL_COLUMN as i32);
+    tree_view.set_enable_search(true);
+    tree_view.set_search_column(TITLE_COLUMN as i32);
+
+    let icon = gtk::CellRendererPixbuf::new();
+    let text = gtk::CellRendererText::new();
+    let column = gtk::TreeViewColumn::new();
+    column.pack_start(&icon, false);
+    column.add_attribute(&icon, "icon-name", ICON_COLUMN as i32);
+    column.pack_start(&text, true);
+    column.add_attribute(&text, "text", TITLE_COLUMN as i32);
+    tree_view.append_column(&column);
+
+    let tree_scrolled = gtk::ScrolledWindow::new();
+    tree_scrolled.set_child(Some(&tree_view));
+    tree_scrolled.set_vexpand(true);
+    tree_scrolled.add_css_class("card");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&status);
+    content.append(&warnings);
+    content.append(&tree_scrolled);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&content);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    // New submenu form
+    let title_entry = gtk::Entry::new();
+    title_entry.set_placeholder_text(Some("Name"));
+    let icon_entry = gtk::Entry::new();
+    icon_entry.set_placeholder_text(Some("Icon (optional)"));
+    let category_entry = gtk::Entry::new();
+    category_entry.set_placeholder_text(Some("Category of its entries (X-...)"));
+    let parent_dropdown = gtk::DropDown::from_strings(&[]);
+    parent_dropdown.set_tooltip_text(Some("Menu to create the submenu in"));
+    let create_button = gtk::Button::with_label("Create Submenu");
+    create_button.add_css_class("suggested-action");
+
+    let form = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    form.set_width_request(300);
+    form.append(&title_entry);
+    form.append(&icon_entry);
+    form.append(&category_entry);
+    form.append(&parent_dropdown);
+    form.append(&create_button);
+
+    let popover = gtk::Popover::new();
+    popover.set_child(Some(&form));
+    new_button.set_popover(Some(&popover));
+
+    // Root menu name and the `<Name>` paths offered as parents
+    let root_name = Rc::new(RefCell::new(String::new()));
+    let parents: Rc<RefCell<Vec<Vec<String>>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let reload: Rc<dyn Fn()> = {
+        let store = store.clone();
+        let status = status.clone();
+        let warnings = warnings.clone();
+        let parent_dropdown = parent_dropdown.clone();
+        let root_name = root_name.clone();
+        let parents = parents.clone();
+        let tree_view = tree_view.clone();
+        Rc::new(move || {
+            store.clear();
+            parents.borrow_mut().clear();
+            match menu_file::load(&xdg::current_desktops()) {
+                Ok(tree) => {
+                    status.set_text(&format!("Built from {}", tree.path.display()));
+                    let mut problems = tree.warnings.clone();
+                    if !tree.merges_user_files {
+                        problems.insert(
+                            0,
+                            "This menu does not include <DefaultMergeDirs/>, so custom \
+                             submenus will not be shown"
+                                .to_string(),
+                        );
+                    }
+                    warnings.set_text(&problems.join("\n"));
+                    warnings.set_visible(!problems.is_empty());
+
+                    *root_name.borrow_mut() = tree.root.name.clone();
+                    let mut paths = Vec::new();
+                    collect_parents(&tree.root, &mut Vec::new(), "", &mut paths);
+                    let labels: Vec<String> =
+                        paths.iter().map(|(_, label)| label.clone()).collect();
+                    let labels: Vec<&str> = labels.iter().map(|l| l.as_str()).collect();
+                    parent_dropdown.set_model(Some(&gtk::StringList::new(&labels)));
+                    *parents.borrow_mut() = paths.into_iter().map(|(path, _)| path).collect();
+
+                    append_menu(&store, None, &tree.root);
+                    tree_view.expand_row(&gtk::TreePath::new_first(), false);
+                }
+                Err(e) => {
+                    status.set_text(&e.to_string());
+                    warnings.set_visible(false);
+                    parent_dropdown.set_model(Some(&gtk::StringList::new(&[])));
+                }
+            }
+        })
+    };
+    reload();
+
+    {
+        let reload = reload.clone();
+        refresh_button.connect_clicked(move |_| reload());
+    }
+
+    {
+        let category_entry = category_entry.clone();
+        title_entry.connect_changed(move |entry| {
+            category_entry.set_placeholder_text(Some(&format!(
+                "Category of its entries ({})",
+                menu_file::suggested_category(&entry.text())
+            )));
+        });
+    }
+
+    {
+        let reload = reload.clone();
+        let title_entry = title_entry.clone();
+        let icon_entry = icon_entry.clone();
+        let category_entry = category_entry.clone();
+        let popover = popover.clone();
+        let feedback = feedback.clone();
+        create_button.connect_clicked(move |_| {
+            let title = title_entry.text().to_string();
+            let category = match category_entry.text().trim() {
+                "" => menu_file::suggested_category(&title),
+                category => category.to_string(),
+            };
+            let parent = parents
+                .borrow()
+                .get(parent_dropdown.selected() as usize)
+                .cloned()
+                .unwrap_or_default();
+            let submenu = CustomSubmenu {
+                title,
+                icon: Some(icon_entry.text().to_string()),
+                category: category.clone(),
+                parent,
+            };
+            match menu_file::create_submenu(&root_name.borrow(), &submenu) {
+                Ok(path) => {
+                    logging::info("menu", format!("Saved submenu to {}", path.display()));
+                    feedback.success(
+                        "menu",
+                        format!("Submenu created; add {category} to the categories of its entries"),
+                    );
+                    title_entry.set_text("");
+                    icon_entry.set_text("");
+                    category_entry.set_text("");
+                    popover.popdown();
+                }
+                Err(e) => feedback.problem("menu", format!("Could not create the submenu: {e}")),
+            }
+            reload();
+        });
+    }
+
+    {
+        let remove_button = remove_button.clone();
+        tree_view.selection().connect_changed(move |selection| {
+            let custom = selection
+                .selected()
+                .map(|(model, iter)| {
+                    !model
+                        .get::<String>(&iter, MERGE_FILE_COLUMN as i32)
+                        .is_empty()
+                })
+                .unwrap_or(false);
+            remove_button.set_sensitive(custom);
+        });
+    }
+
+    // The files of the last removed submenu, which the toast can put back
+    let pending_undo: Rc<RefCell<Vec<Trashed>>> = Rc::new(RefCell::new(Vec::new()));
+    {
+        let actions = gio::SimpleActionGroup::new();
+        let undo_remove = gio::SimpleAction::new("undo-remove", None);
+        let reload = reload.clone();
+        let feedback = feedback.clone();
+        let pending_undo = pending_undo.clone();
+        undo_remove.connect_activate(move |_, _| {
+            let items = std::mem::take(&mut *pending_undo.borrow_mut());
+            match items.iter().try_for_each(trash::restore) {
+                Ok(()) => feedback.success("menu", "Restored the submenu"),
+                Err(e) => feedback.error("menu", "Could not undo", &e),
+            }
+            reload();
+        });
+        actions.add_action(&undo_remove);
+        window.insert_action_group("menu", Some(&actions));
+    }
+
+    {
+        let tree_view = tree_view.clone();
+        remove_button.connect_clicked(move |_| {
+            let Some((model, iter)) = tree_view.selection().selected() else {
+                return;
+            };
+            let merge_file = model.get::<String>(&iter, MERGE_FILE_COLUMN as i32);
+            if merge_file.is_empty() {
+                return;
+            }
+            match menu_file::remove_submenu(std::path::Path::new(&merge_file)) {
+                Ok(trashed) => {
+                    let message = format!("Moved submenu {merge_file} to the trash");
+                    if trashed.is_empty() {
+                        feedback.success("menu", message);
+                    } else {
+                        feedback.success_with_action("menu", message, "Undo", "menu.undo-remove");
+                    }
+                    *pending_undo.borrow_mut() = trashed;
+                }
+                Err(e) => feedback.problem("menu", format!("Could not remove the submenu: {e}")),
+            }
+            reload();
+        });
+    }
+
+    window.present();
+}
+
+/// The `<Name>` path below the root of every menu, with a label such as
+/// `Applications › Internet`.
+fn collect_parents(
+    node: &MenuNode,
+    path: &mut Vec<String>,
+    label: &str,
+    out: &mut Vec<(Vec<String>, String)>,
+) {
+    let label = if label.is_empty() {
+        node.title.clone()
+    } else {
+        format!("{label} › {}", node.title)
+    };
+    out.push((path.clone(), label.clone()));
+    for submenu in node.submenus() {
+        path.push(submenu.name.clone());
+        collect_parents(submenu, path, &label, out);
+        path.pop();
+    }
+}
+
+fn append_menu(store: &gtk::TreeStore, parent: Option<&gtk::TreeIter>, node: &MenuNode) {
+    let detail = match (&node.comment, &node.directory) {
+        (Some(comment), Some(dir)) => format!("{comment}\n{}", dir.display()),
+        (None, Some(dir)) => dir.display().to_string(),
+        (Some(comment), None) => comment.clone(),
+        (None, None) => format!("<Name>{}</Name>", node.name),
+    };
+    let merge_file = node
+        .custom_merge_file()
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let iter = store.insert_with_values(
+        parent,
+        None,
+        &[
+            (
+                ICON_COLUMN,
+                &node.icon.as_deref().unwrap_or("folder-symbolic"),
+            ),
+            (TITLE_COLUMN, &node.title),
+            (
+                DETAIL_COLUMN,
+                &glib::markup_escape_text(&detail).to_string(),
+            ),
+            (MERGE_FILE_COLUMN, &merge_file),
+        ],
+    );
+
+    for item in &node.items {
+        match item {
+            MenuItem::Menu(submenu) => append_menu(store, Some(&iter), submenu),
+            MenuItem::Entry(entry) => {
+                store.insert_with_values(
+                    Some(&iter),
+                    None,
+                    &[
+                        (
+                            ICON_COLUMN,
+                            &entry.icon.as_deref().unwrap_or("application-x-executable"),
+                        ),
+                        (TITLE_COLUMN, &entry.name),
+                        (
+                            DETAIL_COLUMN,
+                            &glib::markup_escape_text(&format!(
+                                "{}\n{}",
+                                entry.id,
+                                entry.path.display()
+                            ))
+                            .to_string(),
+                        ),
+                        (MERGE_FILE_COLUMN, &""),
+                    ],
+                );
+            }
+            MenuItem::Separator => {
+                store.insert_with_values(
+                    Some(&iter),
+                    None,
+                    &[
+                        (ICON_COLUMN, &""),
+                        (TITLE_COLUMN, &"──────"),
+                        (DETAIL_COLUMN, &"Separator"),
+                        (MERGE_FILE_COLUMN, &""),
+                    ],
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,356 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::desktop_file::{self, DesktopFile, DesktopFileError, MergeResult};
+use crate::feedback::Feedback;
+
+/// Called with the merged content when the user applies a merge.
+pub type ApplyMerge = Rc<dyn Fn(&str) -> Result<(), DesktopFileError>>;
+
+/// Opens the window resolving the conflicts of `result` key by key.
+/// Conflicts start out with our value; `on_apply` receives the merged file.
+pub fn present(
+    parent: &impl IsA<gtk::Window>,
+    title: &str,
+    result: MergeResult,
+    ours_label: &str,
+    theirs_label: &str,
+    on_apply: ApplyMerge,
+) {
+    let window = adw::Window::new();
+    window.set_title(Some(title));
+    window.set_transient_for(Some(parent));
+    window.set_modal(true);
+    window.set_default_size(760, 680);
+
+    let header = adw::HeaderBar::new();
+    let title_label = gtk::Label::new(Some(title));
+    title_label.add_css_class("title-2");
+    header.set_title_widget(Some(&title_label));
+
+    let apply_button = gtk::Button::with_label("Apply");
+    apply_button.add_css_class("suggested-action");
+    header.pack_end(&apply_button);
+
+    let conflicts = result.conflicts();
+    let summary = gtk::Label::new(Some(&if conflicts.is_empty() {
+        format!("All changes merge cleanly. Ours is {ours_label}, theirs is {theirs_label}.")
+    } else {
+        format!(
+            "{} keys were changed differently on both sides. Choose the value to keep \
+             for each; ours ({ours_label}) is selected. Theirs is {theirs_label}.",
+            conflicts.len()
+        )
+    }));
+    summary.set_wrap(true);
+    summary.set_halign(gtk::Align::Start);
+
+    let state = Rc::new(RefCell::new(result));
+
+    let text_view = gtk::TextView::new();
+    text_view.set_monospace(true);
+    text_view.set_editable(false);
+    text_view.buffer().set_text(&state.borrow().content());
+
+    let conflict_list = gtk::ListBox::new();
+    conflict_list.set_selection_mode(gtk::SelectionMode::None);
+    conflict_list.add_css_class("boxed-list");
+    conflict_list.set_visible(!conflicts.is_empty());
+
+    for index in conflicts {
+        let row = create_conflict_row(&state, index, &text_view);
+        conflict_list.append(&row);
+    }
+
+    let preview_label = gtk::Label::new(Some("Result"));
+    preview_label.add_css_class("heading");
+    preview_label.set_halign(gtk::Align::Start);
+
+    let preview_scrolled = gtk::ScrolledWindow::new();
+    preview_scrolled.set_child(Some(&text_view));
+    preview_scrolled.set_min_content_height(220);
+    preview_scrolled.add_css_class("card");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&summary);
+    content.append(&conflict_list);
+    content.append(&preview_label);
+    content.append(&preview_scrolled);
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&content));
+    scrolled.set_vexpand(true);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&scrolled);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    {
+        let window = window.clone();
+        apply_button.connect_clicked(move |_| {
+            let content = state.borrow().content();
+            match on_apply(&content) {
+                Ok(()) => window.close(),
+                Err(e) => feedback.error("merge", "Could not apply the merge", &e),
+            }
+        });
+    }
+
+    window.present();
+}
+
+/// A conflict with one radio button per candidate value.
+fn create_conflict_row(
+    state: &Rc<RefCell<MergeResult>>,
+    index: usize,
+    text_view: &gtk::TextView,
+) -> gtk::ListBoxRow {
+    let key = state.borrow().keys[index].clone();
+    let Some(conflict) = key.conflict.clone() else {
+        return gtk::ListBoxRow::new();
+    };
+
+    let title = gtk::Label::new(Some(&format!("[{}] {}", key.group, key.full_key())));
+    title.add_css_class("heading");
+    title.set_halign(gtk::Align::Start);
+
+    let description = gtk::Label::new(Some(conflict.describe()));
+    description.add_css_class("dim-label");
+    description.set_halign(gtk::Align::Start);
+
+    let row_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+    row_box.set_margin_start(12);
+    row_box.set_margin_end(12);
+    row_box.set_margin_top(8);
+    row_box.set_margin_bottom(8);
+    row_box.append(&title);
+    row_box.append(&description);
+
+    let mut choices = vec![
+        ("Ours", conflict.ours.clone()),
+        ("Theirs", conflict.theirs.clone()),
+    ];
+    if conflict.base.is_some() {
+        choices.push(("Base", conflict.base.clone()));
+    }
+
+    let mut first: Option<gtk::CheckButton> = None;
+    for (label, value) in choices {
+        let shown = value.clone().unwrap_or_else(|| "(remove key)".to_string());
+        let check = gtk::CheckButton::with_label(&format!("{label}: {shown}"));
+        if let Some(child) = check.last_child().and_downcast::<gtk::Label>() {
+            child.set_wrap(true);
+            child.set_wrap_mode(gtk::pango::WrapMode::WordChar);
+        }
+        match &first {
+            Some(first) => check.set_group(Some(first)),
+            None => {
+                check.set_active(true);
+                first = Some(check.clone());
+            }
+        }
+
+        let state = state.clone();
+        let text_view = text_view.clone();
+        check.connect_toggled(move |check| {
+            if check.is_active() {
+                let content = {
+                    let mut result = state.borrow_mut();
+                    result.keys[index].value = value.clone();
+                    result.content()
+                };
+                text_view.buffer().set_text(&content);
+            }
+        });
+        row_box.append(&check);
+    }
+
+    let row = gtk::ListBoxRow::new();
+    row.set_activatable(false);
+    row.set_child(Some(&row_box));
+    row
+}
+
+/// Opens the window that merges another copy of the file at `ours_path`
+/// into it, optionally using the version both copies started from.
+/// `on_saved` is called after the merged file was written.
+pub fn present_for_file(
+    parent: &adw::ApplicationWindow,
+    ours_path: PathBuf,
+    on_saved: Rc<dyn Fn()>,
+) {
+    let window = adw::Window::new();
+    window.set_title(Some("Merge With Another Copy"));
+    window.set_transient_for(Some(parent));
+    window.set_modal(true);
+    window.set_default_size(560, -1);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("Merge With Another Copy"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let compare_button = gtk::Button::with_label("Compare");
+    compare_button.add_css_class("suggested-action");
+    compare_button.set_sensitive(false);
+    header.pack_end(&compare_button);
+
+    let group = adw::PreferencesGroup::new();
+    group.set_description(Some(
+        "Without the common ancestor, every key both copies have with different \
+         values is a conflict.",
+    ));
+
+    let ours_row = adw::ActionRow::new();
+    ours_row.set_title("Ours");
+    ours_row.set_subtitle(&glib::markup_escape_text(&ours_path.display().to_string()));
+    group.add(&ours_row);
+
+    let theirs: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(None));
+    let base: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(None));
+
+    let theirs_row = create_file_row(&window, "Theirs", "The other edited copy", &theirs, {
+        let compare_button = compare_button.clone();
+        move || compare_button.set_sensitive(true)
+    });
+    group.add(&theirs_row);
+
+    let base_row = create_file_row(
+        &window,
+        "Common Ancestor",
+        "Optional: the version both copies were made from",
+        &base,
+        || {},
+    );
+    group.add(&base_row);
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&group);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&content);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    {
+        let window = window.clone();
+        let parent = parent.clone();
+        compare_button.connect_clicked(move |_| {
+            let Some(theirs_path) = theirs.borrow().clone() else {
+                return;
+            };
+            let read = |path: &Path| {
+                fs::read_to_string(path)
+                    .map_err(|e| {
+                        feedback.error(
+                            "merge",
+                            &format!("Could not read {}", path.display()),
+                            &e.into(),
+                        )
+                    })
+                    .ok()
+            };
+            let (Some(ours), Some(theirs_content)) = (read(&ours_path), read(&theirs_path)) else {
+                return;
+            };
+            let base_content = match base.borrow().as_deref() {
+                Some(path) => match read(path) {
+                    Some(content) => Some(content),
+                    None => return,
+                },
+                None => None,
+            };
+
+            let result = desktop_file::merge(base_content.as_deref(), &ours, &theirs_content);
+            let ours_path = ours_path.clone();
+            let on_saved = on_saved.clone();
+            window.close();
+            present(
+                &parent,
+                "Merge Copies",
+                result,
+                &ours_path.display().to_string(),
+                &theirs_path.display().to_string(),
+                Rc::new(move |content| {
+                    DesktopFile::from_string(content)?.save(&ours_path)?;
+                    on_saved();
+                    Ok(())
+                }),
+            );
+        });
+    }
+
+    window.present();
+}
+
+/// A row with a button choosing a file into `chosen`.
+fn create_file_row(
+    window: &adw::Window,
+    title: &str,
+    subtitle: &str,
+    chosen: &Rc<RefCell<Option<PathBuf>>>,
+    on_chosen: impl Fn() + Clone + 'static,
+) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(title);
+    row.set_subtitle(subtitle);
+
+    let button = gtk::Button::with_label("Choose…");
+    button.set_valign(gtk::Align::Center);
+    row.add_suffix(&button);
+
+    let window = window.clone();
+    let chosen = chosen.clone();
+    let title = title.to_string();
+    let row_clone = row.clone();
+    button.connect_clicked(move |_| {
+        let dialog = gtk::FileChooserDialog::new(
+            Some(&format!("Select {title}")),
+            Some(&window),
+            gtk::FileChooserAction::Open,
+            &[
+                ("_Cancel", gtk::ResponseType::Cancel),
+                ("_Open", gtk::ResponseType::Accept),
+            ],
+        );
+        dialog.set_modal(true);
+        let chosen = chosen.clone();
+        let row = row_clone.clone();
+        let on_chosen = on_chosen.clone();
+        dialog.connect_response(move |dialog, response| {
+            if response == gtk::ResponseType::Accept {
+                if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                    row.set_subtitle(&glib::markup_escape_text(&path.display().to_string()));
+                    *chosen.borrow_mut() = Some(path);
+                    on_chosen();
+                }
+            }
+            dialog.close();
+        });
+        dialog.show();
+    });
+
+    row
+}
@@ -0,0 +1,473 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{get_desktop_file_paths, DesktopFile};
+use crate::xdg;
+use crate::{desktop_id, logging};
+
+pub const DEFAULT_APPLICATIONS: &str = "Default Applications";
+pub const ADDED_ASSOCIATIONS: &str = "Added Associations";
+pub const REMOVED_ASSOCIATIONS: &str = "Removed Associations";
+
+/// A type from the shared-mime-info database.
+#[derive(Debug, Clone)]
+pub struct MimeType {
+    pub name: String,
+    pub comment: Option<String>,
+    pub globs: Vec<String>,
+}
+
+impl MimeType {
+    /// Case-insensitive match against the name, description and globs.
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.name.to_lowercase().contains(&query)
+            || self
+                .comment
+                .as_ref()
+                .is_some_and(|c| c.to_lowercase().contains(&query))
+            || self.globs.iter().any(|g| g.to_lowercase().contains(&query))
+    }
+}
+
+/// Types known to shared-mime-info, read from the compiled `mime` directories
+/// (`types`, `globs2` and the per-type XML files) of every XDG data directory.
+#[derive(Debug, Default)]
+pub struct MimeDatabase {
+    types: BTreeMap<String, MimeType>,
+}
+
+impl MimeDatabase {
+    pub fn load() -> Self {
+        let mut types = BTreeMap::new();
+        let mut dirs: Vec<PathBuf> = xdg::data_home().into_iter().collect();
+        dirs.extend(xdg::data_dirs());
+        let mime_dirs: Vec<PathBuf> = dirs.iter().map(|d| d.join("mime")).collect();
+
+        for dir in &mime_dirs {
+            if let Ok(content) = fs::read_to_string(dir.join("types")) {
+                for name in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    type_entry(&mut types, name);
+                }
+            }
+            if let Ok(content) = fs::read_to_string(dir.join("globs2")) {
+                // weight:type:glob[:flags]
+                for line in content.lines().filter(|l| !l.starts_with('#')) {
+                    let mut fields = line.split(':');
+                    let (Some(_), Some(name), Some(glob)) =
+                        (fields.next(), fields.next(), fields.next())
+                    else {
+                        continue;
+                    };
+                    let mime = type_entry(&mut types, name);
+                    if !mime.globs.iter().any(|g| g == glob) {
+                        mime.globs.push(glob.to_string());
+                    }
+                }
+            }
+        }
+
+        for mime in types.values_mut() {
+            mime.comment = mime_dirs
+                .iter()
+                .find_map(|dir| read_comment(&dir.join(format!("{}.xml", mime.name))));
+        }
+
+        Self { types }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&MimeType> {
+        self.types.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &MimeType> {
+        self.types.values()
+    }
+
+    /// The description of a type, or its name if it has none.
+    pub fn describe(&self, name: &str) -> String {
+        self.get(name)
+            .and_then(|m| m.comment.clone())
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+fn type_entry<'a>(types: &'a mut BTreeMap<String, MimeType>, name: &str) -> &'a mut MimeType {
+    types.entry(name.to_string()).or_insert_with(|| MimeType {
+        name: name.to_string(),
+        comment: None,
+        globs: Vec::new(),
+    })
+}
+
+/// The untranslated `<comment>` of a per-type XML file such as
+/// `/usr/share/mime/text/plain.xml`.
+fn read_comment(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    let start = content.find("<comment>")? + "<comment>".len();
+    let end = content[start..].find("</comment>")? + start;
+    Some(
+        content[start..end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+/// A `mimeapps.list` file. Saving rewrites only the keys that changed and
+/// keeps comments, blank lines and the order of everything else.
+#[derive(Debug, Clone)]
+pub struct MimeApps {
+    path: PathBuf,
+    groups: Vec<(String, Vec<(String, String)>)>,
+    /// The file as read.
+    content: String,
+}
+
+impl MimeApps {
+    /// `$XDG_CONFIG_HOME/mimeapps.list`
+    pub fn user_path() -> Option<PathBuf> {
+        xdg::config_home().map(|dir| dir.join("mimeapps.list"))
+    }
+
+    pub fn load_user() -> io::Result<Self> {
+        let path = Self::user_path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME is not set"))?;
+        Self::load(&path)
+    }
+
+    /// Makes `id` the preferred application for `mime` in the user's list.
+    pub fn set_user_default(mime: &str, id: &str) -> io::Result<()> {
+        let mut list = Self::load_user()?;
+        list.set_default(mime, id);
+        list.save()
+    }
+
+    /// Reads `path`; a missing file is an empty list.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+
+        let mut groups: Vec<(String, Vec<(String, String)>)> = Vec::new();
+        for line in content.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                groups.push((name.to_string(), Vec::new()));
+            } else if let (Some((key, value)), Some((_, entries))) =
+                (line.split_once('='), groups.last_mut())
+            {
+                entries.push((key.trim().to_string(), value.trim().to_string()));
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            groups,
+            content,
+        })
+    }
+
+    /// Desktop file IDs listed for `mime` in `group`.
+    pub fn ids(&self, group: &str, mime: &str) -> Vec<String> {
+        self.groups
+            .iter()
+            .filter(|(name, _)| name == group)
+            .flat_map(|(_, entries)| entries.iter())
+            .filter(|(key, _)| key == mime)
+            .flat_map(|(_, value)| value.split(';'))
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| id.to_string())
+            .collect()
+    }
+
+    /// Replaces the IDs listed for `mime` in `group`; an empty list removes the key.
+    pub fn set_ids(&mut self, group: &str, mime: &str, ids: &[String]) {
+        let index = match self.groups.iter().position(|(name, _)| name == group) {
+            Some(index) => index,
+            None if ids.is_empty() => return,
+            None => {
+                self.groups.push((group.to_string(), Vec::new()));
+                self.groups.len() - 1
+            }
+        };
+        let entries = &mut self.groups[index].1;
+        let value = format!("{};", ids.join(";"));
+
+        match entries.iter().position(|(key, _)| key == mime) {
+            Some(i) if ids.is_empty() => {
+                entries.remove(i);
+            }
+            Some(i) => entries[i].1 = value,
+            None if ids.is_empty() => {}
+            None => entries.push((mime.to_string(), value)),
+        }
+    }
+
+    /// Replaces `old_id` with `new_id` wherever it is listed. Returns whether
+    /// any list named it.
+    pub fn replace_id(&mut self, old_id: &str, new_id: &str) -> bool {
+        let keys: Vec<(String, String)> = self
+            .groups
+            .iter()
+            .flat_map(|(group, entries)| {
+                entries
+                    .iter()
+                    .map(move |(mime, _)| (group.clone(), mime.clone()))
+            })
+            .collect();
+        let mut changed = false;
+        for (group, mime) in keys {
+            let ids = self.ids(&group, &mime);
+            if ids.iter().any(|id| id == old_id) {
+                let ids: Vec<String> = ids
+                    .into_iter()
+                    .map(|id| if id == old_id { new_id.to_string() } else { id })
+                    .collect();
+                self.set_ids(&group, &mime, &ids);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Makes `id` the preferred application for `mime`: first in the default
+    /// and added lists, and no longer in the removed list.
+    pub fn set_default(&mut self, mime: &str, id: &str) {
+        for group in [DEFAULT_APPLICATIONS, ADDED_ASSOCIATIONS] {
+            let mut ids = vec![id.to_string()];
+            ids.extend(self.ids(group, mime).into_iter().filter(|i| i != id));
+            self.set_ids(group, mime, &ids);
+        }
+        let removed: Vec<String> = self
+            .ids(REMOVED_ASSOCIATIONS, mime)
+            .into_iter()
+            .filter(|i| i != id)
+            .collect();
+        self.set_ids(REMOVED_ASSOCIATIONS, mime, &removed);
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        // Entries not written yet, by group in the order the headers appear
+        let mut pending: Vec<Vec<&(String, String)>> = self
+            .groups
+            .iter()
+            .map(|(_, entries)| entries.iter().collect())
+            .collect();
+        let mut content = String::new();
+        // The group being copied and where its new keys go: after its last key
+        let mut group: Option<(usize, usize)> = None;
+        let mut headers = 0;
+
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('#') {
+                content.push_str(line);
+                content.push('\n');
+            } else if trimmed.starts_with('[') && trimmed.ends_with(']') {
+                if let Some((index, at)) = group {
+                    content.insert_str(at, &entry_lines(&mut pending[index]));
+                }
+                content.push_str(line);
+                content.push('\n');
+                group = Some((headers, content.len()));
+                headers += 1;
+            } else if let (Some((index, _)), Some((key, value))) = (group, trimmed.split_once('='))
+            {
+                let key = key.trim();
+                // Removed keys are dropped
+                if let Some(i) = pending[index].iter().position(|(k, _)| k == key) {
+                    let (_, new_value) = pending[index].remove(i);
+                    if new_value == value.trim() {
+                        content.push_str(line);
+                    } else {
+                        content.push_str(&format!("{key}={new_value}"));
+                    }
+                    content.push('\n');
+                }
+                group = Some((index, content.len()));
+            } else {
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+        if let Some((index, at)) = group {
+            content.insert_str(at, &entry_lines(&mut pending[index]));
+        }
+
+        for ((name, _), entries) in self.groups.iter().zip(&mut pending).skip(headers) {
+            if !content.is_empty() && !content.ends_with("\n\n") {
+                content.push('\n');
+            }
+            content.push_str(&format!("[{name}]\n"));
+            content.push_str(&entry_lines(entries));
+        }
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, content)
+    }
+}
+
+/// `key=value` lines for `entries`, which are taken out.
+fn entry_lines(entries: &mut Vec<&(String, String)>) -> String {
+    entries
+        .drain(..)
+        .map(|(key, value)| format!("{key}={value}\n"))
+        .collect()
+}
+
+/// `mimeapps.list` files in lookup order. In each directory the lists for the
+/// desktops in `$XDG_CURRENT_DESKTOP` come before the generic one.
+pub fn mimeapps_paths() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = xdg::config_home().into_iter().collect();
+    dirs.extend(xdg::config_dirs());
+    dirs.extend(xdg::data_home().map(|d| d.join("applications")));
+    dirs.extend(xdg::data_dirs().into_iter().map(|d| d.join("applications")));
+
+    let desktops: Vec<String> = xdg::current_desktops()
+        .iter()
+        .map(|d| d.to_lowercase())
+        .collect();
+    dirs.iter()
+        .flat_map(|dir| {
+            desktops
+                .iter()
+                .map(|d| dir.join(format!("{d}-mimeapps.list")))
+                .chain(std::iter::once(dir.join("mimeapps.list")))
+        })
+        .collect()
+}
+
+/// Snapshot of the associations on this system: every `mimeapps.list` plus
+/// the `MimeType` keys of installed entries.
+#[derive(Debug, Default)]
+pub struct Associations {
+    lists: Vec<MimeApps>,
+    declared: BTreeMap<String, Vec<String>>,
+    installed: HashSet<String>,
+}
+
+impl Associations {
+    pub fn load() -> Self {
+        let lists = mimeapps_paths()
+            .iter()
+            .filter(|p| p.exists())
+            .filter_map(|p| match MimeApps::load(p) {
+                Ok(list) => Some(list),
+                Err(e) => {
+                    logging::warn("mime", format!("Could not read {}: {e}", p.display()));
+                    None
+                }
+            })
+            .collect();
+
+        let mut declared: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut installed = HashSet::new();
+        for path in get_desktop_file_paths() {
+            let Some(id) = desktop_id::from_path(Path::new(&path)) else {
+                continue;
+            };
+            let Ok(file) = DesktopFile::from_file(&path) else {
+                continue;
+            };
+            if file.desktop_entry.hidden == Some(true) {
+                continue;
+            }
+            for mime in split_list(file.desktop_entry.mime_type.as_deref().unwrap_or_default()) {
+                let ids = declared.entry(mime).or_default();
+                if !ids.contains(&id) {
+                    ids.push(id.clone());
+                }
+            }
+            installed.insert(id);
+        }
+
+        Self {
+            lists,
+            declared,
+            installed,
+        }
+    }
+
+    pub fn is_installed(&self, id: &str) -> bool {
+        self.installed.contains(id)
+    }
+
+    /// The application that opens `mime`: the first installed entry of a
+    /// `Default Applications` list, otherwise the most preferred handler.
+    pub fn default_handler(&self, mime: &str) -> Option<String> {
+        self.lists
+            .iter()
+            .flat_map(|list| list.ids(DEFAULT_APPLICATIONS, mime))
+            .find(|id| self.is_installed(id))
+            .or_else(|| self.handlers(mime).into_iter().next())
+    }
+
+    /// Every installed application that can open `mime`, most preferred
+    /// first. A removed association hides the application from the lists
+    /// after it and from the entries' own `MimeType` keys.
+    pub fn handlers(&self, mime: &str) -> Vec<String> {
+        let mut removed: HashSet<String> = HashSet::new();
+        let mut handlers: Vec<String> = Vec::new();
+        for list in &self.lists {
+            for id in list.ids(ADDED_ASSOCIATIONS, mime) {
+                if self.is_installed(&id) && !removed.contains(&id) && !handlers.contains(&id) {
+                    handlers.push(id);
+                }
+            }
+            removed.extend(list.ids(REMOVED_ASSOCIATIONS, mime));
+        }
+        for id in self.declared.get(mime).into_iter().flatten() {
+            if !removed.contains(id) && !handlers.contains(id) {
+                handlers.push(id.clone());
+            }
+        }
+        handlers
+    }
+}
+
+pub const SCHEME_HANDLER_PREFIX: &str = "x-scheme-handler/";
+
+/// Checks a URL scheme against RFC 3986: a letter followed by letters,
+/// digits, `+`, `-` or `.`.
+pub fn validate_scheme(scheme: &str) -> Result<(), String> {
+    let mut chars = scheme.chars();
+    match chars.next() {
+        None => return Err("The scheme is empty".to_string()),
+        Some(c) if !c.is_ascii_alphabetic() => {
+            return Err("The scheme must start with a letter".to_string())
+        }
+        _ => {}
+    }
+    if let Some(c) = chars.find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))) {
+        return Err(format!("'{c}' is not allowed in a URL scheme"));
+    }
+    Ok(())
+}
+
+/// The lowercased scheme of a URL such as `myapp://open?id=1`.
+pub fn url_scheme(url: &str) -> Option<String> {
+    let (scheme, _) = url.trim().split_once(':')?;
+    validate_scheme(scheme).ok()?;
+    Some(scheme.to_lowercase())
+}
+
+/// Splits a `MimeType` value into its types.
+pub fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .map(|m| m.to_string())
+        .collect()
+}
@@ -0,0 +1,195 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::feedback::Feedback;
+use crate::mime::{self, Associations, MimeApps, MimeDatabase};
+
+/// Lists the MIME types of the entry being edited together with the
+/// application that currently opens them, and lets the user make the entry
+/// the default handler.
+#[derive(Clone)]
+pub struct MimeAssociations {
+    pub widget: gtk::ListBox,
+    database: Rc<MimeDatabase>,
+    associations: Rc<RefCell<Associations>>,
+    mime_types: Rc<RefCell<Vec<String>>>,
+    desktop_id: Rc<RefCell<String>>,
+    feedback: Feedback,
+}
+
+impl MimeAssociations {
+    pub fn new(feedback: &Feedback) -> Self {
+        let widget = gtk::ListBox::new();
+        widget.set_selection_mode(gtk::SelectionMode::None);
+        widget.add_css_class("boxed-list");
+
+        let associations = Self {
+            widget,
+            database: Rc::new(MimeDatabase::load()),
+            associations: Rc::new(RefCell::new(Associations::load())),
+            mime_types: Rc::new(RefCell::new(Vec::new())),
+            desktop_id: Rc::new(RefCell::new(String::new())),
+            feedback: feedback.clone(),
+        };
+        associations.rebuild();
+        associations
+    }
+
+    /// The association snapshot, shared with other views of `mimeapps.list`.
+    pub fn associations(&self) -> Rc<RefCell<Associations>> {
+        self.associations.clone()
+    }
+
+    /// Completes type names in a semicolon-separated entry, matching the word
+    /// being typed against names, descriptions and file name patterns.
+    pub fn attach_completion(&self, entry: &gtk::Entry) {
+        let store = gtk::ListStore::new(&[glib::Type::STRING, glib::Type::STRING]);
+        for mime in self.database.iter() {
+            let comment = mime.comment.clone().unwrap_or_default();
+            store.insert_with_values(None, &[(0, &mime.name), (1, &comment)]);
+        }
+
+        let completion = gtk::EntryCompletion::new();
+        completion.set_model(Some(&store));
+        completion.set_text_column(0);
+        completion.set_minimum_key_length(2);
+        completion.set_popup_set_width(false);
+
+        let comment_cell = gtk::CellRendererText::new();
+        comment_cell.set_property("foreground", "gray");
+        CellLayoutExt::pack_start(&completion, &comment_cell, false);
+        CellLayoutExt::add_attribute(&completion, &comment_cell, "text", 1);
+
+        {
+            let database = self.database.clone();
+            completion.set_match_func(move |completion, key, iter| {
+                let query = key.rsplit(';').next().unwrap_or_default().trim();
+                if query.len() < 2 {
+                    return false;
+                }
+                let Some(model) = completion.model() else {
+                    return false;
+                };
+                let name = model.get::<String>(iter, 0);
+                database.get(&name).is_some_and(|m| m.matches(query))
+            });
+        }
+
+        {
+            let entry = entry.clone();
+            completion.connect_match_selected(move |_, model, iter| {
+                let name = model.get::<String>(iter, 0);
+                let text = entry.text();
+                let prefix = match text.rfind(';') {
+                    Some(i) => &text[..=i],
+                    None => "",
+                };
+                entry.set_text(&format!("{prefix}{name};"));
+                entry.set_position(-1);
+                glib::Propagation::Stop
+            });
+        }
+
+        entry.set_completion(Some(&completion));
+    }
+
+    /// Shows the types of a `MimeType` value for the entry with this desktop
+    /// file ID.
+    pub fn update(&self, mime_types: &str, desktop_id: &str) {
+        *self.mime_types.borrow_mut() = mime::split_list(mime_types)
+            .into_iter()
+            .filter(|m| !m.starts_with(mime::SCHEME_HANDLER_PREFIX))
+            .collect();
+        *self.desktop_id.borrow_mut() = desktop_id.to_string();
+        self.rebuild();
+    }
+
+    /// Re-reads `mimeapps.list` files and installed entries.
+    pub fn reload(&self) {
+        *self.associations.borrow_mut() = Associations::load();
+        self.rebuild();
+    }
+
+    fn rebuild(&self) {
+        while let Some(child) = self.widget.first_child() {
+            self.widget.remove(&child);
+        }
+
+        let mime_types = self.mime_types.borrow().clone();
+        if mime_types.is_empty() {
+            let row = adw::ActionRow::new();
+            row.set_title("No MIME types");
+            row.set_subtitle("Add types to MIME Types to open files with this entry");
+            self.widget.append(&row);
+            return;
+        }
+
+        let desktop_id = self.desktop_id.borrow().clone();
+        for mime_type in mime_types {
+            let row = self.create_row(&mime_type, &desktop_id);
+            self.widget.append(&row);
+        }
+    }
+
+    fn create_row(&self, mime_type: &str, desktop_id: &str) -> adw::ActionRow {
+        let associations = self.associations.borrow();
+        let default = associations.default_handler(mime_type);
+
+        let row = adw::ActionRow::new();
+        row.set_title(&glib::markup_escape_text(
+            &self.database.describe(mime_type),
+        ));
+        row.set_subtitle(&glib::markup_escape_text(&format!(
+            "{mime_type} · Default: {}",
+            default.as_deref().unwrap_or("None")
+        )));
+
+        if self.database.get(mime_type).is_none() {
+            let warning = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            warning.set_tooltip_text(Some("Not a known MIME type"));
+            row.add_prefix(&warning);
+        }
+
+        let is_default = default.as_deref() == Some(desktop_id);
+        let button = gtk::Button::with_label(if is_default {
+            "Default"
+        } else {
+            "Set as Default"
+        });
+        button.set_valign(gtk::Align::Center);
+        if is_default {
+            button.set_sensitive(false);
+        } else if !associations.is_installed(desktop_id) {
+            button.set_sensitive(false);
+            button.set_tooltip_text(Some("Save the entry first"));
+        }
+        row.add_suffix(&button);
+
+        {
+            let associations = self.clone();
+            let mime_type = mime_type.to_string();
+            let desktop_id = desktop_id.to_string();
+            button.connect_clicked(move |_| {
+                Self::set_default(&associations.feedback, &mime_type, &desktop_id);
+                associations.reload();
+            });
+        }
+
+        row
+    }
+
+    /// Makes `desktop_id` the user's default for `mime_type` and reports
+    /// the outcome.
+    pub fn set_default(feedback: &Feedback, mime_type: &str, desktop_id: &str) {
+        match MimeApps::set_user_default(mime_type, desktop_id) {
+            Ok(()) => feedback.success(
+                "mime",
+                format!("{desktop_id} is now the default for {mime_type}"),
+            ),
+            Err(e) => feedback.problem("mime", format!("Could not update mimeapps.list: {e}")),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::DesktopFile;
+use crate::exec::{self, EnvPrefix};
+use crate::flatpak::{self, FlatpakCommand};
+use crate::launch_options::LaunchOptions;
+
+/// Where snapd puts the desktop files of installed snaps, named
+/// `<snap>_<app>.desktop`.
+const SNAP_DATA_DIR: &str = "/var/lib/snapd/desktop";
+
+/// Directory of the commands snapd exposes, `<snap>` or `<snap>.<app>`.
+const SNAP_BIN_DIR: &str = "/snap/bin/";
+
+/// Prefix of the keys appimaged and AppImageLauncher add when integrating
+/// an AppImage, e.g. `X-AppImage-Version`.
+const APPIMAGE_KEY_PREFIX: &str = "X-AppImage-";
+
+/// How the application of an entry was installed, for entries managed by a
+/// packaging format rather than by the distribution or the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Flatpak {
+        app_id: String,
+    },
+    Snap {
+        name: String,
+    },
+    /// The AppImage file, when the entry says where it is.
+    AppImage {
+        path: Option<PathBuf>,
+    },
+}
+
+impl Origin {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Origin::Flatpak { .. } => "Flatpak",
+            Origin::Snap { .. } => "Snap",
+            Origin::AppImage { .. } => "AppImage",
+        }
+    }
+
+    /// One line about the package, e.g. "Snap firefox".
+    pub fn details(&self) -> String {
+        match self {
+            Origin::Flatpak { app_id } => format!("Flatpak application {app_id}"),
+            Origin::Snap { name } => format!("Snap {name}"),
+            Origin::AppImage { path: Some(path) } => format!("AppImage {}", path.display()),
+            Origin::AppImage { path: None } => "AppImage at an unknown location".to_string(),
+        }
+    }
+}
+
+/// `$XDG_DATA_DIRS` with the Flatpak and snapd directories it lacks.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let mut dirs = flatpak::data_dirs();
+    let snap = PathBuf::from(SNAP_DATA_DIR);
+    if !dirs.contains(&snap) {
+        dirs.push(snap);
+    }
+    dirs
+}
+
+/// `applications` directory of snapd.
+pub fn snap_applications_dir() -> PathBuf {
+    Path::new(SNAP_DATA_DIR).join("applications")
+}
+
+/// Tells where the entry at `path` comes from, by its location, the keys the
+/// packaging tools add and the program it runs.
+pub fn detect(path: &Path, file: &DesktopFile) -> Option<Origin> {
+    let entry = &file.desktop_entry;
+    let command = main_command(entry.exec.as_deref().unwrap_or_default());
+    let program = exec::program(&command).unwrap_or_default();
+
+    if flatpak::is_export(path) || entry.extra_key("X-Flatpak").is_some() {
+        let app_id = entry
+            .extra_key("X-Flatpak")
+            .map(str::to_string)
+            .or_else(|| FlatpakCommand::parse(&command).map(|c| c.app_id))
+            .unwrap_or_else(|| file_stem(path));
+        return Some(Origin::Flatpak { app_id });
+    }
+
+    let snap_from_program = program
+        .strip_prefix(SNAP_BIN_DIR)
+        .map(|name| name.split('.').next().unwrap_or(name).to_string());
+    if path.starts_with(SNAP_DATA_DIR)
+        || entry.extra_key("X-SnapInstanceName").is_some()
+        || snap_from_program.is_some()
+    {
+        let name = entry
+            .extra_key("X-SnapInstanceName")
+            .map(str::to_string)
+            .or(snap_from_program)
+            .unwrap_or_else(|| {
+                let stem = file_stem(path);
+                stem.split('_').next().unwrap_or(&stem).to_string()
+            });
+        return Some(Origin::Snap { name });
+    }
+
+    let is_appimage = |program: &str| program.to_lowercase().ends_with(".appimage");
+    if is_appimage(&program)
+        || entry
+            .extra_keys
+            .iter()
+            .any(|(key, _)| key.starts_with(APPIMAGE_KEY_PREFIX))
+    {
+        let path = [Some(program.as_str()), entry.try_exec.as_deref()]
+            .into_iter()
+            .flatten()
+            .find(|p| p.starts_with('/') && is_appimage(p))
+            // Integration tools may rename the file, but run it directly
+            .or(Some(program.as_str()).filter(|p| p.starts_with('/')))
+            .map(PathBuf::from);
+        return Some(Origin::AppImage { path });
+    }
+
+    None
+}
+
+/// The command of an `Exec` value inside its `env` prefix and launch
+/// wrappers, or the value itself if these cannot be read.
+fn main_command(exec: &str) -> String {
+    EnvPrefix::parse(exec)
+        .and_then(|prefix| LaunchOptions::parse(&prefix.command))
+        .map(|options| options.command)
+        .unwrap_or_else(|_| exec.to_string())
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
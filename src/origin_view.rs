@@ -0,0 +1,150 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::desktop_file::DesktopFile;
+use crate::feedback::Feedback;
+use crate::launcher_import;
+use crate::origin::{self, Origin};
+
+/// Form section naming the package an entry belongs to, with actions for
+/// its format. Hidden for entries of the distribution or the user.
+#[derive(Clone)]
+pub struct OriginView {
+    pub widget: adw::PreferencesGroup,
+    row: adw::ActionRow,
+    copy_button: gtk::Button,
+    folder_button: gtk::Button,
+    icon_button: gtk::Button,
+    origin: Rc<RefCell<Option<Origin>>>,
+}
+
+impl OriginView {
+    pub fn new(icon_entry: &gtk::Entry, feedback: &Feedback) -> Self {
+        let widget = adw::PreferencesGroup::new();
+        widget.set_title("Origin");
+        widget.set_visible(false);
+
+        let row = adw::ActionRow::new();
+        let new_button = |label: &str| {
+            let button = gtk::Button::with_label(label);
+            button.set_valign(gtk::Align::Center);
+            row.add_suffix(&button);
+            button
+        };
+        let copy_button = new_button("Copy Name");
+        let folder_button = new_button("Open Folder");
+        folder_button.set_tooltip_text(Some("Open the folder containing the AppImage"));
+        let icon_button = new_button("Re-extract Icon");
+        icon_button.set_tooltip_text(Some(
+            "Extract the icon from the AppImage again, e.g. after it was updated",
+        ));
+        widget.add(&row);
+
+        let view = Self {
+            widget,
+            row,
+            copy_button,
+            folder_button,
+            icon_button,
+            origin: Rc::new(RefCell::new(None)),
+        };
+
+        {
+            let view_clone = view.clone();
+            let feedback = feedback.clone();
+            view.copy_button.connect_clicked(move |button| {
+                let name = match &*view_clone.origin.borrow() {
+                    Some(Origin::Snap { name }) => name.clone(),
+                    Some(Origin::Flatpak { app_id }) => app_id.clone(),
+                    _ => return,
+                };
+                WidgetExt::display(button).clipboard().set_text(&name);
+                feedback.success("origin", format!("Copied {name}"));
+            });
+        }
+
+        {
+            let view_clone = view.clone();
+            let feedback = feedback.clone();
+            view.folder_button.connect_clicked(move |button| {
+                let Some(folder) = view_clone
+                    .appimage()
+                    .and_then(|p| p.parent().map(Path::to_path_buf))
+                else {
+                    return;
+                };
+                let context = WidgetExt::display(button).app_launch_context();
+                let uri = gio::File::for_path(&folder).uri();
+                if let Err(e) = gio::AppInfo::launch_default_for_uri(&uri, Some(&context)) {
+                    feedback.problem(
+                        "origin",
+                        format!("Could not open {}: {e}", folder.display()),
+                    );
+                }
+            });
+        }
+
+        {
+            let view_clone = view.clone();
+            let icon_entry = icon_entry.clone();
+            let feedback = feedback.clone();
+            view.icon_button.connect_clicked(move |_| {
+                let Some(appimage) = view_clone.appimage() else {
+                    return;
+                };
+                match launcher_import::extract_icon(&appimage) {
+                    Some(icon) => {
+                        icon_entry.set_text(&icon.to_string_lossy());
+                        feedback.success(
+                            "origin",
+                            format!("Extracted the icon to {}; save to use it", icon.display()),
+                        );
+                    }
+                    None => feedback.problem(
+                        "origin",
+                        format!("Could not extract an icon from {}", appimage.display()),
+                    ),
+                }
+            });
+        }
+
+        view
+    }
+
+    /// Shows the origin of the entry at `path`, or hides the section for
+    /// unsaved entries and entries without a package.
+    pub fn show(&self, path: Option<&str>, file: Option<&DesktopFile>) {
+        let origin = match (path, file) {
+            (Some(path), Some(file)) => origin::detect(Path::new(path), file),
+            _ => None,
+        };
+
+        self.widget.set_visible(origin.is_some());
+        if let Some(origin) = &origin {
+            self.row.set_title(origin.label());
+            self.row
+                .set_subtitle(&glib::markup_escape_text(&origin.details()));
+            let has_file = matches!(origin, Origin::AppImage { path: Some(path) } if path.exists());
+            self.folder_button.set_visible(has_file);
+            self.icon_button.set_visible(has_file);
+            self.copy_button
+                .set_visible(!matches!(origin, Origin::AppImage { .. }));
+            self.copy_button.set_label(match origin {
+                Origin::Flatpak { .. } => "Copy Application ID",
+                _ => "Copy Snap Name",
+            });
+        }
+        *self.origin.borrow_mut() = origin;
+    }
+
+    fn appimage(&self) -> Option<std::path::PathBuf> {
+        match &*self.origin.borrow() {
+            Some(Origin::AppImage { path }) => path.clone(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::{self, DesktopFile, DesktopFileError, MergeResult};
+use crate::{desktop_id, flatpak, origin, xdg};
+
+/// The system file a user override was derived from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Base {
+    pub system_path: PathBuf,
+    /// SHA-256 of the system file at the time the override was made.
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The system entry is unchanged since the override was made.
+    Current,
+    /// The system entry was updated; the override may carry outdated values.
+    SystemChanged,
+    /// The override was made before base tracking, so its origin is unknown.
+    Untracked,
+}
+
+/// A file in the user's `applications` folder that replaces a system entry
+/// with the same desktop file ID.
+#[derive(Debug, Clone)]
+pub struct Override {
+    pub id: String,
+    pub user_path: PathBuf,
+    pub system_path: PathBuf,
+    pub status: Status,
+}
+
+/// `$XDG_DATA_HOME/menu-entry-manager/overrides`, holding `index.json` with
+/// the base of each override and a copy of each base file.
+fn store_dir() -> Option<PathBuf> {
+    xdg::app_data_dir().map(|dir| dir.join("overrides"))
+}
+
+fn base_copy_path(id: &str) -> Option<PathBuf> {
+    store_dir().map(|dir| dir.join("bases").join(id))
+}
+
+fn load_index() -> BTreeMap<String, Base> {
+    store_dir()
+        .and_then(|dir| fs::read_to_string(dir.join("index.json")).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(index: &BTreeMap<String, Base>) -> Result<(), DesktopFileError> {
+    let dir = store_dir().ok_or_else(|| io::Error::other("No home directory"))?;
+    fs::create_dir_all(&dir)?;
+    let content = serde_json::to_string_pretty(index).map_err(io::Error::from)?;
+    fs::write(dir.join("index.json"), content)?;
+    Ok(())
+}
+
+pub fn checksum(content: &[u8]) -> String {
+    glib::compute_checksum_for_data(glib::ChecksumType::Sha256, content)
+        .map(|sum| sum.to_string())
+        .unwrap_or_default()
+}
+
+/// The system file a user entry with this ID overrides, if any.
+pub fn system_path(id: &str) -> Option<PathBuf> {
+    origin::data_dirs().into_iter().find_map(|dir| {
+        desktop_id::files_in(&dir.join("applications"))
+            .into_iter()
+            .find(|(file_id, _)| file_id == id)
+            .map(|(_, path)| path)
+    })
+}
+
+/// Remembers the current content of `system_path` as the base of the
+/// override `id`, so later updates of the system entry can be merged.
+pub fn record_base(id: &str, system_path: &Path) -> Result<(), DesktopFileError> {
+    let content = fs::read(system_path)?;
+    let copy = base_copy_path(id).ok_or_else(|| io::Error::other("No home directory"))?;
+    if let Some(parent) = copy.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&copy, &content)?;
+
+    let mut index = load_index();
+    index.insert(
+        id.to_string(),
+        Base {
+            system_path: system_path.to_path_buf(),
+            sha256: checksum(&content),
+        },
+    );
+    save_index(&index)
+}
+
+/// Stops tracking the override `id`.
+pub fn forget(id: &str) -> Result<(), DesktopFileError> {
+    let mut index = load_index();
+    if index.remove(id).is_some() {
+        save_index(&index)?;
+    }
+    if let Some(copy) = base_copy_path(id).filter(|p| p.exists()) {
+        fs::remove_file(copy)?;
+    }
+    Ok(())
+}
+
+/// All user overrides of system entries, sorted by ID.
+pub fn list() -> Vec<Override> {
+    let Some(user_apps) = xdg::data_home().map(|d| d.join("applications")) else {
+        return Vec::new();
+    };
+    let index = load_index();
+
+    desktop_id::files_in(&user_apps)
+        .into_iter()
+        .filter_map(|(id, user_path)| {
+            let system_path = system_path(&id)?;
+            let status = match index.get(&id) {
+                Some(base) => {
+                    let current = fs::read(&system_path).map(|c| checksum(&c));
+                    if current.is_ok_and(|sum| sum == base.sha256) {
+                        Status::Current
+                    } else {
+                        Status::SystemChanged
+                    }
+                }
+                None => Status::Untracked,
+            };
+            Some(Override {
+                id,
+                user_path,
+                system_path,
+                status,
+            })
+        })
+        .collect()
+}
+
+/// Carries the user's changes to the base over to the current system entry.
+/// The user's copy is "ours", the updated system entry "theirs".
+pub fn merge(entry: &Override) -> Result<MergeResult, DesktopFileError> {
+    let copy = base_copy_path(&entry.id).ok_or_else(|| io::Error::other("No home directory"))?;
+    let base = fs::read_to_string(copy)?;
+    let system = fs::read_to_string(&entry.system_path)?;
+    let user = fs::read_to_string(&entry.user_path)?;
+    Ok(desktop_file::merge(Some(&base), &user, &system))
+}
+
+/// Writes merged content over the override and makes the current system
+/// entry its new base.
+pub fn apply(entry: &Override, content: &str) -> Result<(), DesktopFileError> {
+    let file = DesktopFile::from_string(content)?;
+    file.save(&entry.user_path)?;
+    record_base(&entry.id, &entry.system_path)
+}
+
+/// Whether `path` is outside the user's data directory or exported there by
+/// Flatpak, so it cannot be deleted or changed in place.
+pub fn is_system_file(path: &Path) -> bool {
+    !xdg::data_home().is_some_and(|home| path.starts_with(home)) || flatpak::is_export(path)
+}
+
+/// Hides a system entry, which cannot be deleted, with a user override that
+/// sets `Hidden=true`. Reverting the override brings the entry back.
+pub fn hide(system_path: &Path) -> Result<Override, DesktopFileError> {
+    let id = desktop_id::from_path(system_path).ok_or_else(|| {
+        DesktopFileError::InvalidValue(
+            "desktop file ID".to_string(),
+            system_path.display().to_string(),
+        )
+    })?;
+    let user_dir =
+        desktop_id::user_dir(&id).ok_or_else(|| io::Error::other("No home directory"))?;
+    let user_path = user_dir.join(&id);
+    if user_path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already overrides this entry", user_path.display()),
+        )
+        .into());
+    }
+
+    let mut file = DesktopFile::from_file(system_path)?;
+    file.desktop_entry.hidden = Some(true);
+    fs::create_dir_all(&user_dir)?;
+    file.save(&user_path)?;
+    record_base(&id, system_path)?;
+
+    Ok(Override {
+        id,
+        user_path,
+        system_path: system_path.to_path_buf(),
+        status: Status::Current,
+    })
+}
+
+/// Deletes the override so the system entry is used again.
+pub fn revert(entry: &Override) -> Result<(), DesktopFileError> {
+    fs::remove_file(&entry.user_path)?;
+    forget(&entry.id)
+}
@@ -0,0 +1,226 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::rc::Rc;
+
+use crate::feedback::Feedback;
+use crate::merge_view;
+use crate::overrides::{self, Override, Status};
+
+/// Opens the window listing the user's overrides of system entries, with
+/// merging for overrides whose system entry was updated. `on_changed` is
+/// called after files were modified.
+pub fn present(parent: &adw::ApplicationWindow, on_changed: Rc<dyn Fn()>) {
+    let window = adw::Window::new();
+    window.set_title(Some("User Overrides"));
+    window.set_transient_for(Some(parent));
+    window.set_default_size(700, 560);
+
+    let header = adw::HeaderBar::new();
+    let title = gtk::Label::new(Some("User Overrides"));
+    title.add_css_class("title-2");
+    header.set_title_widget(Some(&title));
+
+    let refresh_button = gtk::Button::from_icon_name("view-refresh-symbolic");
+    refresh_button.set_tooltip_text(Some("Check Again"));
+    header.pack_start(&refresh_button);
+
+    let description = gtk::Label::new(Some(
+        "Launchers in your applications folder that replace a system entry. When a \
+         package update changes the system entry, merging carries your changes over \
+         to the new version, and keys changed on both sides can be resolved one by one.",
+    ));
+    description.add_css_class("dim-label");
+    description.set_wrap(true);
+    description.set_halign(gtk::Align::Start);
+
+    let list = gtk::ListBox::new();
+    list.set_selection_mode(gtk::SelectionMode::None);
+    list.add_css_class("boxed-list");
+
+    let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    content.set_margin_start(12);
+    content.set_margin_end(12);
+    content.set_margin_top(12);
+    content.set_margin_bottom(12);
+    content.append(&description);
+    content.append(&list);
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_child(Some(&content));
+    scrolled.set_vexpand(true);
+
+    let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    main_box.append(&header);
+    main_box.append(&scrolled);
+
+    let toasts = adw::ToastOverlay::new();
+    toasts.set_child(Some(&main_box));
+    window.set_content(Some(&toasts));
+    let feedback = Feedback::new(&toasts);
+
+    populate(&window, &list, &feedback, &on_changed);
+
+    {
+        let window = window.clone();
+        let list = list.clone();
+        refresh_button.connect_clicked(move |_| populate(&window, &list, &feedback, &on_changed));
+    }
+
+    window.present();
+}
+
+fn populate(
+    window: &adw::Window,
+    list: &gtk::ListBox,
+    feedback: &Feedback,
+    on_changed: &Rc<dyn Fn()>,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    let entries = overrides::list();
+    if entries.is_empty() {
+        let row = adw::ActionRow::new();
+        row.set_title("No overrides");
+        row.set_subtitle("None of your launchers replaces a system entry");
+        list.append(&row);
+        return;
+    }
+
+    for entry in entries {
+        list.append(&create_row(window, list, &entry, feedback, on_changed));
+    }
+}
+
+fn create_row(
+    window: &adw::Window,
+    list: &gtk::ListBox,
+    entry: &Override,
+    feedback: &Feedback,
+    on_changed: &Rc<dyn Fn()>,
+) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(&glib::markup_escape_text(&entry.id));
+    let status = match entry.status {
+        Status::Current => "Up to date with the system entry",
+        Status::SystemChanged => "⚠ The system entry was updated since this override was made",
+        Status::Untracked => "Made before tracking; track it to detect future updates",
+    };
+    row.set_subtitle(&glib::markup_escape_text(&format!(
+        "{status}\nOverrides {}",
+        entry.system_path.display()
+    )));
+    row.set_tooltip_text(Some(&entry.user_path.display().to_string()));
+
+    let refresh = {
+        let window = window.clone();
+        let list = list.clone();
+        let feedback = feedback.clone();
+        let on_changed = on_changed.clone();
+        Rc::new(move || {
+            populate(&window, &list, &feedback, &on_changed);
+            on_changed();
+        })
+    };
+
+    match entry.status {
+        Status::SystemChanged => {
+            let merge_button = gtk::Button::with_label("Merge…");
+            merge_button.add_css_class("suggested-action");
+            merge_button.set_valign(gtk::Align::Center);
+            let window = window.clone();
+            let entry = entry.clone();
+            let refresh = refresh.clone();
+            let feedback = feedback.clone();
+            merge_button.connect_clicked(move |_| match overrides::merge(&entry) {
+                Ok(result) => {
+                    let entry = entry.clone();
+                    let refresh = refresh.clone();
+                    let feedback = feedback.clone();
+                    merge_view::present(
+                        &window,
+                        &format!("Merge {}", entry.id),
+                        result,
+                        "your override",
+                        "the updated system entry",
+                        Rc::new(move |content| {
+                            overrides::apply(&entry, content)?;
+                            feedback.success("overrides", format!("Merged {}", entry.id));
+                            refresh();
+                            Ok(())
+                        }),
+                    );
+                }
+                Err(e) => feedback.error("overrides", &format!("Could not merge {}", entry.id), &e),
+            });
+            row.add_suffix(&merge_button);
+        }
+        Status::Untracked => {
+            let track_button = gtk::Button::with_label("Track");
+            track_button.set_tooltip_text(Some(
+                "Treat the current system entry as the version this override was made from",
+            ));
+            track_button.set_valign(gtk::Align::Center);
+            let entry = entry.clone();
+            let refresh = refresh.clone();
+            let feedback = feedback.clone();
+            track_button.connect_clicked(move |_| {
+                match overrides::record_base(&entry.id, &entry.system_path) {
+                    Ok(()) => feedback.success("overrides", format!("Tracking {}", entry.id)),
+                    Err(e) => {
+                        feedback.error("overrides", &format!("Could not track {}", entry.id), &e)
+                    }
+                }
+                refresh();
+            });
+            row.add_suffix(&track_button);
+        }
+        Status::Current => {}
+    }
+
+    let revert_button = gtk::Button::from_icon_name("edit-undo-symbolic");
+    revert_button.set_tooltip_text(Some("Delete the Override and Use the System Entry"));
+    revert_button.set_valign(gtk::Align::Center);
+    revert_button.add_css_class("flat");
+    {
+        let window = window.clone();
+        let entry = entry.clone();
+        let feedback = feedback.clone();
+        revert_button.connect_clicked(move |_| {
+            let dialog = gtk::MessageDialog::builder()
+                .transient_for(&window)
+                .modal(true)
+                .message_type(gtk::MessageType::Warning)
+                .buttons(gtk::ButtonsType::OkCancel)
+                .text(format!("Revert {} to the System Entry?", entry.id))
+                .secondary_text(format!(
+                    "{} will be deleted, together with your changes.",
+                    entry.user_path.display()
+                ))
+                .build();
+            let entry = entry.clone();
+            let refresh = refresh.clone();
+            let feedback = feedback.clone();
+            dialog.connect_response(move |dialog, response| {
+                if response == gtk::ResponseType::Ok {
+                    match overrides::revert(&entry) {
+                        Ok(()) => feedback.success("overrides", format!("Reverted {}", entry.id)),
+                        Err(e) => feedback.error(
+                            "overrides",
+                            &format!("Could not revert {}", entry.id),
+                            &e,
+                        ),
+                    }
+                    refresh();
+                }
+                dialog.close();
+            });
+            dialog.show();
+        });
+    }
+    row.add_suffix(&revert_button);
+
+    row
+}
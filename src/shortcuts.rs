@@ -0,0 +1,162 @@
+use gtk4 as gtk;
+use libadwaita::prelude::*;
+
+/// A command reachable from the keyboard, listed in the command palette and
+/// the shortcuts window.
+pub struct Command {
+    /// Detailed action name, e.g. `win.save`.
+    pub action: &'static str,
+    pub title: &'static str,
+    pub section: &'static str,
+    /// Accelerators in `gtk::accelerator_parse` format.
+    pub accels: &'static [&'static str],
+    /// Only active while the file list has the focus, as the keys edit text
+    /// elsewhere.
+    pub list_only: bool,
+}
+
+const fn command(
+    action: &'static str,
+    title: &'static str,
+    section: &'static str,
+    accels: &'static [&'static str],
+) -> Command {
+    Command {
+        action,
+        title,
+        section,
+        accels,
+        list_only: false,
+    }
+}
+
+pub const COMMANDS: &[Command] = &[
+    command("win.new", "New Desktop File", "Entries", &["<Control>n"]),
+    command("win.save", "Save", "Entries", &["<Control>s"]),
+    command(
+        "win.save-as-override",
+        "Save as User Override",
+        "Entries",
+        &[],
+    ),
+    command("win.duplicate", "Duplicate", "Entries", &["<Control>d"]),
+    Command {
+        action: "win.delete",
+        title: "Delete",
+        section: "Entries",
+        accels: &["Delete"],
+        list_only: true,
+    },
+    command("win.test-launch", "Test Launch", "Entries", &["<Control>r"]),
+    command(
+        "win.search",
+        "Search Entries",
+        "Navigation",
+        &["<Control>f"],
+    ),
+    command(
+        "win.next-entry",
+        "Next Entry",
+        "Navigation",
+        &["<Alt>Down", "<Control>Page_Down"],
+    ),
+    command(
+        "win.previous-entry",
+        "Previous Entry",
+        "Navigation",
+        &["<Alt>Up", "<Control>Page_Up"],
+    ),
+    command(
+        "win.command-palette",
+        "Command Palette",
+        "Navigation",
+        &["<Control>k"],
+    ),
+    command("win.autostart", "Autostart", "Windows", &[]),
+    command(
+        "win.menu-structure",
+        "Application Menu Structure",
+        "Windows",
+        &[],
+    ),
+    command("win.health-report", "Health Report", "Windows", &[]),
+    command("win.overrides", "User Overrides", "Windows", &[]),
+    command("win.cleanup", "Clean Up Launchers", "Windows", &[]),
+    command("app.show-log", "Log", "Windows", &["<Control>l"]),
+    command(
+        "win.show-shortcuts",
+        "Keyboard Shortcuts",
+        "Windows",
+        &["<Control>question"],
+    ),
+];
+
+/// Registers the accelerators of all commands that work anywhere.
+pub fn set_accels(app: &gtk::Application) {
+    for command in COMMANDS.iter().filter(|c| !c.list_only) {
+        app.set_accels_for_action(command.action, command.accels);
+    }
+}
+
+/// Adds the accelerators of the commands that only work in `list`.
+pub fn attach_list_shortcuts(list: &impl IsA<gtk::Widget>) {
+    let controller = gtk::ShortcutController::new();
+    for command in COMMANDS.iter().filter(|c| c.list_only) {
+        for accel in command.accels {
+            controller.add_shortcut(gtk::Shortcut::new(
+                gtk::ShortcutTrigger::parse_string(accel),
+                Some(gtk::NamedAction::new(command.action)),
+            ));
+        }
+    }
+    list.add_controller(controller);
+}
+
+/// The first accelerator of `command` as shown to the user, e.g. "Ctrl+S".
+pub fn accel_label(command: &Command) -> Option<String> {
+    let (key, modifiers) = gtk::accelerator_parse(*command.accels.first()?)?;
+    Some(gtk::accelerator_get_label(key, modifiers).to_string())
+}
+
+/// Opens the window listing the keyboard shortcuts by section.
+pub fn present_window(parent: &impl IsA<gtk::Window>) {
+    let mut sections: Vec<&str> = Vec::new();
+    for command in COMMANDS {
+        if !sections.contains(&command.section) {
+            sections.push(command.section);
+        }
+    }
+
+    let mut groups = String::new();
+    for section in sections {
+        groups.push_str(&format!(
+            r#"<child><object class="GtkShortcutsGroup"><property name="title">{}</property>"#,
+            glib::markup_escape_text(section)
+        ));
+        for command in COMMANDS
+            .iter()
+            .filter(|c| c.section == section && !c.accels.is_empty())
+        {
+            let title = if command.list_only {
+                format!("{} (in the file list)", command.title)
+            } else {
+                command.title.to_string()
+            };
+            groups.push_str(&format!(
+                r#"<child><object class="GtkShortcutsShortcut"><property name="title">{}</property><property name="accelerator">{}</property></object></child>"#,
+                glib::markup_escape_text(&title),
+                glib::markup_escape_text(&command.accels.join(" "))
+            ));
+        }
+        groups.push_str("</object></child>");
+    }
+
+    let ui = format!(
+        r#"<interface><object class="GtkShortcutsWindow" id="window"><property name="modal">true</property><child><object class="GtkShortcutsSection"><property name="section-name">shortcuts</property>{groups}</object></child></object></interface>"#
+    );
+    let builder = gtk::Builder::from_string(&ui);
+    if let Some(window) = builder.object::<gtk::ShortcutsWindow>("window") {
+        window.set_transient_for(Some(parent));
+        window.present();
+    }
+}
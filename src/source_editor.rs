@@ -0,0 +1,138 @@
+use gtk4 as gtk;
+use libadwaita::prelude::*;
+
+use crate::desktop_file::DesktopFile;
+
+/// Raw text editor for a desktop file with simple key-file highlighting and
+/// per-line diagnostics.
+#[derive(Clone)]
+pub struct SourceEditor {
+    pub widget: gtk::Box,
+    pub buffer: gtk::TextBuffer,
+    diagnostics: gtk::Label,
+}
+
+impl SourceEditor {
+    pub fn new() -> Self {
+        let buffer = gtk::TextBuffer::new(None);
+        buffer.create_tag(
+            Some("group"),
+            &[("weight", &700), ("foreground", &"#1c71d8")],
+        );
+        buffer.create_tag(Some("key"), &[("foreground", &"#26a269")]);
+        buffer.create_tag(Some("locale"), &[("foreground", &"#c64600")]);
+        buffer.create_tag(
+            Some("comment"),
+            &[
+                ("foreground", &"#77767b"),
+                ("style", &gtk::pango::Style::Italic),
+            ],
+        );
+        buffer.create_tag(
+            Some("error"),
+            &[
+                ("underline", &gtk::pango::Underline::Error),
+                ("background", &"rgba(224, 27, 36, 0.15)"),
+            ],
+        );
+
+        let text_view = gtk::TextView::with_buffer(&buffer);
+        text_view.set_monospace(true);
+        text_view.set_wrap_mode(gtk::WrapMode::None);
+        text_view.set_top_margin(6);
+        text_view.set_bottom_margin(6);
+        text_view.set_left_margin(6);
+        text_view.set_right_margin(6);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_child(Some(&text_view));
+        scrolled.set_hexpand(true);
+        scrolled.set_vexpand(true);
+
+        let diagnostics = gtk::Label::new(None);
+        diagnostics.add_css_class("error");
+        diagnostics.set_halign(gtk::Align::Start);
+        diagnostics.set_wrap(true);
+        diagnostics.set_selectable(true);
+        diagnostics.set_visible(false);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&scrolled);
+        widget.append(&diagnostics);
+
+        Self {
+            widget,
+            buffer,
+            diagnostics,
+        }
+    }
+
+    pub fn text(&self) -> String {
+        let (start, end) = self.buffer.bounds();
+        self.buffer.text(&start, &end, false).to_string()
+    }
+
+    pub fn set_text(&self, text: &str) {
+        self.buffer.set_text(text);
+    }
+
+    /// Re-applies syntax highlighting and refreshes the diagnostics list.
+    pub fn highlight(&self) {
+        let (start, end) = self.buffer.bounds();
+        self.buffer.remove_all_tags(&start, &end);
+
+        let content = self.text();
+
+        for (line_no, line) in content.lines().enumerate() {
+            let trimmed = line.trim();
+            let indent = (line.chars().count() - line.trim_start().chars().count()) as i32;
+            let len = trimmed.chars().count() as i32;
+
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            if trimmed.starts_with('#') {
+                self.apply_tag("comment", line_no, indent, indent + len);
+            } else if trimmed.starts_with('[') {
+                self.apply_tag("group", line_no, indent, indent + len);
+            } else if let Some(pos) = trimmed.find('=') {
+                let key = &trimmed[..pos];
+                let key_len = key.trim_end().chars().count() as i32;
+                match key.find('[') {
+                    Some(bracket) => {
+                        let bracket = key[..bracket].chars().count() as i32;
+                        self.apply_tag("key", line_no, indent, indent + bracket);
+                        self.apply_tag("locale", line_no, indent + bracket, indent + key_len);
+                    }
+                    None => self.apply_tag("key", line_no, indent, indent + key_len),
+                }
+            }
+        }
+
+        let mut messages = Vec::new();
+        if !content.trim().is_empty() {
+            for problem in DesktopFile::diagnostics(&content) {
+                if let Some(line) = problem.line() {
+                    if let Some(raw_line) = content.lines().nth(line - 1) {
+                        self.apply_tag("error", line - 1, 0, raw_line.chars().count() as i32);
+                    }
+                }
+                messages.push(problem.to_string());
+            }
+        }
+
+        self.diagnostics.set_text(&messages.join("\n"));
+        self.diagnostics.set_visible(!messages.is_empty());
+    }
+
+    fn apply_tag(&self, tag: &str, line: usize, start: i32, end: i32) {
+        let line = line as i32;
+        if let (Some(start), Some(end)) = (
+            self.buffer.iter_at_line_offset(line, start),
+            self.buffer.iter_at_line_offset(line, end),
+        ) {
+            self.buffer.apply_tag_by_name(tag, &start, &end);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::desktop_file::{DesktopFile, DesktopFileError};
+use crate::xdg;
+use crate::{exec, logging};
+
+/// Browsers that support `--app=URL`, in order of preference.
+const APP_MODE_BROWSERS: &[&str] = &[
+    "chromium",
+    "chromium-browser",
+    "google-chrome",
+    "google-chrome-stable",
+    "brave-browser",
+    "microsoft-edge",
+    "vivaldi",
+];
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub name: String,
+    pub description: String,
+    pub file: DesktopFile,
+    /// Set for templates loaded from the user's template directory.
+    pub path: Option<PathBuf>,
+}
+
+impl Template {
+    fn builtin(name: &str, description: &str, file: DesktopFile) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            file,
+            path: None,
+        }
+    }
+}
+
+/// Built-in templates followed by the user's own templates.
+pub fn all_templates() -> Vec<Template> {
+    let mut templates = builtin_templates();
+    templates.extend(user_templates());
+    templates
+}
+
+pub fn builtin_templates() -> Vec<Template> {
+    let mut templates = Vec::new();
+
+    templates.push(Template::builtin(
+        "Application",
+        "Empty application launcher",
+        DesktopFile::new("New Application".to_string(), "".to_string()),
+    ));
+
+    let mut appimage = DesktopFile::new(
+        "New AppImage".to_string(),
+        "/path/to/Application.AppImage %U".to_string(),
+    );
+    appimage.desktop_entry.try_exec = Some("/path/to/Application.AppImage".to_string());
+    appimage.desktop_entry.categories = Some("Utility;".to_string());
+    templates.push(Template::builtin(
+        "AppImage",
+        "Self-contained AppImage bundle",
+        appimage,
+    ));
+
+    let mut script = DesktopFile::new(
+        "New Script".to_string(),
+        "/bin/sh /path/to/script.sh".to_string(),
+    );
+    script.desktop_entry.terminal = Some(true);
+    script.desktop_entry.icon = Some("utilities-terminal".to_string());
+    script.desktop_entry.categories = Some("Utility;".to_string());
+    templates.push(Template::builtin(
+        "Shell Script",
+        "Run a script in a terminal window",
+        script,
+    ));
+
+    let browser = find_program(APP_MODE_BROWSERS).unwrap_or("chromium");
+    let mut web_app = DesktopFile::new(
+        "New Web App".to_string(),
+        format!("{browser} --app=https://example.com"),
+    );
+    web_app.desktop_entry.icon = Some("web-browser".to_string());
+    web_app.desktop_entry.categories = Some("Network;".to_string());
+    web_app.desktop_entry.startup_wm_class = Some("example.com".to_string());
+    templates.push(Template::builtin(
+        "Web App",
+        "Open a site in its own browser window",
+        web_app,
+    ));
+
+    let mut wine = DesktopFile::new(
+        "New Windows Game".to_string(),
+        "wine /path/to/game/Game.exe".to_string(),
+    );
+    wine.desktop_entry.path = Some("/path/to/game".to_string());
+    wine.desktop_entry.icon = Some("wine".to_string());
+    wine.desktop_entry.categories = Some("Game;".to_string());
+    wine.desktop_entry.prefers_non_default_gpu = Some(true);
+    templates.push(Template::builtin(
+        "Wine / Proton Game",
+        "Windows executable run through Wine",
+        wine,
+    ));
+
+    let mut jar = DesktopFile::new(
+        "New Java Application".to_string(),
+        "java -jar /path/to/application.jar %f".to_string(),
+    );
+    jar.desktop_entry.try_exec = Some("java".to_string());
+    jar.desktop_entry.icon = Some("java".to_string());
+    jar.desktop_entry.categories = Some("Development;".to_string());
+    templates.push(Template::builtin(
+        "Java JAR",
+        "Executable Java archive",
+        jar,
+    ));
+
+    let mut venv = DesktopFile::new(
+        "New Python Application".to_string(),
+        "/path/to/project/.venv/bin/python /path/to/project/main.py %F".to_string(),
+    );
+    venv.desktop_entry.path = Some("/path/to/project".to_string());
+    venv.desktop_entry.try_exec = Some("/path/to/project/.venv/bin/python".to_string());
+    venv.desktop_entry.icon = Some("text-x-python".to_string());
+    venv.desktop_entry.categories = Some("Development;".to_string());
+    templates.push(Template::builtin(
+        "Python Virtualenv",
+        "Python script run with a virtualenv interpreter",
+        venv,
+    ));
+
+    let mut link = DesktopFile::new("New Link".to_string(), "".to_string());
+    link.desktop_entry.entry_type = "Link".to_string();
+    link.desktop_entry.exec = None;
+    link.desktop_entry.terminal = None;
+    link.desktop_entry.dbus_activatable = None;
+    link.desktop_entry.url = Some("https://example.com".to_string());
+    link.desktop_entry.icon = Some("text-html".to_string());
+    templates.push(Template::builtin("Link", "Shortcut to a URL", link));
+
+    let mut directory = DesktopFile::new("New Folder".to_string(), "".to_string());
+    directory.desktop_entry.entry_type = "Directory".to_string();
+    directory.desktop_entry.exec = None;
+    directory.desktop_entry.terminal = None;
+    directory.desktop_entry.dbus_activatable = None;
+    directory.desktop_entry.icon = Some("folder".to_string());
+    templates.push(Template::builtin(
+        "Directory",
+        "Menu folder entry",
+        directory,
+    ));
+
+    templates
+}
+
+/// `$XDG_CONFIG_HOME/menu-entry-manager/templates`
+pub fn user_templates_dir() -> Option<PathBuf> {
+    xdg::app_config_dir().map(|dir| dir.join("templates"))
+}
+
+pub fn user_templates() -> Vec<Template> {
+    let mut templates = Vec::new();
+
+    let Some(dir) = user_templates_dir() else {
+        return templates;
+    };
+
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "desktop"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            match DesktopFile::from_file(&path) {
+                Ok(file) => templates.push(Template {
+                    name: file.desktop_entry.name.clone(),
+                    description: file
+                        .desktop_entry
+                        .comment
+                        .clone()
+                        .unwrap_or_else(|| "User template".to_string()),
+                    file,
+                    path: Some(path),
+                }),
+                Err(e) => logging::warn(
+                    "templates",
+                    format!("Could not load template {}: {e}", path.display()),
+                ),
+            }
+        }
+    }
+
+    templates
+}
+
+/// Saves `file` as a user template, named after its `Name` key. A numeric
+/// suffix keeps it from replacing a template with a similar name.
+pub fn save_user_template(file: &DesktopFile) -> Result<PathBuf, DesktopFileError> {
+    let dir = user_templates_dir().ok_or_else(|| io::Error::other("No home directory"))?;
+    fs::create_dir_all(&dir)?;
+
+    let stem: String = file
+        .desktop_entry
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .to_lowercase();
+    let path = std::iter::once(format!("{stem}.desktop"))
+        .chain((2..).map(|n| format!("{stem}-{n}.desktop")))
+        .map(|name| dir.join(name))
+        .find(|path| !path.exists())
+        .unwrap_or_else(|| dir.join(format!("{stem}.desktop")));
+    file.save(&path)?;
+    Ok(path)
+}
+
+fn find_program<'a>(candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .copied()
+        .find(|name| exec::find_program(name).is_some())
+}
@@ -0,0 +1,110 @@
+use gio::prelude::*;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::desktop_file::DesktopFileError;
+use crate::xdg;
+
+/// A file moved to the trash, with what is needed to put it back.
+#[derive(Debug, Clone)]
+pub struct Trashed {
+    pub original: PathBuf,
+    file: PathBuf,
+    info: PathBuf,
+}
+
+/// Moves `path` to the freedesktop.org trash. Returns `None` when the file
+/// went to a trash other than the user's, e.g. on another file system; it
+/// can still be restored from the file manager there.
+pub fn trash(path: &Path) -> Result<Option<Trashed>, DesktopFileError> {
+    gio::File::for_path(path)
+        .trash(None::<&gio::Cancellable>)
+        .map_err(to_io_error)?;
+    Ok(locate(path))
+}
+
+/// Moves a trashed file back to where it was.
+pub fn restore(item: &Trashed) -> Result<(), DesktopFileError> {
+    if item.original.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} exists again", item.original.display()),
+        )
+        .into());
+    }
+    if let Some(parent) = item.original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::rename(&item.file, &item.original)?;
+    fs::remove_file(&item.info)?;
+    Ok(())
+}
+
+/// `$XDG_DATA_HOME/Trash`, where files of the user's home go.
+fn home_trash() -> Option<PathBuf> {
+    xdg::data_home().map(|dir| dir.join("Trash"))
+}
+
+/// The most recent trash entry of `original`, found through the `Path` and
+/// `DeletionDate` keys of the `.trashinfo` files.
+fn locate(original: &Path) -> Option<Trashed> {
+    let trash = home_trash()?;
+    let mut best: Option<(String, Trashed)> = None;
+
+    for entry in fs::read_dir(trash.join("info")).ok()?.flatten() {
+        let info = entry.path();
+        let Some(name) = info
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".trashinfo"))
+        else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&info) else {
+            continue;
+        };
+
+        let mut path = None;
+        let mut date = String::new();
+        for line in content.lines() {
+            if let Some(value) = line.strip_prefix("Path=") {
+                path = glib::Uri::unescape_string(value, None::<&str>).map(PathBuf::from);
+            } else if let Some(value) = line.strip_prefix("DeletionDate=") {
+                date = value.to_string();
+            }
+        }
+        if path.as_deref() != Some(original) {
+            continue;
+        }
+        // ISO 8601 dates compare in time order
+        if best
+            .as_ref()
+            .is_some_and(|(best_date, _)| *best_date >= date)
+        {
+            continue;
+        }
+        best = Some((
+            date,
+            Trashed {
+                original: original.to_path_buf(),
+                file: trash.join("files").join(name),
+                info,
+            },
+        ));
+    }
+
+    best.map(|(_, item)| item)
+}
+
+fn to_io_error(error: glib::Error) -> io::Error {
+    let kind = match error.kind::<gio::IOErrorEnum>() {
+        Some(gio::IOErrorEnum::NotFound) => io::ErrorKind::NotFound,
+        Some(gio::IOErrorEnum::PermissionDenied) => io::ErrorKind::PermissionDenied,
+        Some(gio::IOErrorEnum::ReadOnly) => io::ErrorKind::ReadOnlyFilesystem,
+        Some(gio::IOErrorEnum::NoSpace) => io::ErrorKind::StorageFull,
+        Some(gio::IOErrorEnum::NotSupported) => io::ErrorKind::Unsupported,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, error.message().to_string())
+}
@@ -1,27 +1,52 @@
 use glib::Propagation;
+use gtk::gdk;
 use gtk4 as gtk;
 use libadwaita as adw;
 use libadwaita::prelude::*;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
-use std::env;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
-use crate::desktop_file::{get_desktop_file_paths, DesktopFile};
-
-const COMMON_CATEGORIES: &[&str] = &[
-    "AudioVideo",
-    "Development",
-    "Education",
-    "Game",
-    "Graphics",
-    "Network",
-    "Office",
-    "Settings",
-    "System",
-    "Utility",
-];
+use crate::categories;
+use crate::category_tree::CategoryTree;
+use crate::desktop_file::{
+    get_desktop_file_paths, get_directory_file_paths, DesktopFile, DesktopFileError,
+};
+use crate::desktop_id;
+use crate::env_editor::EnvEditor;
+use crate::exec;
+use crate::feedback::{self, Feedback};
+use crate::flatpak;
+use crate::flatpak_editor::FlatpakEditor;
+use crate::key_table::KeyTable;
+use crate::launch_options_editor::LaunchOptionsEditor;
+use crate::launcher_import;
+use crate::menu_preview_view::MenuPreviewView;
+use crate::mime_associations::MimeAssociations;
+use crate::origin::{self, Origin};
+use crate::origin_view::OriginView;
+use crate::overrides::Override;
+use crate::source_editor::SourceEditor;
+use crate::templates;
+use crate::trash::Trashed;
+use crate::url_handlers::UrlHandlers;
+use crate::{
+    autostart, autostart_view, broken_entries, cleanup_view, command_palette, health_view,
+    log_view, logging, menu_tree_view, merge_view, overrides, overrides_view, shortcuts, trash,
+};
+
+/// What "Undo" puts back after a deletion.
+enum Undo {
+    /// A user file moved to the trash.
+    Restore(Trashed),
+    /// A system entry hidden by a user override.
+    Unhide(Override),
+}
+
+/// File name, path, strict parse error, program problems and origin of a file
+/// list row.
+type FileListItem = (String, String, Option<String>, Vec<String>, Option<Origin>);
 
 pub struct DesktopFileManagerWindow {
     window: adw::ApplicationWindow,
@@ -45,9 +70,9 @@ pub struct DesktopFileManagerWindow {
     #[allow(dead_code)]
     categories_popover: gtk::Popover,
     #[allow(dead_code)]
-    categories_checkboxes: Vec<gtk::CheckButton>,
+    category_tree: CategoryTree,
     #[allow(dead_code)]
-    categories_custom_entry: gtk::Entry,
+    categories_warning: gtk::Label,
     #[allow(dead_code)]
     categories_visible_entry: gtk::Entry,
     #[allow(dead_code)]
@@ -62,11 +87,54 @@ pub struct DesktopFileManagerWindow {
     url_entry: gtk::Entry,
     #[allow(dead_code)]
     mime_type_entry: gtk::Entry,
+    #[allow(dead_code)]
+    generic_name_entry: gtk::Entry,
+    #[allow(dead_code)]
+    version_entry: gtk::Entry,
+    #[allow(dead_code)]
+    try_exec_entry: gtk::Entry,
+    #[allow(dead_code)]
+    startup_wm_class_entry: gtk::Entry,
+    #[allow(dead_code)]
+    implements_entry: gtk::Entry,
+    #[allow(dead_code)]
+    actions_entry: gtk::Entry,
+    #[allow(dead_code)]
+    only_show_in_entry: gtk::Entry,
+    #[allow(dead_code)]
+    not_show_in_entry: gtk::Entry,
+    #[allow(dead_code)]
+    startup_notify_switch: gtk::Switch,
+    #[allow(dead_code)]
+    dbus_activatable_switch: gtk::Switch,
+    #[allow(dead_code)]
+    prefers_non_default_gpu_switch: gtk::Switch,
+    #[allow(dead_code)]
+    single_main_window_switch: gtk::Switch,
+    #[allow(dead_code)]
+    no_display_switch: gtk::Switch,
+    #[allow(dead_code)]
+    additional_keys_table: KeyTable,
+
+    #[allow(dead_code)]
+    view_stack: adw::ViewStack,
+    #[allow(dead_code)]
+    source_editor: SourceEditor,
+    #[allow(dead_code)]
+    mime_associations: MimeAssociations,
+    #[allow(dead_code)]
+    url_handlers: UrlHandlers,
+    #[allow(dead_code)]
+    origin_view: OriginView,
+    #[allow(dead_code)]
+    menu_preview: MenuPreviewView,
 
     #[allow(dead_code)]
     file_list: gtk::ListBox,
     #[allow(dead_code)]
     search_entry: gtk::Entry,
+    #[allow(dead_code)]
+    broken_filter_button: gtk::ToggleButton,
 }
 
 impl DesktopFileManagerWindow {
@@ -75,27 +143,29 @@ impl DesktopFileManagerWindow {
         window.set_title(Some("Menu Entry Manager"));
         window.set_default_size(1200, 800);
 
+        let toasts = adw::ToastOverlay::new();
+        let feedback = Feedback::new(&toasts);
+
+        let show_log = gio::SimpleAction::new("show-log", None);
+        show_log.connect_activate(glib::clone!(@weak window => move |_, _| {
+            log_view::present(&window);
+        }));
+        app.add_action(&show_log);
+        shortcuts::set_accels(app);
+
         let current_file = Rc::new(RefCell::new(None));
         let file_path = Rc::new(RefCell::new(None));
         let updating_ui = Rc::new(Cell::new(false));
 
         let categories_popover = gtk::Popover::new();
-        let categories_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
-        let mut categories_checkboxes = Vec::new();
-        for &cat in COMMON_CATEGORIES {
-            let check = gtk::CheckButton::with_label(cat);
-            categories_box.append(&check);
-            categories_checkboxes.push(check);
-        }
-        let categories_custom_entry = gtk::Entry::new();
-        categories_custom_entry
-            .set_placeholder_text(Some("Custom categories (semicolon separated)"));
-        println!(
-            "Created categories entry with ID: {:?}",
-            categories_custom_entry.as_ptr()
-        );
-        categories_box.append(&categories_custom_entry);
-        categories_popover.set_child(Some(&categories_box));
+        let category_tree = CategoryTree::new();
+        categories_popover.set_child(Some(&category_tree.widget));
+
+        let categories_warning = gtk::Label::new(None);
+        categories_warning.add_css_class("warning");
+        categories_warning.set_wrap(true);
+        categories_warning.set_halign(gtk::Align::Start);
+        categories_warning.set_visible(false);
 
         let (
             name_entry,
@@ -109,43 +179,117 @@ impl DesktopFileManagerWindow {
             type_combo,
             url_entry,
             mime_type_entry,
+            generic_name_entry,
+            version_entry,
+            try_exec_entry,
+            startup_wm_class_entry,
+            implements_entry,
+            actions_entry,
+            only_show_in_entry,
+            not_show_in_entry,
+            startup_notify_switch,
+            dbus_activatable_switch,
+            prefers_non_default_gpu_switch,
+            single_main_window_switch,
+            no_display_switch,
+            additional_keys_table,
         ) = Self::create_form_fields();
 
+        let source_editor = SourceEditor::new();
+
+        let mime_associations = MimeAssociations::new(&feedback);
+        mime_associations.attach_completion(&mime_type_entry);
+        let url_handlers = UrlHandlers::new(
+            &mime_type_entry,
+            &exec_entry,
+            mime_associations.associations(),
+            &feedback,
+        );
+
+        let origin_view = OriginView::new(&icon_entry, &feedback);
+
+        let menu_preview = MenuPreviewView::new(&current_file);
+
         let file_list = gtk::ListBox::new();
         file_list.set_selection_mode(gtk::SelectionMode::Single);
+        shortcuts::attach_list_shortcuts(&file_list);
 
         let main_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
         main_box.set_hexpand(true);
         main_box.set_vexpand(true);
 
-        let (left_panel, new_button, search_entry) = Self::create_left_panel(&file_list);
+        let (
+            left_panel,
+            new_button,
+            search_entry,
+            templates_popover,
+            templates_list,
+            save_template_button,
+            import_button,
+            autostart_button,
+            menu_button,
+            health_button,
+            overrides_button,
+            broken_filter_button,
+            cleanup_button,
+        ) = Self::create_left_panel(&file_list);
         left_panel.set_hexpand(false);
         left_panel.set_vexpand(true);
 
-        let (right_panel, save_button, delete_button, categories_visible_entry) =
-            Self::create_right_panel(
-                &name_entry,
-                &exec_entry,
-                &comment_entry,
-                &icon_entry,
-                &path_entry,
-                &keywords_entry,
-                &terminal_switch,
-                &hidden_switch,
-                &type_combo,
-                &url_entry,
-                &mime_type_entry,
-                &categories_popover,
-                &categories_checkboxes,
-                &categories_custom_entry,
-            );
+        let (
+            right_panel,
+            save_button,
+            delete_button,
+            merge_button,
+            categories_visible_entry,
+            view_stack,
+            desktop_id_entry,
+            desktop_id_status,
+            rename_button,
+            add_to_autostart_button,
+            override_banner,
+        ) = Self::create_right_panel(
+            &name_entry,
+            &exec_entry,
+            &comment_entry,
+            &icon_entry,
+            &path_entry,
+            &keywords_entry,
+            &terminal_switch,
+            &hidden_switch,
+            &type_combo,
+            &url_entry,
+            &mime_type_entry,
+            &generic_name_entry,
+            &version_entry,
+            &try_exec_entry,
+            &startup_wm_class_entry,
+            &implements_entry,
+            &actions_entry,
+            &only_show_in_entry,
+            &not_show_in_entry,
+            &startup_notify_switch,
+            &dbus_activatable_switch,
+            &prefers_non_default_gpu_switch,
+            &single_main_window_switch,
+            &no_display_switch,
+            &additional_keys_table,
+            &categories_popover,
+            &categories_warning,
+            &source_editor,
+            &mime_associations,
+            &url_handlers,
+            &origin_view,
+            &menu_preview,
+        );
         right_panel.set_hexpand(true);
         right_panel.set_vexpand(true);
 
         main_box.append(&left_panel);
         main_box.append(&right_panel);
 
-        window.set_content(Some(&main_box));
+        toasts.set_child(Some(&main_box));
+        window.set_content(Some(&toasts));
 
         Self::connect_signals(
             &window,
@@ -164,14 +308,51 @@ impl DesktopFileManagerWindow {
             &type_combo,
             &url_entry,
             &mime_type_entry,
+            &generic_name_entry,
+            &version_entry,
+            &try_exec_entry,
+            &startup_wm_class_entry,
+            &implements_entry,
+            &actions_entry,
+            &only_show_in_entry,
+            &not_show_in_entry,
+            &startup_notify_switch,
+            &dbus_activatable_switch,
+            &prefers_non_default_gpu_switch,
+            &single_main_window_switch,
+            &no_display_switch,
+            &additional_keys_table,
             &new_button,
+            &templates_popover,
+            &templates_list,
+            &save_template_button,
+            &import_button,
+            &autostart_button,
+            &menu_button,
+            &health_button,
+            &overrides_button,
             &save_button,
             &delete_button,
+            &merge_button,
             &categories_popover,
-            &categories_checkboxes[..],
-            &categories_custom_entry,
+            &category_tree,
+            &categories_warning,
             &categories_visible_entry,
             &search_entry,
+            &broken_filter_button,
+            &cleanup_button,
+            &view_stack,
+            &source_editor,
+            &mime_associations,
+            &url_handlers,
+            &origin_view,
+            &menu_preview,
+            &desktop_id_entry,
+            &desktop_id_status,
+            &rename_button,
+            &add_to_autostart_button,
+            &feedback,
+            &override_banner,
         );
 
         Self::load_desktop_files(&file_list);
@@ -187,8 +368,8 @@ impl DesktopFileManagerWindow {
             icon_entry,
             path_entry,
             categories_popover,
-            categories_checkboxes,
-            categories_custom_entry,
+            category_tree,
+            categories_warning,
             categories_visible_entry,
             keywords_entry,
             terminal_switch,
@@ -196,11 +377,33 @@ impl DesktopFileManagerWindow {
             type_combo,
             url_entry,
             mime_type_entry,
+            generic_name_entry,
+            version_entry,
+            try_exec_entry,
+            startup_wm_class_entry,
+            implements_entry,
+            actions_entry,
+            only_show_in_entry,
+            not_show_in_entry,
+            startup_notify_switch,
+            dbus_activatable_switch,
+            prefers_non_default_gpu_switch,
+            single_main_window_switch,
+            no_display_switch,
+            additional_keys_table,
+            view_stack,
+            source_editor,
+            mime_associations,
+            url_handlers,
+            origin_view,
+            menu_preview,
             file_list,
             search_entry,
+            broken_filter_button,
         }
     }
 
+    #[allow(clippy::type_complexity)]
     fn create_form_fields() -> (
         gtk::Entry,
         gtk::Entry,
@@ -213,6 +416,20 @@ impl DesktopFileManagerWindow {
         gtk::ComboBoxText,
         gtk::Entry,
         gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Entry,
+        gtk::Switch,
+        gtk::Switch,
+        gtk::Switch,
+        gtk::Switch,
+        gtk::Switch,
+        KeyTable,
     ) {
         let name_entry = gtk::Entry::new();
         name_entry.set_placeholder_text(Some("Application Name"));
@@ -239,18 +456,58 @@ impl DesktopFileManagerWindow {
         hidden_switch.set_active(false);
 
         let type_combo = gtk::ComboBoxText::new();
-        type_combo.append_text("Application");
-        type_combo.append_text("Link");
-        type_combo.append_text("Directory");
+        for entry_type in ["Application", "Link", "Directory"] {
+            type_combo.append(Some(entry_type), entry_type);
+        }
         type_combo.set_active(Some(0));
 
         let url_entry = gtk::Entry::new();
         url_entry.set_placeholder_text(Some("URL (for Link type)"));
-        url_entry.set_sensitive(false);
 
         let mime_type_entry = gtk::Entry::new();
         mime_type_entry.set_placeholder_text(Some("MIME types (semicolon separated)"));
 
+        let generic_name_entry = gtk::Entry::new();
+        generic_name_entry.set_placeholder_text(Some("Generic name (e.g. Web Browser)"));
+
+        let version_entry = gtk::Entry::new();
+        version_entry.set_placeholder_text(Some("Specification version (e.g. 1.5)"));
+
+        let try_exec_entry = gtk::Entry::new();
+        try_exec_entry.set_placeholder_text(Some("Program used to check if installed"));
+
+        let startup_wm_class_entry = gtk::Entry::new();
+        startup_wm_class_entry.set_placeholder_text(Some("WM class or name hint"));
+
+        let implements_entry = gtk::Entry::new();
+        implements_entry.set_placeholder_text(Some("Interfaces (semicolon separated)"));
+
+        let actions_entry = gtk::Entry::new();
+        actions_entry.set_placeholder_text(Some("Action IDs (semicolon separated)"));
+
+        let only_show_in_entry = gtk::Entry::new();
+        only_show_in_entry.set_placeholder_text(Some("Desktops (e.g. GNOME;KDE;)"));
+
+        let not_show_in_entry = gtk::Entry::new();
+        not_show_in_entry.set_placeholder_text(Some("Desktops (e.g. XFCE;)"));
+
+        let startup_notify_switch = gtk::Switch::new();
+        startup_notify_switch.set_active(false);
+
+        let dbus_activatable_switch = gtk::Switch::new();
+        dbus_activatable_switch.set_active(false);
+
+        let prefers_non_default_gpu_switch = gtk::Switch::new();
+        prefers_non_default_gpu_switch.set_active(false);
+
+        let single_main_window_switch = gtk::Switch::new();
+        single_main_window_switch.set_active(false);
+
+        let no_display_switch = gtk::Switch::new();
+        no_display_switch.set_active(false);
+
+        let additional_keys_table = KeyTable::new();
+
         (
             name_entry,
             exec_entry,
@@ -263,6 +520,20 @@ impl DesktopFileManagerWindow {
             type_combo,
             url_entry,
             mime_type_entry,
+            generic_name_entry,
+            version_entry,
+            try_exec_entry,
+            startup_wm_class_entry,
+            implements_entry,
+            actions_entry,
+            only_show_in_entry,
+            not_show_in_entry,
+            startup_notify_switch,
+            dbus_activatable_switch,
+            prefers_non_default_gpu_switch,
+            single_main_window_switch,
+            no_display_switch,
+            additional_keys_table,
         )
     }
 
@@ -273,7 +544,24 @@ impl DesktopFileManagerWindow {
         list
     }
 
-    fn create_left_panel(file_list: &gtk::ListBox) -> (gtk::Box, gtk::Button, gtk::Entry) {
+    #[allow(clippy::type_complexity)]
+    fn create_left_panel(
+        file_list: &gtk::ListBox,
+    ) -> (
+        gtk::Box,
+        gtk::Button,
+        gtk::Entry,
+        gtk::Popover,
+        gtk::ListBox,
+        gtk::Button,
+        gtk::Button,
+        gtk::Button,
+        gtk::Button,
+        gtk::Button,
+        gtk::Button,
+        gtk::ToggleButton,
+        gtk::Button,
+    ) {
         let panel = gtk::Box::new(gtk::Orientation::Vertical, 12);
         panel.set_margin_start(12);
         panel.set_margin_end(12);
@@ -291,10 +579,71 @@ impl DesktopFileManagerWindow {
         new_button.set_tooltip_text(Some("Create New Desktop File"));
         header.pack_start(&new_button);
 
+        let import_button = gtk::Button::from_icon_name("document-open-symbolic");
+        import_button.set_tooltip_text(Some(
+            "Create Launcher from a Program, AppImage or Script (or drop one onto the window)",
+        ));
+        header.pack_start(&import_button);
+
+        let autostart_button = gtk::Button::from_icon_name("system-run-symbolic");
+        autostart_button.set_tooltip_text(Some("Manage Autostart Entries"));
+        header.pack_end(&autostart_button);
+
+        let menu_button = gtk::Button::from_icon_name("view-list-bullet-symbolic");
+        menu_button.set_tooltip_text(Some("Application Menu Structure"));
+        header.pack_end(&menu_button);
+
+        let health_button = gtk::Button::from_icon_name("emblem-ok-symbolic");
+        health_button.set_tooltip_text(Some("Health Report of All Installed Entries"));
+        header.pack_end(&health_button);
+
+        let overrides_button = gtk::Button::from_icon_name("edit-copy-symbolic");
+        overrides_button.set_tooltip_text(Some("User Overrides of System Entries"));
+        header.pack_end(&overrides_button);
+
+        let log_button = gtk::Button::from_icon_name("text-x-generic-symbolic");
+        log_button.set_tooltip_text(Some("Log"));
+        log_button.set_action_name(Some(feedback::SHOW_LOG_ACTION));
+        header.pack_end(&log_button);
+
+        let templates_list = gtk::ListBox::new();
+        templates_list.set_selection_mode(gtk::SelectionMode::None);
+        templates_list.set_activate_on_single_click(true);
+
+        let templates_scrolled = gtk::ScrolledWindow::new();
+        templates_scrolled.set_child(Some(&templates_list));
+        templates_scrolled.set_propagate_natural_height(true);
+        templates_scrolled.set_max_content_height(480);
+        templates_scrolled.set_min_content_width(280);
+
+        let save_template_button = gtk::Button::with_label("Save Current as Template");
+
+        let templates_box = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        templates_box.append(&templates_scrolled);
+        templates_box.append(&save_template_button);
+
+        let templates_popover = gtk::Popover::new();
+        templates_popover.set_child(Some(&templates_box));
+        templates_popover.set_parent(&new_button);
+
         let search_entry = gtk::Entry::new();
         search_entry.set_placeholder_text(Some("Search desktop files..."));
         search_entry.set_hexpand(true);
 
+        let broken_filter_button = gtk::ToggleButton::new();
+        broken_filter_button.set_icon_name("dialog-warning-symbolic");
+        broken_filter_button.set_tooltip_text(Some(
+            "Show Only Broken Entries (missing TryExec or Exec program)",
+        ));
+
+        let cleanup_button = gtk::Button::from_icon_name("edit-clear-all-symbolic");
+        cleanup_button.set_tooltip_text(Some("Clean Up Orphaned Launchers"));
+
+        let search_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        search_box.append(&search_entry);
+        search_box.append(&broken_filter_button);
+        search_box.append(&cleanup_button);
+
         let scrolled = gtk::ScrolledWindow::new();
         scrolled.set_child(Some(file_list));
         scrolled.set_min_content_width(300);
@@ -302,10 +651,24 @@ impl DesktopFileManagerWindow {
         scrolled.set_vexpand(true);
 
         panel.append(&header);
-        panel.append(&search_entry);
+        panel.append(&search_box);
         panel.append(&scrolled);
 
-        (panel, new_button, search_entry)
+        (
+            panel,
+            new_button,
+            search_entry,
+            templates_popover,
+            templates_list,
+            save_template_button,
+            import_button,
+            autostart_button,
+            menu_button,
+            health_button,
+            overrides_button,
+            broken_filter_button,
+            cleanup_button,
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -321,10 +684,40 @@ impl DesktopFileManagerWindow {
         type_combo: &gtk::ComboBoxText,
         url_entry: &gtk::Entry,
         mime_type_entry: &gtk::Entry,
-        _categories_popover: &gtk::Popover,
-        _categories_checkboxes: &[gtk::CheckButton],
-        _categories_custom_entry: &gtk::Entry,
-    ) -> (gtk::Box, gtk::Button, gtk::Button, gtk::Entry) {
+        generic_name_entry: &gtk::Entry,
+        version_entry: &gtk::Entry,
+        try_exec_entry: &gtk::Entry,
+        startup_wm_class_entry: &gtk::Entry,
+        implements_entry: &gtk::Entry,
+        actions_entry: &gtk::Entry,
+        only_show_in_entry: &gtk::Entry,
+        not_show_in_entry: &gtk::Entry,
+        startup_notify_switch: &gtk::Switch,
+        dbus_activatable_switch: &gtk::Switch,
+        prefers_non_default_gpu_switch: &gtk::Switch,
+        single_main_window_switch: &gtk::Switch,
+        no_display_switch: &gtk::Switch,
+        additional_keys_table: &KeyTable,
+        categories_popover: &gtk::Popover,
+        categories_warning: &gtk::Label,
+        source_editor: &SourceEditor,
+        mime_associations: &MimeAssociations,
+        url_handlers: &UrlHandlers,
+        origin_view: &OriginView,
+        menu_preview: &MenuPreviewView,
+    ) -> (
+        gtk::Box,
+        gtk::Button,
+        gtk::Button,
+        gtk::Button,
+        gtk::Entry,
+        adw::ViewStack,
+        gtk::Entry,
+        gtk::Image,
+        gtk::Button,
+        gtk::Button,
+        gtk::InfoBar,
+    ) {
         let panel = gtk::Box::new(gtk::Orientation::Vertical, 12);
         panel.set_margin_start(12);
         panel.set_margin_end(12);
@@ -334,9 +727,6 @@ impl DesktopFileManagerWindow {
         panel.set_vexpand(true);
 
         let header = adw::HeaderBar::new();
-        let title = gtk::Label::new(Some("Desktop File Editor"));
-        title.add_css_class("title-2");
-        header.set_title_widget(Some(&title));
 
         let delete_button = gtk::Button::from_icon_name("user-trash-symbolic");
         delete_button.set_tooltip_text(Some("Delete Desktop File"));
@@ -344,10 +734,32 @@ impl DesktopFileManagerWindow {
         delete_button.set_visible(false);
         header.pack_end(&delete_button);
 
+        let merge_button = gtk::Button::from_icon_name("view-dual-symbolic");
+        merge_button.set_tooltip_text(Some("Merge With Another Copy"));
+        // Only saved files can be merged into
+        delete_button
+            .bind_property("visible", &merge_button, "visible")
+            .sync_create()
+            .build();
+        header.pack_end(&merge_button);
+
+        let duplicate_button = gtk::Button::from_icon_name("edit-copy-symbolic");
+        duplicate_button.set_tooltip_text(Some("Duplicate as a New Entry"));
+        duplicate_button.set_action_name(Some("win.duplicate"));
+        delete_button
+            .bind_property("visible", &duplicate_button, "visible")
+            .sync_create()
+            .build();
+        header.pack_end(&duplicate_button);
+
         let save_button = gtk::Button::from_icon_name("document-save-symbolic");
         save_button.set_tooltip_text(Some("Save Desktop File"));
         header.pack_end(&save_button);
 
+        let add_to_autostart_button = gtk::Button::from_icon_name("system-run-symbolic");
+        add_to_autostart_button.set_tooltip_text(Some("Add to Autostart"));
+        header.pack_start(&add_to_autostart_button);
+
         let content = gtk::Box::new(gtk::Orientation::Vertical, 24);
 
         let basic_group = adw::PreferencesGroup::new();
@@ -358,6 +770,64 @@ impl DesktopFileManagerWindow {
         name_row.add_suffix(name_entry);
         basic_group.add(&name_row);
 
+        let desktop_id_row = adw::ActionRow::new();
+        desktop_id_row.set_title("Desktop File ID");
+
+        let desktop_id_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+
+        let desktop_id_status = gtk::Image::new();
+        desktop_id_status.set_valign(gtk::Align::Center);
+        desktop_id_box.append(&desktop_id_status);
+
+        let desktop_id_entry = gtk::Entry::new();
+        desktop_id_entry.set_placeholder_text(Some("org.example.App.desktop"));
+        desktop_id_entry.set_valign(gtk::Align::Center);
+        desktop_id_box.append(&desktop_id_entry);
+
+        let id_suggestions_list = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        let id_suggestions_popover = gtk::Popover::new();
+        id_suggestions_popover.set_child(Some(&id_suggestions_list));
+
+        let id_suggestions_button = gtk::MenuButton::new();
+        id_suggestions_button.set_icon_name("view-more-symbolic");
+        id_suggestions_button.set_tooltip_text(Some("Suggested IDs"));
+        id_suggestions_button.set_valign(gtk::Align::Center);
+        id_suggestions_button.set_popover(Some(&id_suggestions_popover));
+        desktop_id_box.append(&id_suggestions_button);
+
+        let rename_button = gtk::Button::with_label("Rename");
+        rename_button.set_tooltip_text(Some(
+            "Move the file to the new ID and update mimeapps.list, autostart and favorites",
+        ));
+        rename_button.set_valign(gtk::Align::Center);
+        rename_button.set_visible(false);
+        desktop_id_box.append(&rename_button);
+
+        desktop_id_row.add_suffix(&desktop_id_box);
+        basic_group.add(&desktop_id_row);
+
+        {
+            let name_entry = name_entry.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let popover = id_suggestions_popover.clone();
+            id_suggestions_popover.connect_show(move |_| {
+                while let Some(child) = id_suggestions_list.first_child() {
+                    id_suggestions_list.remove(&child);
+                }
+                for id in desktop_id::suggestions(&name_entry.text(), &desktop_id::taken_ids()) {
+                    let button = gtk::Button::with_label(&id);
+                    button.add_css_class("flat");
+                    let desktop_id_entry = desktop_id_entry.clone();
+                    let popover = popover.clone();
+                    button.connect_clicked(move |_| {
+                        desktop_id_entry.set_text(&id);
+                        popover.popdown();
+                    });
+                    id_suggestions_list.append(&button);
+                }
+            });
+        }
+
         let type_row = adw::ActionRow::new();
         type_row.set_title("Type");
         type_row.add_suffix(type_combo);
@@ -373,6 +843,16 @@ impl DesktopFileManagerWindow {
         comment_row.add_suffix(comment_entry);
         basic_group.add(&comment_row);
 
+        let icon_row = adw::ActionRow::new();
+        icon_row.set_title("Icon");
+        icon_row.add_suffix(icon_entry);
+        basic_group.add(&icon_row);
+
+        let generic_name_row = adw::ActionRow::new();
+        generic_name_row.set_title("Generic Name");
+        generic_name_row.add_suffix(generic_name_entry);
+        basic_group.add(&generic_name_row);
+
         let link_group = adw::PreferencesGroup::new();
         link_group.set_title("Link Settings");
 
@@ -384,11 +864,6 @@ impl DesktopFileManagerWindow {
         let app_group = adw::PreferencesGroup::new();
         app_group.set_title("Application Settings");
 
-        let icon_row = adw::ActionRow::new();
-        icon_row.set_title("Icon");
-        icon_row.add_suffix(icon_entry);
-        app_group.add(&icon_row);
-
         let path_row = adw::ActionRow::new();
         path_row.set_title("Working Directory");
         path_row.add_suffix(path_entry);
@@ -404,6 +879,99 @@ impl DesktopFileManagerWindow {
         mime_row.add_suffix(mime_type_entry);
         app_group.add(&mime_row);
 
+        let associations_group = adw::PreferencesGroup::new();
+        associations_group.set_title("File Associations");
+        associations_group.set_description(Some(
+            "Default applications for the MIME types above, from mimeapps.list",
+        ));
+        associations_group.add(&mime_associations.widget);
+
+        let url_handlers_group = adw::PreferencesGroup::new();
+        url_handlers_group.set_title("URL Handlers");
+        url_handlers_group.set_description(Some(
+            "Custom URL schemes opened by this entry (x-scheme-handler)",
+        ));
+        url_handlers_group.add(&url_handlers.widget);
+
+        let try_exec_row = adw::ActionRow::new();
+        try_exec_row.set_title("Try Exec");
+        try_exec_row.add_suffix(try_exec_entry);
+        app_group.add(&try_exec_row);
+
+        // Show where the programs of Exec and TryExec resolve to
+        {
+            let update_row = |row: &adw::ActionRow, program: Option<String>| {
+                let subtitle = match program.filter(|p| !p.is_empty()) {
+                    Some(program) => match exec::resolve(&program) {
+                        Ok(path) => format!("Runs {}", path.display()),
+                        Err(e) => format!("⚠ {e}"),
+                    },
+                    None => String::new(),
+                };
+                row.set_subtitle(&glib::markup_escape_text(&subtitle));
+            };
+            {
+                let exec_row = exec_row.clone();
+                exec_entry.connect_changed(move |entry| {
+                    update_row(&exec_row, exec::program(&entry.text()));
+                });
+            }
+            let try_exec_row = try_exec_row.clone();
+            try_exec_entry.connect_changed(move |entry| {
+                update_row(&try_exec_row, Some(entry.text().trim().to_string()));
+            });
+        }
+
+        let startup_wm_class_row = adw::ActionRow::new();
+        startup_wm_class_row.set_title("Startup WM Class");
+        startup_wm_class_row.add_suffix(startup_wm_class_entry);
+        app_group.add(&startup_wm_class_row);
+
+        let startup_notify_row = adw::ActionRow::new();
+        startup_notify_row.set_title("Startup Notify");
+        startup_notify_row.add_suffix(startup_notify_switch);
+        app_group.add(&startup_notify_row);
+
+        let single_main_window_row = adw::ActionRow::new();
+        single_main_window_row.set_title("Single Main Window");
+        single_main_window_row.add_suffix(single_main_window_switch);
+        app_group.add(&single_main_window_row);
+
+        let prefers_non_default_gpu_row = adw::ActionRow::new();
+        prefers_non_default_gpu_row.set_title("Prefers Non-Default GPU");
+        prefers_non_default_gpu_row.add_suffix(prefers_non_default_gpu_switch);
+        app_group.add(&prefers_non_default_gpu_row);
+
+        let dbus_activatable_row = adw::ActionRow::new();
+        dbus_activatable_row.set_title("D-Bus Activatable");
+        dbus_activatable_row.add_suffix(dbus_activatable_switch);
+        app_group.add(&dbus_activatable_row);
+
+        let implements_row = adw::ActionRow::new();
+        implements_row.set_title("Implements");
+        implements_row.add_suffix(implements_entry);
+        app_group.add(&implements_row);
+
+        let actions_row = adw::ActionRow::new();
+        actions_row.set_title("Actions");
+        actions_row.add_suffix(actions_entry);
+        app_group.add(&actions_row);
+
+        let env_group = adw::PreferencesGroup::new();
+        env_group.set_title("Environment");
+        env_group.set_description(Some(
+            "Variables set for the program through an env prefix in Exec",
+        ));
+        env_group.add(&EnvEditor::new(exec_entry).widget);
+
+        let launch_group = adw::PreferencesGroup::new();
+        launch_group.set_title("Launch Options");
+        launch_group.set_description(Some("Wrappers and flags added around the command in Exec"));
+        launch_group
+            .add(&LaunchOptionsEditor::new(exec_entry, prefers_non_default_gpu_switch).widget);
+
+        let flatpak_editor = FlatpakEditor::new(exec_entry);
+
         let cat_group = adw::PreferencesGroup::new();
         cat_group.set_title("Categories &amp; Keywords");
 
@@ -424,8 +992,9 @@ impl DesktopFileManagerWindow {
 
         categories_row.add_suffix(&categories_box);
         cat_group.add(&categories_row);
+        cat_group.add(categories_warning);
 
-        let popover = _categories_popover.clone();
+        let popover = categories_popover.clone();
         categories_button.connect_clicked(move |btn| {
             popover.set_parent(btn);
             popover.popup();
@@ -444,27 +1013,143 @@ impl DesktopFileManagerWindow {
         hidden_row.add_suffix(hidden_switch);
         vis_group.add(&hidden_row);
 
+        let no_display_row = adw::ActionRow::new();
+        no_display_row.set_title("No Display");
+        no_display_row.add_suffix(no_display_switch);
+        vis_group.add(&no_display_row);
+
+        let only_show_in_row = adw::ActionRow::new();
+        only_show_in_row.set_title("Only Show In");
+        only_show_in_row.add_suffix(only_show_in_entry);
+        vis_group.add(&only_show_in_row);
+
+        let not_show_in_row = adw::ActionRow::new();
+        not_show_in_row.set_title("Not Show In");
+        not_show_in_row.add_suffix(not_show_in_entry);
+        vis_group.add(&not_show_in_row);
+
+        let extra_group = adw::PreferencesGroup::new();
+        extra_group.set_title("Additional Keys");
+        extra_group.set_description(Some(
+            "Localized values, X- extensions and any other keys without a dedicated field",
+        ));
+
+        let version_row = adw::ActionRow::new();
+        version_row.set_title("Specification Version");
+        version_row.add_suffix(version_entry);
+        extra_group.add(&version_row);
+        extra_group.add(&additional_keys_table.widget);
+
+        // Only show the fields the specification defines for the selected type
+        {
+            let application_only: Vec<gtk::Widget> = vec![
+                exec_row.upcast(),
+                app_group.clone().upcast(),
+                env_group.clone().upcast(),
+                launch_group.clone().upcast(),
+                associations_group.clone().upcast(),
+                url_handlers_group.clone().upcast(),
+                categories_row.upcast(),
+                categories_warning.clone().upcast(),
+                add_to_autostart_button.clone().upcast(),
+            ];
+            let link_group = link_group.clone();
+            let categories_warning = categories_warning.clone();
+            let flatpak_editor = flatpak_editor.clone();
+            let update = move |combo: &gtk::ComboBoxText| {
+                let entry_type = combo.active_id().unwrap_or_default();
+                for widget in &application_only {
+                    widget.set_visible(entry_type == "Application");
+                }
+                // The warning label and the Flatpak group manage their own
+                // visibility for applications
+                if entry_type == "Application" {
+                    categories_warning.set_visible(!categories_warning.text().is_empty());
+                }
+                flatpak_editor
+                    .widget
+                    .set_visible(entry_type == "Application" && flatpak_editor.is_flatpak());
+                link_group.set_visible(entry_type == "Link");
+            };
+            update(type_combo);
+            type_combo.connect_changed(update);
+        }
+
+        content.append(&origin_view.widget);
         content.append(&basic_group);
         content.append(&link_group);
         content.append(&app_group);
+        content.append(&env_group);
+        content.append(&launch_group);
+        content.append(&flatpak_editor.widget);
+        content.append(&associations_group);
+        content.append(&url_handlers_group);
         content.append(&cat_group);
         content.append(&vis_group);
+        content.append(&extra_group);
 
         let scrolled = gtk::ScrolledWindow::new();
         scrolled.set_child(Some(&content));
         scrolled.set_hexpand(true);
         scrolled.set_vexpand(true);
 
+        let view_stack = adw::ViewStack::new();
+        view_stack
+            .add_titled(&scrolled, Some("form"), "Form")
+            .set_icon_name(Some("document-edit-symbolic"));
+        view_stack
+            .add_titled(&source_editor.widget, Some("source"), "Source")
+            .set_icon_name(Some("text-x-generic-symbolic"));
+        view_stack
+            .add_titled(&menu_preview.widget, Some("preview"), "Menu Preview")
+            .set_icon_name(Some("view-list-symbolic"));
+        view_stack.set_vexpand(true);
+
+        let switcher = adw::ViewSwitcher::new();
+        switcher.set_stack(Some(&view_stack));
+        switcher.set_policy(adw::ViewSwitcherPolicy::Wide);
+        header.set_title_widget(Some(&switcher));
+
+        // Shown for files the user cannot write, such as system entries, and
+        // for Flatpak exports, which are replaced on every update
+        let override_banner = gtk::InfoBar::new();
+        override_banner.set_message_type(gtk::MessageType::Info);
+        let banner_label = gtk::Label::new(Some(
+            "Changes to this file cannot be saved in place or would be lost with the next \
+             update. Save a user override to have your copy replace it in the menu.",
+        ));
+        banner_label.set_wrap(true);
+        banner_label.set_xalign(0.0);
+        banner_label.set_hexpand(true);
+        override_banner.add_child(&banner_label);
+        let override_button = gtk::Button::with_label("Save as User Override");
+        override_button.set_action_name(Some("win.save-as-override"));
+        override_banner.add_action_widget(&override_button, gtk::ResponseType::Apply);
+        override_banner.set_visible(false);
+
         panel.append(&header);
-        panel.append(&scrolled);
+        panel.append(&override_banner);
+        panel.append(&view_stack);
 
-        (panel, save_button, delete_button, categories_visible_entry)
+        (
+            panel,
+            save_button,
+            delete_button,
+            merge_button,
+            categories_visible_entry,
+            view_stack,
+            desktop_id_entry,
+            desktop_id_status,
+            rename_button,
+            add_to_autostart_button,
+            override_banner,
+        )
     }
 
     #[allow(clippy::too_many_arguments)]
     #[allow(clippy::needless_borrows_for_generic_args)]
     fn connect_signals(
-        _window: &adw::ApplicationWindow,
+        window: &adw::ApplicationWindow,
         current_file: &Rc<RefCell<Option<DesktopFile>>>,
         file_path: &Rc<RefCell<Option<String>>>,
         updating_ui: &Rc<Cell<bool>>,
@@ -480,16 +1165,61 @@ impl DesktopFileManagerWindow {
         type_combo: &gtk::ComboBoxText,
         url_entry: &gtk::Entry,
         mime_type_entry: &gtk::Entry,
+        generic_name_entry: &gtk::Entry,
+        version_entry: &gtk::Entry,
+        try_exec_entry: &gtk::Entry,
+        startup_wm_class_entry: &gtk::Entry,
+        implements_entry: &gtk::Entry,
+        actions_entry: &gtk::Entry,
+        only_show_in_entry: &gtk::Entry,
+        not_show_in_entry: &gtk::Entry,
+        startup_notify_switch: &gtk::Switch,
+        dbus_activatable_switch: &gtk::Switch,
+        prefers_non_default_gpu_switch: &gtk::Switch,
+        single_main_window_switch: &gtk::Switch,
+        no_display_switch: &gtk::Switch,
+        additional_keys_table: &KeyTable,
         new_button: &gtk::Button,
+        templates_popover: &gtk::Popover,
+        templates_list: &gtk::ListBox,
+        save_template_button: &gtk::Button,
+        import_button: &gtk::Button,
+        autostart_button: &gtk::Button,
+        menu_button: &gtk::Button,
+        health_button: &gtk::Button,
+        overrides_button: &gtk::Button,
         save_button: &gtk::Button,
         delete_button: &gtk::Button,
+        merge_button: &gtk::Button,
         _categories_popover: &gtk::Popover,
-        categories_checkboxes: &[gtk::CheckButton],
-        _categories_custom_entry: &gtk::Entry,
+        category_tree: &CategoryTree,
+        categories_warning: &gtk::Label,
         categories_visible_entry: &gtk::Entry,
         search_entry: &gtk::Entry,
+        broken_filter_button: &gtk::ToggleButton,
+        cleanup_button: &gtk::Button,
+        view_stack: &adw::ViewStack,
+        source_editor: &SourceEditor,
+        mime_associations: &MimeAssociations,
+        url_handlers: &UrlHandlers,
+        origin_view: &OriginView,
+        menu_preview: &MenuPreviewView,
+        desktop_id_entry: &gtk::Entry,
+        desktop_id_status: &gtk::Image,
+        rename_button: &gtk::Button,
+        add_to_autostart_button: &gtk::Button,
+        feedback: &Feedback,
+        override_banner: &gtk::InfoBar,
     ) {
-        {
+        // Whether the ID of an unsaved file was typed by the user rather than
+        // derived from its name
+        let id_edited = Rc::new(Cell::new(false));
+        // IDs in use, collected when an unsaved file is opened so its ID can
+        // follow the name without scanning the directories on every change
+        let taken_ids: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
+
+        // Opens a file that has not been saved yet (templates, imported programs, ...)
+        let open_unsaved: Rc<dyn Fn(DesktopFile)> = {
             let current_file = current_file.clone();
             let file_path = file_path.clone();
             let updating_ui = updating_ui.clone();
@@ -504,13 +1234,34 @@ impl DesktopFileManagerWindow {
             let type_combo = type_combo.clone();
             let url_entry = url_entry.clone();
             let mime_type_entry = mime_type_entry.clone();
+            let generic_name_entry = generic_name_entry.clone();
+            let version_entry = version_entry.clone();
+            let try_exec_entry = try_exec_entry.clone();
+            let startup_wm_class_entry = startup_wm_class_entry.clone();
+            let implements_entry = implements_entry.clone();
+            let actions_entry = actions_entry.clone();
+            let only_show_in_entry = only_show_in_entry.clone();
+            let not_show_in_entry = not_show_in_entry.clone();
+            let startup_notify_switch = startup_notify_switch.clone();
+            let dbus_activatable_switch = dbus_activatable_switch.clone();
+            let prefers_non_default_gpu_switch = prefers_non_default_gpu_switch.clone();
+            let single_main_window_switch = single_main_window_switch.clone();
+            let no_display_switch = no_display_switch.clone();
+            let additional_keys_table = additional_keys_table.clone();
             let categories_visible_entry = categories_visible_entry.clone();
-            let categories_checkboxes = categories_checkboxes.to_vec();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let id_edited = id_edited.clone();
+            let taken_ids = taken_ids.clone();
+            let delete_button = delete_button.clone();
+            let category_tree = category_tree.clone();
+            let source_editor = source_editor.clone();
+            let override_banner = override_banner.clone();
+            let origin_view = origin_view.clone();
 
-            new_button.connect_clicked(move |_| {
-                let new_file = DesktopFile::new("New Application".to_string(), "".to_string());
+            Rc::new(move |new_file: DesktopFile| {
                 *current_file.borrow_mut() = Some(new_file.clone());
                 *file_path.borrow_mut() = None;
+                *taken_ids.borrow_mut() = desktop_id::taken_ids();
                 updating_ui.set(true);
                 Self::update_ui_fields(
                     &new_file,
@@ -525,57 +1276,326 @@ impl DesktopFileManagerWindow {
                     &type_combo,
                     &url_entry,
                     &mime_type_entry,
+                    &generic_name_entry,
+                    &version_entry,
+                    &try_exec_entry,
+                    &startup_wm_class_entry,
+                    &implements_entry,
+                    &actions_entry,
+                    &only_show_in_entry,
+                    &not_show_in_entry,
+                    &startup_notify_switch,
+                    &dbus_activatable_switch,
+                    &prefers_non_default_gpu_switch,
+                    &single_main_window_switch,
+                    &no_display_switch,
+                    &additional_keys_table,
                     &categories_visible_entry,
-                    &categories_checkboxes,
+                    &category_tree,
                 );
                 updating_ui.set(false);
+                Self::refresh_source_editor(Some(&new_file), &source_editor, &updating_ui);
+                delete_button.set_visible(false);
+                override_banner.set_visible(false);
+                origin_view.show(None, None);
+                updating_ui.set(true);
+                id_edited.set(false);
+                desktop_id_entry.set_text(&desktop_id::normalize_for(
+                    &desktop_id::suggestions(&new_file.desktop_entry.name, &taken_ids.borrow())[0],
+                    &new_file.desktop_entry.entry_type,
+                ));
+                updating_ui.set(false);
+            })
+        };
+
+        {
+            let templates_popover = templates_popover.clone();
+            let open_unsaved = open_unsaved.clone();
+            templates_list.connect_row_activated(move |_, row| {
+                let template = unsafe {
+                    row.data::<DesktopFile>("template")
+                        .map(|ptr| (*ptr.as_ptr()).clone())
+                };
+                if let Some(new_file) = template {
+                    templates_popover.popdown();
+                    open_unsaved(new_file);
+                }
+            });
+        }
+
+        {
+            let window = window.clone();
+            let open_unsaved = open_unsaved.clone();
+            let feedback = feedback.clone();
+            import_button.connect_clicked(move |_| {
+                let dialog = gtk::FileChooserDialog::new(
+                    Some("Select a Program, AppImage or Script"),
+                    Some(&window),
+                    gtk::FileChooserAction::Open,
+                    &[
+                        ("_Cancel", gtk::ResponseType::Cancel),
+                        ("_Open", gtk::ResponseType::Accept),
+                    ],
+                );
+                dialog.set_modal(true);
+                let open_unsaved = open_unsaved.clone();
+                let feedback = feedback.clone();
+                dialog.connect_response(move |dialog, response| {
+                    if response == gtk::ResponseType::Accept {
+                        if let Some(path) = dialog.file().and_then(|f| f.path()) {
+                            Self::import_program(&path, &open_unsaved, &feedback);
+                        }
+                    }
+                    dialog.close();
+                });
+                dialog.show();
+            });
+        }
+
+        // Dropping a program onto the window creates a launcher for it
+        {
+            let open_unsaved = open_unsaved.clone();
+            let feedback = feedback.clone();
+            let drop_target =
+                gtk::DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+            drop_target.connect_drop(move |_, value, _, _| {
+                let Ok(files) = value.get::<gdk::FileList>() else {
+                    return false;
+                };
+                match files.files().first().and_then(|f| f.path()) {
+                    Some(path) => {
+                        Self::import_program(&path, &open_unsaved, &feedback);
+                        true
+                    }
+                    None => false,
+                }
+            });
+            window.add_controller(drop_target);
+        }
+
+        {
+            let templates_popover = templates_popover.clone();
+            let templates_list = templates_list.clone();
+            let feedback = feedback.clone();
+            new_button.connect_clicked(move |_| {
+                Self::load_templates(&templates_list, &feedback);
+                templates_popover.popup();
             });
         }
 
         {
             let current_file = current_file.clone();
+            let templates_popover = templates_popover.clone();
+            let feedback = feedback.clone();
+            save_template_button.connect_clicked(move |_| {
+                let file_opt = current_file.borrow().clone();
+                if let Some(file) = file_opt {
+                    match templates::save_user_template(&file) {
+                        Ok(path) => feedback
+                            .success("templates", format!("Saved template to {}", path.display())),
+                        Err(e) => feedback.error("templates", "Could not save the template", &e),
+                    }
+                }
+                templates_popover.popdown();
+            });
+        }
+
+        {
+            let window = window.clone();
+            autostart_button.connect_clicked(move |_| autostart_view::present(&window));
+        }
+
+        {
+            let window = window.clone();
+            menu_button.connect_clicked(move |_| menu_tree_view::present(&window));
+        }
+
+        {
+            let window = window.clone();
+            health_button.connect_clicked(move |_| health_view::present(&window));
+        }
+
+        {
+            let window = window.clone();
             let file_path = file_path.clone();
             let file_list = file_list.clone();
+            merge_button.connect_clicked(move |_| {
+                let Some(path) = file_path.borrow().clone() else {
+                    return;
+                };
+                let file_list = file_list.clone();
+                merge_view::present_for_file(
+                    &window,
+                    PathBuf::from(&path),
+                    Rc::new(move || {
+                        // Reselecting the file loads the merged content into the form
+                        Self::load_desktop_files(&file_list);
+                        Self::select_file(&file_list, &path);
+                    }),
+                );
+            });
+        }
+
+        {
+            let window = window.clone();
+            let file_list = file_list.clone();
+            let search_entry = search_entry.clone();
+            let broken_filter_button = broken_filter_button.clone();
+            overrides_button.connect_clicked(move |_| {
+                let file_list = file_list.clone();
+                let search_entry = search_entry.clone();
+                let broken_filter_button = broken_filter_button.clone();
+                overrides_view::present(
+                    &window,
+                    Rc::new(move || {
+                        Self::load_desktop_files(&file_list);
+                        if broken_filter_button.is_active() || !search_entry.text().is_empty() {
+                            Self::filter_file_list(
+                                &file_list,
+                                &search_entry.text().to_lowercase(),
+                                broken_filter_button.is_active(),
+                            );
+                        }
+                    }),
+                );
+            });
+        }
 
-            save_button.connect_clicked(move |_| {
+        {
+            let current_file = current_file.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let feedback = feedback.clone();
+            add_to_autostart_button.connect_clicked(move |_| {
                 let file_opt = current_file.borrow().clone();
+                if let Some(file) = file_opt {
+                    let id = desktop_id::normalize(&desktop_id_entry.text());
+                    if let Err(e) = desktop_id::validate(&id) {
+                        feedback.warning("autostart", format!("Invalid desktop file ID {id}: {e}"));
+                        return;
+                    }
+                    match autostart::add(&file, &id) {
+                        Ok(path) => feedback.success(
+                            "autostart",
+                            format!("Added to autostart as {}", path.display()),
+                        ),
+                        Err(e) => feedback.error("autostart", "Could not add to autostart", &e),
+                    }
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let file_path = file_path.clone();
+            let file_list = file_list.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let desktop_id_status = desktop_id_status.clone();
+            let rename_button = rename_button.clone();
+            let mime_associations = mime_associations.clone();
+            let url_handlers = url_handlers.clone();
+            let feedback = feedback.clone();
+
+            save_button.connect_clicked(move |button| {
+                // Flatpak rewrites its exports on every update, so changes only
+                // last in a user override
+                if file_path
+                    .borrow()
+                    .as_deref()
+                    .is_some_and(|path| flatpak::is_export(Path::new(path)))
+                {
+                    let _ = WidgetExt::activate_action(button, "win.save-as-override", None);
+                    return;
+                }
+                let file_opt = current_file.borrow_mut().as_mut().map(|file| {
+                    file.desktop_entry.clear_inapplicable_keys();
+                    file.clone()
+                });
                 let path_opt = file_path.borrow().clone();
                 // No RefCell borrow is held after this point
                 if let Some(file) = file_opt {
-                    println!(
-                        "Saving file with categories: {:?}",
-                        file.desktop_entry.categories
-                    );
                     if let Some(path) = path_opt {
                         // Save to existing file
                         if let Err(e) = file.save(&path) {
-                            eprintln!("Error saving file: {e}");
+                            if feedback::is_not_writable(&e)
+                                && desktop_id::from_path(Path::new(&path)).is_some()
+                            {
+                                feedback.problem_with_action(
+                                    "save",
+                                    format!(
+                                        "Could not save {path}: {}. Save a user override instead?",
+                                        feedback::explain(&e)
+                                    ),
+                                    "Save as User Override",
+                                    "win.save-as-override",
+                                );
+                            } else {
+                                feedback.error("save", &format!("Could not save {path}"), &e);
+                            }
                         } else {
-                            println!("File saved successfully to: {path}");
+                            feedback.success("save", format!("Saved {path}"));
                             // Refresh the file list to show updated categories
                             Self::load_desktop_files(&file_list);
+                            mime_associations.reload();
+                            url_handlers.refresh();
                         }
                     } else {
-                        // Save as new file
-                        if let Ok(home) = env::var("HOME") {
-                            let user_apps = format!("{home}/.local/share/applications");
-                            let filename = format!(
-                                "{}.desktop",
-                                file.desktop_entry.name.replace(" ", "-").to_lowercase()
-                            );
-                            let full_path = format!("{user_apps}/{filename}");
+                        // Save as new file under the chosen desktop file ID
+                        let filename = desktop_id::normalize_for(
+                            &desktop_id_entry.text(),
+                            &file.desktop_entry.entry_type,
+                        );
+                        if let Some(user_apps) = desktop_id::user_dir(&filename) {
+                            if let Err(e) = desktop_id::validate(&filename) {
+                                feedback.warning(
+                                    "save",
+                                    format!("Invalid desktop file ID {filename}: {e}"),
+                                );
+                                return;
+                            }
+                            let full_path = user_apps.join(&filename).to_string_lossy().to_string();
+                            if Path::new(&full_path).exists() {
+                                feedback.warning(
+                                    "save",
+                                    format!(
+                                        "Not saved: {full_path} already exists, choose another desktop file ID"
+                                    ),
+                                );
+                                return;
+                            }
                             // Create directory if it doesn't exist
                             if let Err(e) = std::fs::create_dir_all(&user_apps) {
-                                eprintln!("Error creating directory: {e}");
+                                feedback.error(
+                                    "save",
+                                    &format!("Could not create {}", user_apps.display()),
+                                    &e.into(),
+                                );
                                 return;
                             }
                             if let Err(e) = file.save(&full_path) {
-                                eprintln!("Error saving file: {e}");
+                                feedback.error("save", &format!("Could not save {full_path}"), &e);
                             } else {
-                                println!("File saved successfully to: {full_path}");
-                                *file_path.borrow_mut() = Some(full_path);
+                                feedback.success("save", format!("Saved {full_path}"));
+                                // Remember what a new override was made from
+                                if let Some(system_path) = overrides::system_path(&filename) {
+                                    if let Err(e) = overrides::record_base(&filename, &system_path) {
+                                        logging::warn(
+                                            "overrides",
+                                            format!("Could not record the base of {filename}: {e}"),
+                                        );
+                                    }
+                                }
+                                *file_path.borrow_mut() = Some(full_path.clone());
+                                desktop_id_entry.set_text(&filename);
+                                Self::update_desktop_id_status(
+                                    &desktop_id_entry,
+                                    &desktop_id_status,
+                                    &rename_button,
+                                    Some(&full_path),
+                                );
                                 // Refresh the file list to show the new file
                                 Self::load_desktop_files(&file_list);
+                                mime_associations.reload();
+                                url_handlers.refresh();
                             }
                         }
                     }
@@ -586,6 +1606,7 @@ impl DesktopFileManagerWindow {
         {
             let current_file = current_file.clone();
             let file_path = file_path.clone();
+            let updating_ui = updating_ui.clone();
             let name_entry = name_entry.clone();
             let exec_entry = exec_entry.clone();
             let comment_entry = comment_entry.clone();
@@ -597,10 +1618,29 @@ impl DesktopFileManagerWindow {
             let type_combo = type_combo.clone();
             let url_entry = url_entry.clone();
             let mime_type_entry = mime_type_entry.clone();
+            let generic_name_entry = generic_name_entry.clone();
+            let version_entry = version_entry.clone();
+            let try_exec_entry = try_exec_entry.clone();
+            let startup_wm_class_entry = startup_wm_class_entry.clone();
+            let implements_entry = implements_entry.clone();
+            let actions_entry = actions_entry.clone();
+            let only_show_in_entry = only_show_in_entry.clone();
+            let not_show_in_entry = not_show_in_entry.clone();
+            let startup_notify_switch = startup_notify_switch.clone();
+            let dbus_activatable_switch = dbus_activatable_switch.clone();
+            let prefers_non_default_gpu_switch = prefers_non_default_gpu_switch.clone();
+            let single_main_window_switch = single_main_window_switch.clone();
+            let no_display_switch = no_display_switch.clone();
+            let additional_keys_table = additional_keys_table.clone();
             let categories_visible_entry = categories_visible_entry.clone();
-            let categories_checkboxes = categories_checkboxes.to_vec();
+            let category_tree = category_tree.clone();
             let file_list = file_list.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
             let delete_button = delete_button.clone();
+            let source_editor = source_editor.clone();
+            let feedback = feedback.clone();
+            let override_banner = override_banner.clone();
+            let origin_view = origin_view.clone();
 
             file_list.connect_row_selected(move |_list, row| {
                 if let Some(row) = row {
@@ -649,59 +1689,212 @@ impl DesktopFileManagerWindow {
                         };
 
                         if let Some(path) = file_path_str {
-                            if let Some(file_name) = Path::new(&path).file_name() {
-                                if let Some(name) = file_name.to_str() {
-                                    Self::load_desktop_file(
-                                        &current_file,
-                                        &file_path,
-                                        name,
-                                        &name_entry,
-                                        &exec_entry,
-                                        &comment_entry,
-                                        &icon_entry,
-                                        &path_entry,
-                                        &keywords_entry,
-                                        &terminal_switch,
-                                        &hidden_switch,
-                                        &type_combo,
-                                        &url_entry,
-                                        &mime_type_entry,
-                                        &categories_visible_entry,
-                                        &categories_checkboxes,
-                                    );
-                                    delete_button.set_visible(true);
-                                }
-                            }
+                            updating_ui.set(true);
+                            Self::load_desktop_file(
+                                &current_file,
+                                &file_path,
+                                &path,
+                                &name_entry,
+                                &exec_entry,
+                                &comment_entry,
+                                &icon_entry,
+                                &path_entry,
+                                &keywords_entry,
+                                &terminal_switch,
+                                &hidden_switch,
+                                &type_combo,
+                                &url_entry,
+                                &mime_type_entry,
+                                &generic_name_entry,
+                                &version_entry,
+                                &try_exec_entry,
+                                &startup_wm_class_entry,
+                                &implements_entry,
+                                &actions_entry,
+                                &only_show_in_entry,
+                                &not_show_in_entry,
+                                &startup_notify_switch,
+                                &dbus_activatable_switch,
+                                &prefers_non_default_gpu_switch,
+                                &single_main_window_switch,
+                                &no_display_switch,
+                                &additional_keys_table,
+                                &categories_visible_entry,
+                                &category_tree,
+                                &feedback,
+                            );
+                            let id = desktop_id::from_path(Path::new(&path));
+                            override_banner.set_visible(
+                                id.is_some()
+                                    && (!Self::is_writable(&path)
+                                        || flatpak::is_export(Path::new(&path))),
+                            );
+                            desktop_id_entry.set_text(&id.unwrap_or_default());
+                            updating_ui.set(false);
+                            let file = current_file.borrow().clone();
+                            origin_view.show(Some(&path), file.as_ref());
+                            Self::refresh_source_editor(
+                                file.as_ref(),
+                                &source_editor,
+                                &updating_ui,
+                            );
+                            delete_button.set_visible(true);
                         }
                     }
                 }
             });
         }
 
-        // Delete button
+        // Saves a file the user cannot write, usually a system entry, to their
+        // applications folder, where the copy replaces it under the same ID
         {
-            let window = _window.clone();
-            delete_button.connect_clicked(
-                glib::clone!(@weak delete_button, @weak current_file, @weak file_path, @weak file_list, @weak window => move |_| {
-                    let path_opt = file_path.borrow().clone();
-                    if let Some(path) = path_opt {
-                        let dialog = gtk::MessageDialog::builder()
-                            .transient_for(&window)
-                            .modal(true)
-                            .message_type(gtk::MessageType::Warning)
-                            .buttons(gtk::ButtonsType::OkCancel)
-                            .text("Delete Desktop File?")
-                            .secondary_text(&format!("Are you sure you want to delete this file?\n{path}"))
-                            .build();
-                        dialog.connect_response(glib::clone!(@weak delete_button, @weak current_file, @weak file_path, @weak file_list => move |dialog, response| {
-                            if response == gtk::ResponseType::Ok {
-                                if let Err(e) = std::fs::remove_file(&path) {
-                                    eprintln!("Error deleting file: {e}");
-                                } else {
-                                    *current_file.borrow_mut() = None;
-                                    *file_path.borrow_mut() = None;
-                                    delete_button.set_visible(false);
-                                    DesktopFileManagerWindow::load_desktop_files(&file_list);
+            let save_as_override = gio::SimpleAction::new("save-as-override", None);
+            let current_file = current_file.clone();
+            let file_path = file_path.clone();
+            let file_list = file_list.clone();
+            let feedback = feedback.clone();
+            let override_banner = override_banner.clone();
+            save_as_override.connect_activate(move |_, _| {
+                let file_opt = current_file.borrow_mut().as_mut().map(|file| {
+                    file.desktop_entry.clear_inapplicable_keys();
+                    file.clone()
+                });
+                let path_opt = file_path.borrow().clone();
+                let (Some(file), Some(path)) = (file_opt, path_opt) else {
+                    return;
+                };
+                let Some(id) = desktop_id::from_path(Path::new(&path)) else {
+                    return;
+                };
+                let Some(user_dir) = desktop_id::user_dir(&id) else {
+                    return;
+                };
+                let target = user_dir.join(&id);
+                if target.exists() {
+                    feedback.warning(
+                        "save",
+                        format!(
+                            "{} already exists; edit that file instead",
+                            target.display()
+                        ),
+                    );
+                    return;
+                }
+
+                let result = std::fs::create_dir_all(&user_dir)
+                    .map_err(DesktopFileError::from)
+                    .and_then(|()| file.save(&target));
+                if let Err(e) = result {
+                    feedback.error("save", &format!("Could not save {}", target.display()), &e);
+                    return;
+                }
+                if let Err(e) = overrides::record_base(&id, Path::new(&path)) {
+                    logging::warn(
+                        "overrides",
+                        format!("Could not record the base of {id}: {e}"),
+                    );
+                }
+                feedback.success("save", format!("Saved {id} as a user override"));
+                override_banner.set_visible(false);
+                // Selecting the override loads it into the form
+                let target = target.to_string_lossy().to_string();
+                *file_path.borrow_mut() = Some(target.clone());
+                Self::load_desktop_files(&file_list);
+                Self::select_file(&file_list, &target);
+            });
+            window.add_action(&save_as_override);
+        }
+
+        // Delete button: user files go to the trash, system entries, which
+        // cannot be deleted, are hidden with a user override
+        {
+            let pending_undo: Rc<RefCell<Option<Undo>>> = Rc::new(RefCell::new(None));
+
+            let undo_delete = gio::SimpleAction::new("undo-delete", None);
+            {
+                let pending_undo = pending_undo.clone();
+                let file_list = file_list.clone();
+                let feedback = feedback.clone();
+                undo_delete.connect_activate(move |_, _| {
+                    let Some(undo) = pending_undo.borrow_mut().take() else {
+                        return;
+                    };
+                    let result = match &undo {
+                        Undo::Restore(item) => trash::restore(item).map(|()| item.original.clone()),
+                        Undo::Unhide(entry) => {
+                            overrides::revert(entry).map(|()| entry.system_path.clone())
+                        }
+                    };
+                    match result {
+                        Ok(path) => {
+                            let path = path.to_string_lossy().to_string();
+                            feedback.success("delete", format!("Restored {path}"));
+                            Self::load_desktop_files(&file_list);
+                            Self::select_file(&file_list, &path);
+                        }
+                        Err(e) => feedback.error("delete", "Could not undo", &e),
+                    }
+                });
+            }
+            window.add_action(&undo_delete);
+
+            let window = window.clone();
+            let source_buffer = source_editor.buffer.clone();
+            let override_banner = override_banner.clone();
+            delete_button.connect_clicked(
+                glib::clone!(@weak delete_button, @weak current_file, @weak file_path, @weak file_list, @weak window, @weak source_buffer, @weak override_banner, @strong origin_view, @strong feedback, @strong pending_undo => move |_| {
+                    let path_opt = file_path.borrow().clone();
+                    if let Some(path) = path_opt {
+                        let system = overrides::is_system_file(Path::new(&path));
+                        let (text, secondary) = if system {
+                            (
+                                "Hide System Entry?",
+                                format!("{path} belongs to the system and cannot be deleted. A user override with Hidden=true will hide it from menus instead."),
+                            )
+                        } else {
+                            (
+                                "Move Desktop File to Trash?",
+                                format!("{path} can be restored from the trash."),
+                            )
+                        };
+                        let dialog = gtk::MessageDialog::builder()
+                            .transient_for(&window)
+                            .modal(true)
+                            .message_type(gtk::MessageType::Warning)
+                            .buttons(gtk::ButtonsType::OkCancel)
+                            .text(text)
+                            .secondary_text(&secondary)
+                            .build();
+                        dialog.connect_response(glib::clone!(@weak delete_button, @weak current_file, @weak file_path, @weak file_list, @weak source_buffer, @weak override_banner, @strong origin_view, @strong feedback, @strong pending_undo => move |dialog, response| {
+                            if response == gtk::ResponseType::Ok {
+                                let result = if system {
+                                    overrides::hide(Path::new(&path))
+                                        .map(|entry| Some(Undo::Unhide(entry)))
+                                } else {
+                                    trash::trash(Path::new(&path)).map(|item| item.map(Undo::Restore))
+                                };
+                                let (verb, message) = if system {
+                                    ("hide", format!("Hid {path}"))
+                                } else {
+                                    ("delete", format!("Moved {path} to the trash"))
+                                };
+                                match result {
+                                    Err(e) => feedback.error("delete", &format!("Could not {verb} {path}"), &e),
+                                    Ok(undo) => {
+                                        if undo.is_some() {
+                                            feedback.success_with_action("delete", message, "Undo", "win.undo-delete");
+                                        } else {
+                                            feedback.success("delete", message);
+                                        }
+                                        *pending_undo.borrow_mut() = undo;
+                                        *current_file.borrow_mut() = None;
+                                        *file_path.borrow_mut() = None;
+                                        delete_button.set_visible(false);
+                                        override_banner.set_visible(false);
+                                        origin_view.show(None, None);
+                                        source_buffer.set_text("");
+                                        DesktopFileManagerWindow::load_desktop_files(&file_list);
+                                    }
                                 }
                             }
                             dialog.close();
@@ -714,25 +1907,26 @@ impl DesktopFileManagerWindow {
 
         // Type combo change
         {
-            let exec_entry = exec_entry.clone();
-            let url_entry = url_entry.clone();
+            let current_file = current_file.clone();
+            let file_path = file_path.clone();
+            let updating_ui = updating_ui.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
             type_combo.connect_changed(move |combo| {
-                if let Some(active_id) = combo.active_id() {
-                    match active_id.as_str() {
-                        "Application" => {
-                            exec_entry.set_sensitive(true);
-                            url_entry.set_sensitive(false);
-                        }
-                        "Link" => {
-                            exec_entry.set_sensitive(false);
-                            url_entry.set_sensitive(true);
-                        }
-                        "Directory" => {
-                            exec_entry.set_sensitive(false);
-                            url_entry.set_sensitive(false);
-                        }
-                        _ => {}
-                    }
+                if updating_ui.get() {
+                    return;
+                }
+                let Some(entry_type) = combo.active_id() else {
+                    return;
+                };
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.entry_type = entry_type.to_string();
+                }
+                // Unsaved directories go to desktop-directories as .directory files
+                if file_path.borrow().is_none() {
+                    desktop_id_entry.set_text(&desktop_id::normalize_for(
+                        &desktop_id_entry.text(),
+                        &entry_type,
+                    ));
                 }
             });
         }
@@ -877,88 +2071,800 @@ impl DesktopFileManagerWindow {
             });
         }
 
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            generic_name_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.generic_name = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            version_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.version = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            try_exec_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.try_exec = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            startup_wm_class_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.startup_wm_class = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            implements_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.implements = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            actions_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.actions = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            only_show_in_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.only_show_in = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            not_show_in_entry.connect_changed(move |entry| {
+                if updating_ui.get() {
+                    return;
+                }
+                let text = entry.text();
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.not_show_in = if text.is_empty() {
+                        None
+                    } else {
+                        Some(text.to_string())
+                    };
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            startup_notify_switch.connect_state_set(move |_, state| {
+                if updating_ui.get() {
+                    return Propagation::Proceed;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.startup_notify = Some(state);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            dbus_activatable_switch.connect_state_set(move |_, state| {
+                if updating_ui.get() {
+                    return Propagation::Proceed;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.dbus_activatable = Some(state);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            prefers_non_default_gpu_switch.connect_state_set(move |_, state| {
+                if updating_ui.get() {
+                    return Propagation::Proceed;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.prefers_non_default_gpu = Some(state);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            single_main_window_switch.connect_state_set(move |_, state| {
+                if updating_ui.get() {
+                    return Propagation::Proceed;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.single_main_window = Some(state);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            no_display_switch.connect_state_set(move |_, state| {
+                if updating_ui.get() {
+                    return Propagation::Proceed;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.no_display = Some(state);
+                }
+                Propagation::Proceed
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            additional_keys_table.connect_changed(move |keys| {
+                if updating_ui.get() {
+                    return;
+                }
+                if let Some(ref mut file) = *current_file.borrow_mut() {
+                    file.desktop_entry.extra_keys = keys;
+                }
+            });
+        }
+
         {
             let current_file = current_file.clone();
             let updating_ui = updating_ui.clone();
             let categories_visible_entry = categories_visible_entry.clone();
+            let category_tree = category_tree.clone();
+            let categories_warning = categories_warning.clone();
+            let only_show_in_entry = only_show_in_entry.clone();
 
-            println!(
-                "Connecting categories visible entry signal handler for entry ID: {:?}",
-                categories_visible_entry.as_ptr()
-            );
             categories_visible_entry.connect_changed(move |entry| {
-                println!(
-                    "Categories visible entry changed signal triggered for entry ID: {:?}",
-                    entry.as_ptr()
-                );
-                println!("Categories visible entry changed signal triggered!");
+                category_tree.set_categories(&entry.text());
+                Self::update_category_warnings(&categories_warning, entry, &only_show_in_entry);
                 if updating_ui.get() {
                     return;
                 }
                 let text = entry.text();
-                println!("Categories changed to: '{text}'");
                 if let Some(ref mut file) = *current_file.borrow_mut() {
+                    logging::debug("categories", format!("Categories set to '{text}'"));
                     if text.is_empty() {
                         file.desktop_entry.categories = None;
-                        println!("Categories cleared");
                     } else {
                         file.desktop_entry.categories = Some(text.to_string());
-                        println!("Categories set to: '{text}'");
                     }
-                } else {
-                    println!("No current file to update");
                 }
             });
         }
 
         {
-            let file_list = file_list.clone();
-            let search_entry = search_entry.clone();
+            let file_path = file_path.clone();
+            let updating_ui = updating_ui.clone();
+            let id_edited = id_edited.clone();
+            let desktop_id_status = desktop_id_status.clone();
+            let rename_button = rename_button.clone();
+            desktop_id_entry.connect_changed(move |entry| {
+                let path = file_path.borrow().clone();
+                Self::update_desktop_id_status(
+                    entry,
+                    &desktop_id_status,
+                    &rename_button,
+                    path.as_deref(),
+                );
+                if !updating_ui.get() {
+                    id_edited.set(true);
+                }
+            });
+        }
 
-            search_entry.connect_changed(move |entry| {
-                let search_text = entry.text().to_lowercase();
-                Self::filter_file_list(&file_list, &search_text);
+        {
+            let categories_visible_entry = categories_visible_entry.clone();
+            let categories_warning = categories_warning.clone();
+            only_show_in_entry.connect_changed(move |entry| {
+                Self::update_category_warnings(
+                    &categories_warning,
+                    &categories_visible_entry,
+                    entry,
+                );
+            });
+        }
+
+        // Show the defaults for the listed MIME types and URL schemes of the
+        // entry being edited
+        for entry in [mime_type_entry, exec_entry, desktop_id_entry] {
+            let mime_type_entry = mime_type_entry.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let mime_associations = mime_associations.clone();
+            let url_handlers = url_handlers.clone();
+            entry.connect_changed(move |_| {
+                let id = desktop_id::normalize(&desktop_id_entry.text());
+                mime_associations.update(&mime_type_entry.text(), &id);
+                url_handlers.update(&id);
             });
         }
 
+        // Keep the ID of an unsaved file in step with its name until edited
         {
             let current_file = current_file.clone();
+            let file_path = file_path.clone();
             let updating_ui = updating_ui.clone();
+            let id_edited = id_edited.clone();
+            let taken_ids = taken_ids.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            name_entry.connect_changed(move |entry| {
+                if updating_ui.get() || id_edited.get() || file_path.borrow().is_some() {
+                    return;
+                }
+                let entry_type = current_file
+                    .borrow()
+                    .as_ref()
+                    .map(|file| file.desktop_entry.entry_type.clone())
+                    .unwrap_or_default();
+                updating_ui.set(true);
+                desktop_id_entry.set_text(&desktop_id::normalize_for(
+                    &desktop_id::suggestions(&entry.text(), &taken_ids.borrow())[0],
+                    &entry_type,
+                ));
+                updating_ui.set(false);
+            });
+        }
+
+        {
+            let file_path = file_path.clone();
+            let file_list = file_list.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let desktop_id_status = desktop_id_status.clone();
+            let feedback = feedback.clone();
+            rename_button.connect_clicked(move |button| {
+                let Some(old_path) = file_path.borrow().clone() else {
+                    return;
+                };
+                let new_id = desktop_id::normalize(&desktop_id_entry.text());
+                match desktop_id::rename(Path::new(&old_path), &new_id) {
+                    Ok(new_path) => {
+                        let new_path = new_path.to_string_lossy().to_string();
+                        feedback.success("rename", format!("Renamed to {new_id}"));
+                        *file_path.borrow_mut() = Some(new_path.clone());
+                        desktop_id_entry.set_text(&new_id);
+                        Self::update_desktop_id_status(
+                            &desktop_id_entry,
+                            &desktop_id_status,
+                            button,
+                            Some(&new_path),
+                        );
+                        Self::load_desktop_files(&file_list);
+                    }
+                    Err(e) => {
+                        feedback.error("rename", &format!("Could not rename to {new_id}"), &e)
+                    }
+                }
+            });
+        }
+
+        // Source editor -> form
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            let name_entry = name_entry.clone();
+            let exec_entry = exec_entry.clone();
+            let comment_entry = comment_entry.clone();
+            let icon_entry = icon_entry.clone();
+            let path_entry = path_entry.clone();
+            let keywords_entry = keywords_entry.clone();
+            let terminal_switch = terminal_switch.clone();
+            let hidden_switch = hidden_switch.clone();
+            let type_combo = type_combo.clone();
+            let url_entry = url_entry.clone();
+            let mime_type_entry = mime_type_entry.clone();
+            let generic_name_entry = generic_name_entry.clone();
+            let version_entry = version_entry.clone();
+            let try_exec_entry = try_exec_entry.clone();
+            let startup_wm_class_entry = startup_wm_class_entry.clone();
+            let implements_entry = implements_entry.clone();
+            let actions_entry = actions_entry.clone();
+            let only_show_in_entry = only_show_in_entry.clone();
+            let not_show_in_entry = not_show_in_entry.clone();
+            let startup_notify_switch = startup_notify_switch.clone();
+            let dbus_activatable_switch = dbus_activatable_switch.clone();
+            let prefers_non_default_gpu_switch = prefers_non_default_gpu_switch.clone();
+            let single_main_window_switch = single_main_window_switch.clone();
+            let no_display_switch = no_display_switch.clone();
+            let additional_keys_table = additional_keys_table.clone();
             let categories_visible_entry = categories_visible_entry.clone();
-            let categories_checkboxes = categories_checkboxes.to_vec();
-
-            #[allow(clippy::unused_enumerate_index)]
-            for (_i, checkbox) in categories_checkboxes.iter().enumerate() {
-                let checkbox = checkbox.clone();
-                let current_file = current_file.clone();
-                let updating_ui = updating_ui.clone();
-                let categories_visible_entry = categories_visible_entry.clone();
-                let categories_checkboxes = categories_checkboxes.clone();
-
-                checkbox.connect_toggled(move |_| {
-                    if updating_ui.get() {
-                        return;
+            let category_tree = category_tree.clone();
+            let editor = source_editor.clone();
+
+            source_editor.buffer.connect_changed(move |_| {
+                editor.highlight();
+                if updating_ui.get() {
+                    return;
+                }
+                // Parse errors are reported by the editor's diagnostics; the form
+                // keeps its last valid state until the source parses again.
+                if let Ok(mut file) = DesktopFile::from_string(&editor.text()) {
+                    if let Some(ref current) = *current_file.borrow() {
+                        file.icon_data = current.icon_data.clone();
                     }
+                    updating_ui.set(true);
+                    Self::update_ui_fields(
+                        &file,
+                        &name_entry,
+                        &exec_entry,
+                        &comment_entry,
+                        &icon_entry,
+                        &path_entry,
+                        &keywords_entry,
+                        &terminal_switch,
+                        &hidden_switch,
+                        &type_combo,
+                        &url_entry,
+                        &mime_type_entry,
+                        &generic_name_entry,
+                        &version_entry,
+                        &try_exec_entry,
+                        &startup_wm_class_entry,
+                        &implements_entry,
+                        &actions_entry,
+                        &only_show_in_entry,
+                        &not_show_in_entry,
+                        &startup_notify_switch,
+                        &dbus_activatable_switch,
+                        &prefers_non_default_gpu_switch,
+                        &single_main_window_switch,
+                        &no_display_switch,
+                        &additional_keys_table,
+                        &categories_visible_entry,
+                        &category_tree,
+                    );
+                    updating_ui.set(false);
+                    *current_file.borrow_mut() = Some(file);
+                }
+            });
+        }
 
-                    let mut selected_categories = Vec::new();
-                    for (j, cb) in categories_checkboxes.iter().enumerate() {
-                        if cb.is_active() {
-                            selected_categories.push(COMMON_CATEGORIES[j]);
-                        }
+        // Form -> source editor
+        {
+            let current_file = current_file.clone();
+            let updating_ui = updating_ui.clone();
+            let source_editor = source_editor.clone();
+            let menu_preview = menu_preview.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+
+            view_stack.connect_visible_child_name_notify(move |stack| {
+                match stack.visible_child_name().as_deref() {
+                    Some("source") => {
+                        let file = current_file.borrow().clone();
+                        Self::refresh_source_editor(file.as_ref(), &source_editor, &updating_ui);
+                    }
+                    Some("preview") => {
+                        menu_preview.refresh(&desktop_id::normalize(&desktop_id_entry.text()))
                     }
+                    _ => {}
+                }
+            });
+        }
 
-                    let categories_text = selected_categories.join(";");
-                    categories_visible_entry.set_text(&categories_text);
+        {
+            let view_stack = view_stack.clone();
+            let menu_preview = menu_preview.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            // Runs after the handler above that loads the selected file
+            file_list.connect_row_selected(move |_, _| {
+                if view_stack.visible_child_name().as_deref() == Some("preview") {
+                    menu_preview.refresh(&desktop_id::normalize(&desktop_id_entry.text()));
+                }
+            });
+        }
 
-                    if let Some(ref mut file) = *current_file.borrow_mut() {
-                        if categories_text.is_empty() {
-                            file.desktop_entry.categories = None;
-                        } else {
-                            file.desktop_entry.categories = Some(categories_text);
+        {
+            let file_list = file_list.clone();
+            let broken_filter_button = broken_filter_button.clone();
+
+            search_entry.connect_changed(move |entry| {
+                let search_text = entry.text().to_lowercase();
+                Self::filter_file_list(&file_list, &search_text, broken_filter_button.is_active());
+            });
+        }
+
+        {
+            let file_list = file_list.clone();
+            let search_entry = search_entry.clone();
+            broken_filter_button.connect_toggled(move |button| {
+                let search_text = search_entry.text().to_lowercase();
+                Self::filter_file_list(&file_list, &search_text, button.is_active());
+            });
+        }
+
+        {
+            let window = window.clone();
+            let file_list = file_list.clone();
+            let search_entry = search_entry.clone();
+            let broken_filter_button = broken_filter_button.clone();
+            cleanup_button.connect_clicked(move |_| {
+                let file_list = file_list.clone();
+                let search_entry = search_entry.clone();
+                let broken_filter_button = broken_filter_button.clone();
+                cleanup_view::present(
+                    &window,
+                    Rc::new(move || {
+                        Self::load_desktop_files(&file_list);
+                        if broken_filter_button.is_active() || !search_entry.text().is_empty() {
+                            Self::filter_file_list(
+                                &file_list,
+                                &search_entry.text().to_lowercase(),
+                                broken_filter_button.is_active(),
+                            );
                         }
+                    }),
+                );
+            });
+        }
+
+        {
+            let categories_visible_entry = categories_visible_entry.clone();
+            category_tree.connect_toggled(move |name, active| {
+                let mut selected: Vec<String> = categories_visible_entry
+                    .text()
+                    .split(';')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty() && c != name)
+                    .collect();
+                if active {
+                    selected.push(name.to_string());
+                }
+                let text = if selected.is_empty() {
+                    String::new()
+                } else {
+                    format!("{};", selected.join(";"))
+                };
+                // The entry's changed handler updates the file
+                categories_visible_entry.set_text(&text);
+            });
+        }
+
+        // Keyboard commands, see `shortcuts::COMMANDS`
+        for (name, button) in [
+            ("new", new_button),
+            ("save", save_button),
+            ("autostart", autostart_button),
+            ("menu-structure", menu_button),
+            ("health-report", health_button),
+            ("overrides", overrides_button),
+            ("cleanup", cleanup_button),
+        ] {
+            let button = button.clone();
+            Self::add_action(window, name, move || button.emit_clicked());
+        }
+
+        {
+            let delete_button = delete_button.clone();
+            Self::add_action(window, "delete", move || {
+                // Only saved files can be deleted
+                if delete_button.is_visible() {
+                    delete_button.emit_clicked();
+                }
+            });
+        }
+
+        {
+            let current_file = current_file.clone();
+            let open_unsaved = open_unsaved.clone();
+            let taken_ids = taken_ids.clone();
+            let desktop_id_entry = desktop_id_entry.clone();
+            let feedback = feedback.clone();
+            Self::add_action(window, "duplicate", move || {
+                // A deep copy: actions, localized and unknown keys come along
+                let file_opt = current_file.borrow().clone();
+                let Some(file) = file_opt else {
+                    return;
+                };
+                let id = desktop_id::normalize_for(
+                    &desktop_id_entry.text(),
+                    &file.desktop_entry.entry_type,
+                );
+                open_unsaved(file);
+                let copy_id = desktop_id::for_copy(&id, &taken_ids.borrow());
+                // Set outside of `updating_ui`, so the ID counts as chosen and
+                // does not follow later changes of the name
+                desktop_id_entry.set_text(&copy_id);
+                feedback.success(
+                    "duplicate",
+                    format!("Duplicated as {copy_id}; save to create the copy"),
+                );
+            });
+        }
+
+        {
+            let search_entry = search_entry.clone();
+            Self::add_action(window, "search", move || {
+                search_entry.grab_focus();
+            });
+        }
+
+        {
+            let window_clone = window.clone();
+            let current_file = current_file.clone();
+            let feedback = feedback.clone();
+            Self::add_action(window, "test-launch", move || {
+                let file_opt = current_file.borrow().clone();
+                if let Some(file) = file_opt {
+                    Self::test_launch(&window_clone, &file, &feedback);
+                }
+            });
+        }
+
+        for (name, forward) in [("next-entry", true), ("previous-entry", false)] {
+            let file_list = file_list.clone();
+            Self::add_action(window, name, move || {
+                Self::select_adjacent(&file_list, forward);
+            });
+        }
+
+        {
+            let window_clone = window.clone();
+            let file_list = file_list.clone();
+            Self::add_action(window, "command-palette", move || {
+                let file_list_clone = file_list.clone();
+                command_palette::present(
+                    &window_clone,
+                    Self::list_entries(&file_list),
+                    Rc::new(move |path| Self::select_file(&file_list_clone, path)),
+                );
+            });
+        }
+
+        {
+            let window_clone = window.clone();
+            Self::add_action(window, "show-shortcuts", move || {
+                shortcuts::present_window(&window_clone);
+            });
+        }
+    }
+
+    /// Adds the window action `name`, running `activate`.
+    fn add_action(window: &adw::ApplicationWindow, name: &str, activate: impl Fn() + 'static) {
+        let action = gio::SimpleAction::new(name, None);
+        action.connect_activate(move |_, _| activate());
+        window.add_action(&action);
+    }
+
+    /// Starts the entry being edited, saved or not, as the desktop would.
+    fn test_launch(window: &adw::ApplicationWindow, file: &DesktopFile, feedback: &Feedback) {
+        let name = file.desktop_entry.name.clone();
+        let context = WidgetExt::display(window).app_launch_context();
+        let result = if file.desktop_entry.entry_type == "Link" {
+            let Some(url) = file.desktop_entry.url.clone().filter(|u| !u.is_empty()) else {
+                feedback.warning("launch", format!("{name} has no URL to open"));
+                return;
+            };
+            gio::AppInfo::launch_default_for_uri(&url, Some(&context))
+        } else {
+            let key_file = glib::KeyFile::new();
+            if let Err(e) = key_file.load_from_data(&file.to_string(), glib::KeyFileFlags::NONE) {
+                feedback.problem("launch", format!("Could not launch {name}: {e}"));
+                return;
+            }
+            let Some(app_info) = gio::DesktopAppInfo::from_keyfile(&key_file) else {
+                feedback.problem(
+                    "launch",
+                    format!(
+                        "Could not launch {name}: not an application entry, or its TryExec program is missing"
+                    ),
+                );
+                return;
+            };
+            app_info.launch(&[], Some(&context))
+        };
+        match result {
+            Ok(()) => feedback.success("launch", format!("Launched {name}")),
+            Err(e) => feedback.problem("launch", format!("Could not launch {name}: {e}")),
+        }
+    }
+
+    /// Names and paths of all entries in the file list.
+    fn list_entries(list: &gtk::ListBox) -> Vec<command_palette::PaletteEntry> {
+        let mut entries = Vec::new();
+        let mut child = list.first_child();
+        while let Some(widget) = child {
+            child = widget.next_sibling();
+            if !widget.has_css_class("file-item") {
+                continue;
+            }
+            let path = unsafe {
+                widget
+                    .data::<String>("file_path")
+                    .map(|ptr| (*ptr.as_ptr()).clone())
+            };
+            let name = widget
+                .first_child()
+                .and_then(|row_box| row_box.first_child())
+                .and_downcast::<gtk::Label>()
+                .map(|label| label.text().to_string());
+            if let (Some(name), Some(path)) = (name, path) {
+                entries.push((name, path));
+            }
+        }
+        entries
+    }
+
+    /// Selects the next or previous visible entry of the file list.
+    fn select_adjacent(list: &gtk::ListBox, forward: bool) {
+        let step = |widget: &gtk::Widget| {
+            if forward {
+                widget.next_sibling()
+            } else {
+                widget.prev_sibling()
+            }
+        };
+        let mut candidate = match list.selected_row() {
+            Some(row) => step(row.upcast_ref()),
+            None if forward => list.first_child(),
+            None => list.last_child(),
+        };
+        while let Some(widget) = candidate {
+            if widget.has_css_class("file-item") && widget.is_visible() {
+                if let Ok(row) = widget.downcast::<gtk::ListBoxRow>() {
+                    list.select_row(Some(&row));
+                    row.grab_focus();
+                }
+                return;
+            }
+            candidate = step(&widget);
+        }
+    }
+
+    fn import_program(path: &Path, open_unsaved: &Rc<dyn Fn(DesktopFile)>, feedback: &Feedback) {
+        match launcher_import::import_launcher(path) {
+            Ok(file) => open_unsaved(file),
+            Err(e) => feedback.error(
+                "import",
+                &format!("Could not create a launcher for {}", path.display()),
+                &e,
+            ),
+        }
+    }
+
+    /// Whether the user can write to the file at `path`.
+    fn is_writable(path: &str) -> bool {
+        std::fs::OpenOptions::new().append(true).open(path).is_ok()
+    }
+
+    fn load_templates(list: &gtk::ListBox, feedback: &Feedback) {
+        while let Some(child) = list.first_child() {
+            list.remove(&child);
+        }
+
+        for template in templates::all_templates() {
+            let row = gtk::ListBoxRow::new();
+            let row_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+            row_box.set_margin_start(12);
+            row_box.set_margin_end(12);
+            row_box.set_margin_top(6);
+            row_box.set_margin_bottom(6);
+
+            let text_box = gtk::Box::new(gtk::Orientation::Vertical, 2);
+            text_box.set_hexpand(true);
+
+            let name_label = gtk::Label::new(Some(&template.name));
+            name_label.add_css_class("heading");
+            name_label.set_halign(gtk::Align::Start);
+
+            let description_label = gtk::Label::new(Some(&template.description));
+            description_label.add_css_class("dim-label");
+            description_label.set_halign(gtk::Align::Start);
+
+            text_box.append(&name_label);
+            text_box.append(&description_label);
+            row_box.append(&text_box);
+
+            if let Some(path) = template.path.clone() {
+                let remove_button = gtk::Button::from_icon_name("user-trash-symbolic");
+                remove_button.set_tooltip_text(Some("Remove Template"));
+                remove_button.add_css_class("flat");
+                remove_button.set_valign(gtk::Align::Center);
+                let list = list.clone();
+                let feedback = feedback.clone();
+                remove_button.connect_clicked(move |_| {
+                    if let Err(e) = std::fs::remove_file(&path) {
+                        feedback.error("templates", "Could not remove the template", &e.into());
                     }
+                    Self::load_templates(&list, &feedback);
                 });
+                row_box.append(&remove_button);
+            }
+
+            row.set_child(Some(&row_box));
+            unsafe {
+                row.set_data("template", template.file);
             }
+            list.append(&row);
         }
     }
 
@@ -971,12 +2877,27 @@ impl DesktopFileManagerWindow {
         }
 
         let paths = get_desktop_file_paths();
-        let mut category_groups: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let mut category_groups: HashMap<String, Vec<FileListItem>> = HashMap::new();
 
         for path in paths {
             if let Some(file_name) = Path::new(&path).file_name() {
                 if let Some(name) = file_name.to_str() {
-                    let categories = match DesktopFile::from_file(&path) {
+                    // Files are grouped using the lenient parser so they still show up
+                    // under their categories; strict errors only add a badge.
+                    let (file, problem) = DesktopFile::from_file_checked(&path);
+                    let error = problem
+                        .as_ref()
+                        .or(file.as_ref().err())
+                        .map(|e| e.to_string());
+                    let problems = file
+                        .as_ref()
+                        .map(broken_entries::program_problems)
+                        .unwrap_or_default();
+                    let origin = file
+                        .as_ref()
+                        .ok()
+                        .and_then(|file| origin::detect(Path::new(&path), file));
+                    let categories = match file {
                         Ok(file) => file
                             .desktop_entry
                             .categories
@@ -989,19 +2910,37 @@ impl DesktopFileManagerWindow {
                                     .collect::<Vec<_>>()
                             })
                             .unwrap_or_else(|| vec!["Uncategorized".to_string()]),
-                        Err(_) => vec!["Uncategorized".to_string()],
+                        Err(_) => vec!["Unreadable".to_string()],
                     };
 
                     for category in categories {
                         category_groups
                             .entry(category.clone())
                             .or_insert_with(Vec::new)
-                            .push((name.to_string(), path.clone()));
+                            .push((
+                                name.to_string(),
+                                path.clone(),
+                                error.clone(),
+                                problems.clone(),
+                                origin.clone(),
+                            ));
                     }
                 }
             }
         }
 
+        // Directory entries name menus and have no categories of their own
+        for path in get_directory_file_paths() {
+            if let Some(name) = Path::new(&path).file_name().and_then(|n| n.to_str()) {
+                let (file, problem) = DesktopFile::from_file_checked(&path);
+                let error = problem.or(file.err()).map(|e| e.to_string());
+                category_groups
+                    .entry("Menu Directories".to_string())
+                    .or_insert_with(Vec::new)
+                    .push((name.to_string(), path.clone(), error, Vec::new(), None));
+            }
+        }
+
         let mut sorted_categories: Vec<_> = category_groups.into_iter().collect();
         sorted_categories.sort_by(|a, b| a.0.cmp(&b.0));
 
@@ -1038,7 +2977,7 @@ impl DesktopFileManagerWindow {
 
             list.append(&expander_row);
 
-            for (file_name, file_path) in files {
+            for (file_name, file_path, error, problems, origin) in files {
                 let file_row = gtk::ListBoxRow::new();
                 let file_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
                 file_box.set_margin_start(24); // Indent files under category
@@ -1051,6 +2990,31 @@ impl DesktopFileManagerWindow {
                 file_label.set_hexpand(true);
 
                 file_box.append(&file_label);
+
+                if let Some(origin) = origin {
+                    let badge = gtk::Label::new(Some(origin.label()));
+                    badge.add_css_class("caption");
+                    badge.add_css_class("dim-label");
+                    badge.set_tooltip_text(Some(&origin.details()));
+                    file_box.append(&badge);
+                }
+
+                if let Some(error) = error {
+                    let badge = gtk::Image::from_icon_name("dialog-error-symbolic");
+                    badge.add_css_class("error");
+                    badge.set_tooltip_text(Some(&error));
+                    file_box.append(&badge);
+                    file_row.add_css_class("broken");
+                }
+
+                if !problems.is_empty() {
+                    let badge = gtk::Image::from_icon_name("dialog-warning-symbolic");
+                    badge.add_css_class("warning");
+                    badge.set_tooltip_text(Some(&problems.join("\n")));
+                    file_box.append(&badge);
+                    file_row.add_css_class("missing-program");
+                }
+
                 file_row.set_child(Some(&file_box));
                 file_row.add_css_class("file-item");
 
@@ -1069,7 +3033,7 @@ impl DesktopFileManagerWindow {
     fn load_desktop_file(
         current_file: &Rc<RefCell<Option<DesktopFile>>>,
         file_path: &Rc<RefCell<Option<String>>>,
-        file_name: &str,
+        path: &str,
         name_entry: &gtk::Entry,
         exec_entry: &gtk::Entry,
         comment_entry: &gtk::Entry,
@@ -1081,44 +3045,61 @@ impl DesktopFileManagerWindow {
         type_combo: &gtk::ComboBoxText,
         url_entry: &gtk::Entry,
         mime_type_entry: &gtk::Entry,
+        generic_name_entry: &gtk::Entry,
+        version_entry: &gtk::Entry,
+        try_exec_entry: &gtk::Entry,
+        startup_wm_class_entry: &gtk::Entry,
+        implements_entry: &gtk::Entry,
+        actions_entry: &gtk::Entry,
+        only_show_in_entry: &gtk::Entry,
+        not_show_in_entry: &gtk::Entry,
+        startup_notify_switch: &gtk::Switch,
+        dbus_activatable_switch: &gtk::Switch,
+        prefers_non_default_gpu_switch: &gtk::Switch,
+        single_main_window_switch: &gtk::Switch,
+        no_display_switch: &gtk::Switch,
+        additional_keys_table: &KeyTable,
         categories_visible_entry: &gtk::Entry,
-        categories_checkboxes: &[gtk::CheckButton],
+        category_tree: &CategoryTree,
+        feedback: &Feedback,
     ) {
-        let paths = get_desktop_file_paths();
-        for path in paths {
-            if let Some(path_file_name) = Path::new(&path).file_name() {
-                if let Some(name) = path_file_name.to_str() {
-                    if name == file_name {
-                        match DesktopFile::from_file(&path) {
-                            Ok(file) => {
-                                *current_file.borrow_mut() = Some(file.clone());
-                                *file_path.borrow_mut() = Some(path);
-
-                                Self::update_ui_fields(
-                                    &file,
-                                    name_entry,
-                                    exec_entry,
-                                    comment_entry,
-                                    icon_entry,
-                                    path_entry,
-                                    keywords_entry,
-                                    terminal_switch,
-                                    hidden_switch,
-                                    type_combo,
-                                    url_entry,
-                                    mime_type_entry,
-                                    categories_visible_entry,
-                                    categories_checkboxes,
-                                );
-                            }
-                            Err(e) => {
-                                eprintln!("Error loading desktop file: {e}");
-                            }
-                        }
-                        break;
-                    }
-                }
+        match DesktopFile::from_file(path) {
+            Ok(file) => {
+                *current_file.borrow_mut() = Some(file.clone());
+                *file_path.borrow_mut() = Some(path.to_string());
+
+                Self::update_ui_fields(
+                    &file,
+                    name_entry,
+                    exec_entry,
+                    comment_entry,
+                    icon_entry,
+                    path_entry,
+                    keywords_entry,
+                    terminal_switch,
+                    hidden_switch,
+                    type_combo,
+                    url_entry,
+                    mime_type_entry,
+                    generic_name_entry,
+                    version_entry,
+                    try_exec_entry,
+                    startup_wm_class_entry,
+                    implements_entry,
+                    actions_entry,
+                    only_show_in_entry,
+                    not_show_in_entry,
+                    startup_notify_switch,
+                    dbus_activatable_switch,
+                    prefers_non_default_gpu_switch,
+                    single_main_window_switch,
+                    no_display_switch,
+                    additional_keys_table,
+                    categories_visible_entry,
+                    category_tree,
+                );
             }
+            Err(e) => feedback.error("open", &format!("Could not open {path}"), &e),
         }
     }
 
@@ -1137,8 +3118,22 @@ impl DesktopFileManagerWindow {
         type_combo: &gtk::ComboBoxText,
         url_entry: &gtk::Entry,
         mime_type_entry: &gtk::Entry,
+        generic_name_entry: &gtk::Entry,
+        version_entry: &gtk::Entry,
+        try_exec_entry: &gtk::Entry,
+        startup_wm_class_entry: &gtk::Entry,
+        implements_entry: &gtk::Entry,
+        actions_entry: &gtk::Entry,
+        only_show_in_entry: &gtk::Entry,
+        not_show_in_entry: &gtk::Entry,
+        startup_notify_switch: &gtk::Switch,
+        dbus_activatable_switch: &gtk::Switch,
+        prefers_non_default_gpu_switch: &gtk::Switch,
+        single_main_window_switch: &gtk::Switch,
+        no_display_switch: &gtk::Switch,
+        additional_keys_table: &KeyTable,
         categories_visible_entry: &gtk::Entry,
-        categories_checkboxes: &[gtk::CheckButton],
+        category_tree: &CategoryTree,
     ) {
         name_entry.set_text(&file.desktop_entry.name);
 
@@ -1204,28 +3199,126 @@ impl DesktopFileManagerWindow {
             mime_type_entry.set_text("");
         }
 
-        if let Some(ref categories) = file.desktop_entry.categories {
-            categories_visible_entry.set_text(categories);
+        generic_name_entry.set_text(file.desktop_entry.generic_name.as_deref().unwrap_or(""));
+        version_entry.set_text(file.desktop_entry.version.as_deref().unwrap_or(""));
+        try_exec_entry.set_text(file.desktop_entry.try_exec.as_deref().unwrap_or(""));
+        startup_wm_class_entry
+            .set_text(file.desktop_entry.startup_wm_class.as_deref().unwrap_or(""));
+        implements_entry.set_text(file.desktop_entry.implements.as_deref().unwrap_or(""));
+        actions_entry.set_text(file.desktop_entry.actions.as_deref().unwrap_or(""));
+        only_show_in_entry.set_text(file.desktop_entry.only_show_in.as_deref().unwrap_or(""));
+        not_show_in_entry.set_text(file.desktop_entry.not_show_in.as_deref().unwrap_or(""));
+
+        startup_notify_switch.set_active(file.desktop_entry.startup_notify.unwrap_or(false));
+        dbus_activatable_switch.set_active(file.desktop_entry.dbus_activatable.unwrap_or(false));
+        prefers_non_default_gpu_switch
+            .set_active(file.desktop_entry.prefers_non_default_gpu.unwrap_or(false));
+        single_main_window_switch
+            .set_active(file.desktop_entry.single_main_window.unwrap_or(false));
+        no_display_switch.set_active(file.desktop_entry.no_display.unwrap_or(false));
+
+        additional_keys_table.set_keys(&file.desktop_entry.extra_keys);
+
+        let categories = file.desktop_entry.categories.as_deref().unwrap_or_default();
+        categories_visible_entry.set_text(categories);
+        category_tree.set_categories(categories);
+    }
 
-            let category_list: Vec<&str> = categories
-                .split(';')
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect();
-            for (i, checkbox) in categories_checkboxes.iter().enumerate() {
-                let is_selected = category_list.contains(&COMMON_CATEGORIES[i]);
-                checkbox.set_active(is_selected);
-            }
+    /// Lists problems with the entered categories below the Categories row.
+    fn update_category_warnings(
+        label: &gtk::Label,
+        categories_entry: &gtk::Entry,
+        only_show_in_entry: &gtk::Entry,
+    ) {
+        let selected: Vec<String> = categories_entry
+            .text()
+            .split(';')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        let warnings = categories::check(&selected, !only_show_in_entry.text().trim().is_empty());
+        label.set_text(&warnings.join("\n"));
+        label.set_visible(!warnings.is_empty());
+    }
+
+    /// Shows whether the ID in `entry` is valid and free, and offers renaming
+    /// when it differs from the ID of the saved user file at `current_path`.
+    fn update_desktop_id_status(
+        entry: &gtk::Entry,
+        status: &gtk::Image,
+        rename_button: &gtk::Button,
+        current_path: Option<&str>,
+    ) {
+        let id = desktop_id::normalize(&entry.text());
+        let current_id = current_path.and_then(|p| desktop_id::from_path(Path::new(p)));
+        let user_apps = desktop_id::user_dir(&id);
+        let others: Vec<String> = desktop_id::find_existing(&id)
+            .into_iter()
+            .filter(|p| Some(p.as_str()) != current_path)
+            .collect();
+        let in_user_dir = |p: &str| {
+            user_apps
+                .as_deref()
+                .is_some_and(|dir| Path::new(p).parent() == Some(dir))
+        };
+
+        let (icon, tooltip) = if let Err(e) = desktop_id::validate(&id) {
+            ("dialog-error-symbolic", e)
+        } else if let Some(existing) = others.iter().find(|p| in_user_dir(p)) {
+            (
+                "dialog-error-symbolic",
+                format!("An entry with this ID already exists: {existing}"),
+            )
+        } else if let Some(existing) = others.first() {
+            (
+                "dialog-warning-symbolic",
+                format!("Overrides the system entry {existing}"),
+            )
         } else {
-            categories_visible_entry.set_text("");
+            ("emblem-ok-symbolic", "This ID is available".to_string())
+        };
+        status.set_from_icon_name(Some(icon));
+        status.set_tooltip_text(Some(&tooltip));
+
+        rename_button.set_visible(
+            current_path.is_some_and(in_user_dir)
+                && current_id.as_deref() != Some(id.as_str())
+                && icon != "dialog-error-symbolic",
+        );
+    }
 
-            for checkbox in categories_checkboxes {
-                checkbox.set_active(false);
+    fn refresh_source_editor(
+        file: Option<&DesktopFile>,
+        source_editor: &SourceEditor,
+        updating_ui: &Rc<Cell<bool>>,
+    ) {
+        updating_ui.set(true);
+        source_editor.set_text(&file.map(|f| f.to_string()).unwrap_or_default());
+        updating_ui.set(false);
+    }
+
+    /// Selects the row of the file at `path`, which loads it into the form.
+    fn select_file(list: &gtk::ListBox, path: &str) {
+        let mut child = list.first_child();
+        while let Some(widget) = child {
+            child = widget.next_sibling();
+            let Ok(row) = widget.downcast::<gtk::ListBoxRow>() else {
+                continue;
+            };
+            let row_path = unsafe {
+                row.data::<String>("file_path")
+                    .map(|ptr| (*ptr.as_ptr()).clone())
+            };
+            if row_path.as_deref() == Some(path) {
+                list.select_row(Some(&row));
+                return;
             }
         }
     }
 
-    fn filter_file_list(list: &gtk::ListBox, search_text: &str) {
+    /// Shows the files whose name contains `search_text` and, with
+    /// `broken_only`, whose program is missing.
+    fn filter_file_list(list: &gtk::ListBox, search_text: &str, broken_only: bool) {
         let mut row = list.first_child();
         while let Some(child) = row {
             let next_sibling = child.next_sibling();
@@ -1237,17 +3330,27 @@ impl DesktopFileManagerWindow {
                             if let Some(label) = box_widget.first_child() {
                                 if let Ok(label_widget) = label.downcast::<gtk::Label>() {
                                     let file_name = label_widget.text().to_lowercase();
-                                    let should_show =
-                                        search_text.is_empty() || file_name.contains(search_text);
+                                    let should_show = (search_text.is_empty()
+                                        || file_name.contains(search_text))
+                                        && (!broken_only
+                                            || list_row.has_css_class("missing-program"));
                                     list_row.set_visible(should_show);
                                 }
                             }
                         }
                     }
                 } else if list_row.has_css_class("category-header") {
-                    // For category headers, always show them for now
-                    // TODO: Implement proper category filtering
-                    list_row.set_visible(true);
+                    // Without the broken filter, category headers are always shown
+                    let mut visible = !broken_only;
+                    let mut sibling = list_row.next_sibling();
+                    while let Some(file_row) = sibling {
+                        if visible || file_row.has_css_class("category-header") {
+                            break;
+                        }
+                        visible = file_row.has_css_class("missing-program");
+                        sibling = file_row.next_sibling();
+                    }
+                    list_row.set_visible(visible);
                 }
             }
             row = next_sibling;
@@ -0,0 +1,279 @@
+use gtk4 as gtk;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::exec;
+use crate::feedback::Feedback;
+use crate::mime::{self, Associations, SCHEME_HANDLER_PREFIX};
+use crate::mime_associations::MimeAssociations;
+
+/// Manages the `x-scheme-handler/<scheme>` types of an entry: the schemes it
+/// handles, which application is the default for each, and a resolver for
+/// sample URLs.
+#[derive(Clone)]
+pub struct UrlHandlers {
+    pub widget: gtk::Box,
+    list: gtk::ListBox,
+    exec_warning: gtk::Label,
+    scheme_entry: gtk::Entry,
+    test_entry: gtk::Entry,
+    test_result: gtk::Label,
+    mime_type_entry: gtk::Entry,
+    exec_entry: gtk::Entry,
+    associations: Rc<RefCell<Associations>>,
+    desktop_id: Rc<RefCell<String>>,
+    feedback: Feedback,
+}
+
+impl UrlHandlers {
+    pub fn new(
+        mime_type_entry: &gtk::Entry,
+        exec_entry: &gtk::Entry,
+        associations: Rc<RefCell<Associations>>,
+        feedback: &Feedback,
+    ) -> Self {
+        let exec_warning = gtk::Label::new(Some(
+            "Exec does not contain %u or %U, so the URL will not be passed to the program",
+        ));
+        exec_warning.add_css_class("warning");
+        exec_warning.set_wrap(true);
+        exec_warning.set_halign(gtk::Align::Start);
+        exec_warning.set_visible(false);
+
+        let list = gtk::ListBox::new();
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.add_css_class("boxed-list");
+
+        let scheme_entry = gtk::Entry::new();
+        scheme_entry.set_placeholder_text(Some("Scheme (e.g. myapp)"));
+        scheme_entry.set_hexpand(true);
+        let add_button = gtk::Button::with_label("Add Scheme");
+
+        let add_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        add_box.append(&scheme_entry);
+        add_box.append(&add_button);
+
+        let test_entry = gtk::Entry::new();
+        test_entry.set_placeholder_text(Some("Sample URL (e.g. myapp://open)"));
+        test_entry.set_hexpand(true);
+        let test_button = gtk::Button::with_label("Test");
+
+        let test_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        test_box.append(&test_entry);
+        test_box.append(&test_button);
+
+        let test_result = gtk::Label::new(None);
+        test_result.set_halign(gtk::Align::Start);
+        test_result.set_wrap(true);
+        test_result.set_selectable(true);
+
+        let widget = gtk::Box::new(gtk::Orientation::Vertical, 6);
+        widget.append(&exec_warning);
+        widget.append(&list);
+        widget.append(&add_box);
+        widget.append(&test_box);
+        widget.append(&test_result);
+
+        let handlers = Self {
+            widget,
+            list,
+            exec_warning,
+            scheme_entry,
+            test_entry,
+            test_result,
+            mime_type_entry: mime_type_entry.clone(),
+            exec_entry: exec_entry.clone(),
+            associations,
+            desktop_id: Rc::new(RefCell::new(String::new())),
+            feedback: feedback.clone(),
+        };
+
+        {
+            let handlers = handlers.clone();
+            add_button.connect_clicked(move |_| handlers.add_scheme());
+        }
+        {
+            let handlers = handlers.clone();
+            handlers
+                .scheme_entry
+                .clone()
+                .connect_activate(move |_| handlers.add_scheme());
+        }
+        {
+            let handlers = handlers.clone();
+            test_button.connect_clicked(move |_| handlers.test_url());
+        }
+        {
+            let handlers = handlers.clone();
+            handlers
+                .test_entry
+                .clone()
+                .connect_activate(move |_| handlers.test_url());
+        }
+
+        handlers.refresh();
+        handlers
+    }
+
+    /// Refreshes the view from the MIME Types and Exec fields for the entry
+    /// with this desktop file ID.
+    pub fn update(&self, desktop_id: &str) {
+        *self.desktop_id.borrow_mut() = desktop_id.to_string();
+        self.refresh();
+    }
+
+    fn schemes(&self) -> Vec<String> {
+        mime::split_list(&self.mime_type_entry.text())
+            .iter()
+            .filter_map(|m| m.strip_prefix(SCHEME_HANDLER_PREFIX))
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    /// Rebuilds the scheme list, e.g. after the associations were reloaded.
+    pub fn refresh(&self) {
+        while let Some(child) = self.list.first_child() {
+            self.list.remove(&child);
+        }
+
+        let schemes = self.schemes();
+        let codes = exec::field_codes(&self.exec_entry.text());
+        self.exec_warning
+            .set_visible(!schemes.is_empty() && !codes.iter().any(|c| matches!(c, 'u' | 'U')));
+
+        if schemes.is_empty() {
+            let row = adw::ActionRow::new();
+            row.set_title("No URL schemes");
+            row.set_subtitle("Add a scheme to open its links with this entry");
+            self.list.append(&row);
+            return;
+        }
+
+        let desktop_id = self.desktop_id.borrow().clone();
+        for scheme in schemes {
+            let row = self.create_row(&scheme, &desktop_id);
+            self.list.append(&row);
+        }
+    }
+
+    fn create_row(&self, scheme: &str, desktop_id: &str) -> adw::ActionRow {
+        let mime_type = format!("{SCHEME_HANDLER_PREFIX}{scheme}");
+        let associations = self.associations.borrow();
+        let default = associations.default_handler(&mime_type);
+
+        let row = adw::ActionRow::new();
+        row.set_title(&glib::markup_escape_text(&format!("{scheme}:")));
+        row.set_subtitle(&glib::markup_escape_text(&format!(
+            "Default: {}",
+            default.as_deref().unwrap_or("None")
+        )));
+
+        if let Err(e) = mime::validate_scheme(scheme) {
+            let warning = gtk::Image::from_icon_name("dialog-warning-symbolic");
+            warning.set_tooltip_text(Some(&e));
+            row.add_prefix(&warning);
+        }
+
+        let is_default = default.as_deref() == Some(desktop_id);
+        let default_button = gtk::Button::with_label(if is_default {
+            "Default"
+        } else {
+            "Set as Default"
+        });
+        default_button.set_valign(gtk::Align::Center);
+        if is_default {
+            default_button.set_sensitive(false);
+        } else if !associations.is_installed(desktop_id) {
+            default_button.set_sensitive(false);
+            default_button.set_tooltip_text(Some("Save the entry first"));
+        }
+        row.add_suffix(&default_button);
+
+        let remove_button = gtk::Button::from_icon_name("list-remove-symbolic");
+        remove_button.set_tooltip_text(Some("Remove Scheme"));
+        remove_button.add_css_class("flat");
+        remove_button.set_valign(gtk::Align::Center);
+        row.add_suffix(&remove_button);
+
+        {
+            let handlers = self.clone();
+            let mime_type = mime_type.clone();
+            let desktop_id = desktop_id.to_string();
+            default_button.connect_clicked(move |_| {
+                MimeAssociations::set_default(&handlers.feedback, &mime_type, &desktop_id);
+                *handlers.associations.borrow_mut() = Associations::load();
+                handlers.refresh();
+            });
+        }
+
+        {
+            let handlers = self.clone();
+            remove_button.connect_clicked(move |_| {
+                let remaining: Vec<String> = mime::split_list(&handlers.mime_type_entry.text())
+                    .into_iter()
+                    .filter(|m| *m != mime_type)
+                    .collect();
+                let text = if remaining.is_empty() {
+                    String::new()
+                } else {
+                    format!("{};", remaining.join(";"))
+                };
+                handlers.mime_type_entry.set_text(&text);
+            });
+        }
+
+        row
+    }
+
+    /// Appends `x-scheme-handler/<scheme>` to the MIME Types field.
+    fn add_scheme(&self) {
+        let scheme = self
+            .scheme_entry
+            .text()
+            .trim()
+            .trim_end_matches("://")
+            .trim_end_matches(':')
+            .to_lowercase();
+        if let Err(e) = mime::validate_scheme(&scheme) {
+            self.feedback
+                .warning("url-handlers", format!("Invalid URL scheme {scheme}: {e}"));
+            return;
+        }
+        if self.schemes().contains(&scheme) {
+            self.scheme_entry.set_text("");
+            return;
+        }
+
+        let mut text = self.mime_type_entry.text().trim().to_string();
+        if !text.is_empty() && !text.ends_with(';') {
+            text.push(';');
+        }
+        text.push_str(&format!("{SCHEME_HANDLER_PREFIX}{scheme};"));
+        self.mime_type_entry.set_text(&text);
+        self.scheme_entry.set_text("");
+    }
+
+    /// Shows which entry currently opens the sample URL.
+    fn test_url(&self) {
+        let url = self.test_entry.text();
+        let Some(scheme) = mime::url_scheme(&url) else {
+            self.test_result
+                .set_text(&format!("'{url}' does not start with a valid URL scheme"));
+            return;
+        };
+
+        *self.associations.borrow_mut() = Associations::load();
+        let mime_type = format!("{SCHEME_HANDLER_PREFIX}{scheme}");
+        let result = match self.associations.borrow().default_handler(&mime_type) {
+            Some(id) if id == *self.desktop_id.borrow() => {
+                format!("{scheme}: links open with this entry ({id})")
+            }
+            Some(id) => format!("{scheme}: links open with {id}"),
+            None => format!("No application handles {scheme}: links"),
+        };
+        self.test_result.set_text(&result);
+        self.refresh();
+    }
+}
@@ -0,0 +1,88 @@
+use std::env;
+use std::path::PathBuf;
+
+fn home() -> Option<PathBuf> {
+    env::var("HOME").ok().map(PathBuf::from)
+}
+
+fn env_dir(var: &str) -> Option<PathBuf> {
+    env::var(var)
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+}
+
+/// `$XDG_CONFIG_HOME`, defaulting to `~/.config`.
+pub fn config_home() -> Option<PathBuf> {
+    env_dir("XDG_CONFIG_HOME").or_else(|| home().map(|h| h.join(".config")))
+}
+
+/// `$XDG_DATA_HOME`, defaulting to `~/.local/share`.
+pub fn data_home() -> Option<PathBuf> {
+    env_dir("XDG_DATA_HOME").or_else(|| home().map(|h| h.join(".local/share")))
+}
+
+/// `$XDG_STATE_HOME`, defaulting to `~/.local/state`.
+pub fn state_home() -> Option<PathBuf> {
+    env_dir("XDG_STATE_HOME").or_else(|| home().map(|h| h.join(".local/state")))
+}
+
+/// `$XDG_CONFIG_DIRS`, defaulting to `/etc/xdg`, most important first.
+pub fn config_dirs() -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = env::var("XDG_CONFIG_DIRS")
+        .unwrap_or_default()
+        .split(':')
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .collect();
+    if dirs.is_empty() {
+        vec![PathBuf::from("/etc/xdg")]
+    } else {
+        dirs
+    }
+}
+
+/// `$XDG_DATA_DIRS`, defaulting to `/usr/local/share:/usr/share`, most
+/// important first.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let dirs: Vec<PathBuf> = env::var("XDG_DATA_DIRS")
+        .unwrap_or_default()
+        .split(':')
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .collect();
+    if dirs.is_empty() {
+        vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ]
+    } else {
+        dirs
+    }
+}
+
+/// Desktop names from `$XDG_CURRENT_DESKTOP`, e.g. `["ubuntu", "GNOME"]`.
+pub fn current_desktops() -> Vec<String> {
+    env::var("XDG_CURRENT_DESKTOP")
+        .unwrap_or_default()
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(|d| d.to_string())
+        .collect()
+}
+
+/// Directory for this application's own configuration files.
+pub fn app_config_dir() -> Option<PathBuf> {
+    config_home().map(|dir| dir.join("menu-entry-manager"))
+}
+
+/// Directory for data this application keeps about the user's files.
+pub fn app_data_dir() -> Option<PathBuf> {
+    data_home().map(|dir| dir.join("menu-entry-manager"))
+}
+
+/// Directory for this application's logs and other state.
+pub fn app_state_dir() -> Option<PathBuf> {
+    state_home().map(|dir| dir.join("menu-entry-manager"))
+}